@@ -0,0 +1,59 @@
+//! Address family selection for outgoing connections.
+
+use std::net::SocketAddr;
+
+/// Restricts which IP address family is used when connecting to a host.
+///
+/// # Examples
+///
+/// ```
+/// use clienter::{AddressFamily, HttpClient};
+///
+/// let mut client = HttpClient::new();
+/// client.address_family = AddressFamily::V4;
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum AddressFamily {
+    /// Use any resolved address, in the order returned by the resolver
+    #[default]
+    Any,
+    /// Only connect to IPv4 addresses
+    V4,
+    /// Only connect to IPv6 addresses
+    V6,
+}
+
+impl AddressFamily {
+    /// Determines if an address belongs to this family.
+    ///
+    /// # Arguments
+    /// * `addr` - The resolved socket address to check
+    ///
+    /// # Returns
+    /// `true` if the address may be connected to under this preference
+    pub fn matches(&self, addr: &SocketAddr) -> bool {
+        match self {
+            AddressFamily::Any => true,
+            AddressFamily::V4 => addr.is_ipv4(),
+            AddressFamily::V6 => addr.is_ipv6(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_address_family_matches() {
+        let v4: SocketAddr = "127.0.0.1:80".parse().unwrap();
+        let v6: SocketAddr = "[::1]:80".parse().unwrap();
+
+        assert!(AddressFamily::Any.matches(&v4));
+        assert!(AddressFamily::Any.matches(&v6));
+        assert!(AddressFamily::V4.matches(&v4));
+        assert!(!AddressFamily::V4.matches(&v6));
+        assert!(!AddressFamily::V6.matches(&v4));
+        assert!(AddressFamily::V6.matches(&v6));
+    }
+}
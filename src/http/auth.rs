@@ -0,0 +1,243 @@
+//! Parsing of `WWW-Authenticate` challenges, as described in RFC 7235 section 4.1.
+//!
+//! A single header value may hold several challenges, each a scheme followed by
+//! either comma-separated `name=value` parameters or a single token68 value.
+//!
+//! # Example
+//! ```
+//! use clienter::AuthChallenge;
+//!
+//! let challenges = AuthChallenge::parse_list(r#"Basic realm="api", Digest realm="api", qop="auth,auth-int""#);
+//! assert_eq!(challenges.len(), 2);
+//! assert_eq!(challenges[0].scheme, "Basic");
+//! assert_eq!(challenges[1].params["qop"], "auth,auth-int");
+//! ```
+
+use std::collections::HashMap;
+
+/// An authentication challenge sent by a server in a `WWW-Authenticate` header.
+#[derive(Debug, PartialEq, Clone)]
+pub struct AuthChallenge {
+    /// The authentication scheme, such as "Basic" or "Digest", as sent by the server
+    pub scheme: String,
+    /// The challenge parameters, keyed by lowercase name, with quotes and escapes removed
+    pub params: HashMap<String, String>,
+    /// The single token68 value sent instead of parameters, as some schemes such as
+    /// "Negotiate" do
+    pub token68: Option<String>,
+}
+
+/// A cursor over the bytes of a header value.
+struct Parser<'a> {
+    input: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t')) {
+            self.position += 1;
+        }
+    }
+
+    /// Skips whitespace and empty list elements between items.
+    fn skip_separators(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b',')) {
+            self.position += 1;
+        }
+    }
+
+    /// Reads a run of bytes matching `accept`, returning it as a string.
+    fn take_while(&mut self, accept: fn(u8) -> bool) -> String {
+        let start = self.position;
+        while self.peek().is_some_and(accept) {
+            self.position += 1;
+        }
+        String::from_utf8_lossy(&self.input[start..self.position]).to_string()
+    }
+
+    /// Reads a quoted string, without its quotes and with escapes resolved.
+    fn quoted_string(&mut self) -> String {
+        // Skip the opening quote
+        self.position += 1;
+        let mut value = Vec::new();
+        while let Some(byte) = self.peek() {
+            self.position += 1;
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    if let Some(escaped) = self.peek() {
+                        value.push(escaped);
+                        self.position += 1;
+                    }
+                }
+                _ => value.push(byte),
+            }
+        }
+        String::from_utf8_lossy(&value).to_string()
+    }
+
+    /// Tries to read a token68 value, which must be the last item of its challenge.
+    fn token68(&mut self) -> Option<String> {
+        let start = self.position;
+        let mut value = self.take_while(is_token68_char);
+        value.push_str(&self.take_while(|byte| byte == b'='));
+
+        self.skip_whitespace();
+        if !value.is_empty() && matches!(self.peek(), None | Some(b',')) {
+            return Some(value);
+        }
+        self.position = start;
+        None
+    }
+
+    /// Determines whether the next list element is a parameter rather than a new scheme.
+    fn at_param(&mut self) -> bool {
+        let start = self.position;
+        let name = self.take_while(is_token_char);
+        self.skip_whitespace();
+        let is_param = !name.is_empty() && self.peek() == Some(b'=');
+        self.position = start;
+        is_param
+    }
+
+    /// Reads a `name=value` parameter.
+    fn param(&mut self) -> (String, String) {
+        let name = self.take_while(is_token_char).to_ascii_lowercase();
+        self.skip_whitespace();
+        // Skip the '='
+        self.position += 1;
+        self.skip_whitespace();
+        let value = match self.peek() {
+            Some(b'"') => self.quoted_string(),
+            _ => self.take_while(is_token_char),
+        };
+        (name, value)
+    }
+}
+
+/// Determines whether a byte may appear in a token (RFC 7230 section 3.2.6).
+fn is_token_char(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte)
+}
+
+/// Determines whether a byte may appear in a token68 value, before its `=` padding.
+fn is_token68_char(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"-._~+/".contains(&byte)
+}
+
+impl AuthChallenge {
+    /// Parses every challenge in a `WWW-Authenticate` header value.
+    ///
+    /// Quoted parameter values may contain commas and escaped quotes. Malformed
+    /// input does not cause an error; whatever could be recognised is returned.
+    ///
+    /// # Arguments
+    /// * `value` - The header value
+    ///
+    /// # Returns
+    /// The challenges, in the order they appear
+    pub fn parse_list(value: &str) -> Vec<AuthChallenge> {
+        let mut parser = Parser {
+            input: value.as_bytes(),
+            position: 0,
+        };
+        let mut challenges = Vec::new();
+
+        loop {
+            parser.skip_separators();
+            let scheme = parser.take_while(is_token_char);
+            if scheme.is_empty() {
+                // Either the end of the value, or a byte that cannot start a challenge
+                if parser.peek().is_none() {
+                    break;
+                }
+                parser.position += 1;
+                continue;
+            }
+
+            let mut challenge = AuthChallenge {
+                scheme,
+                params: HashMap::new(),
+                token68: None,
+            };
+            parser.skip_whitespace();
+            challenge.token68 = parser.token68();
+
+            if challenge.token68.is_none() {
+                loop {
+                    parser.skip_separators();
+                    if !parser.at_param() {
+                        break;
+                    }
+                    let (name, value) = parser.param();
+                    challenge.params.insert(name, value);
+                }
+            }
+            challenges.push(challenge);
+        }
+
+        challenges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_basic() {
+        let challenges = AuthChallenge::parse_list(r#"Basic realm="Access to the site""#);
+        assert_eq!(challenges.len(), 1);
+        assert_eq!(challenges[0].scheme, "Basic");
+        assert_eq!(challenges[0].params["realm"], "Access to the site");
+        assert_eq!(challenges[0].token68, None);
+    }
+
+    #[test]
+    fn test_parse_digest_with_quoted_commas() {
+        let challenges = AuthChallenge::parse_list(
+            r#"Digest realm="a, b", QOP="auth,auth-int", nonce=abc123, algorithm=MD5, stale=false"#,
+        );
+        assert_eq!(challenges.len(), 1);
+        let params = &challenges[0].params;
+        assert_eq!(params["realm"], "a, b");
+        assert_eq!(params["qop"], "auth,auth-int");
+        assert_eq!(params["nonce"], "abc123");
+        assert_eq!(params["algorithm"], "MD5");
+        assert_eq!(params["stale"], "false");
+    }
+
+    #[test]
+    fn test_parse_multiple_challenges_and_escapes() {
+        let challenges = AuthChallenge::parse_list(
+            r#"Newauth realm="apps", type=1, title="Login to \"apps\"", Basic realm="simple""#,
+        );
+        assert_eq!(challenges.len(), 2);
+        assert_eq!(challenges[0].scheme, "Newauth");
+        assert_eq!(challenges[0].params["title"], r#"Login to "apps""#);
+        assert_eq!(challenges[0].params["type"], "1");
+        assert_eq!(challenges[1].scheme, "Basic");
+        assert_eq!(challenges[1].params["realm"], "simple");
+    }
+
+    #[test]
+    fn test_parse_token68_and_bare_schemes() {
+        let challenges = AuthChallenge::parse_list("Negotiate YII=, NTLM, Bearer");
+        let schemes: Vec<&str> = challenges.iter().map(|c| c.scheme.as_str()).collect();
+        assert_eq!(schemes, ["Negotiate", "NTLM", "Bearer"]);
+        assert_eq!(challenges[0].token68.as_deref(), Some("YII="));
+        assert!(challenges[1].params.is_empty());
+        assert_eq!(challenges[1].token68, None);
+    }
+
+    #[test]
+    fn test_parse_empty() {
+        assert!(AuthChallenge::parse_list("").is_empty());
+        assert!(AuthChallenge::parse_list(" , ").is_empty());
+    }
+}
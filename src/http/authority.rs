@@ -0,0 +1,52 @@
+//! The host and port a request is sent to.
+//!
+//! `Uri::authority` resolves the port to the protocol default, so code that needs
+//! the pair doesn't have to repeat that, or the bracketing of IPv6 addresses.
+
+/// The host and port of a URI, with the port resolved.
+///
+/// # Example
+/// ```
+/// use clienter::{Authority, Uri};
+///
+/// let uri: Uri = "https://example.com/path".parse().unwrap();
+/// assert_eq!(
+///     uri.authority(),
+///     Authority { host: "example.com".to_string(), port: 443 }
+/// );
+/// assert_eq!(uri.authority().to_string(), "example.com:443");
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Authority {
+    /// The host name or IP address, without the brackets around an IPv6 address
+    pub host: String,
+    /// The port, which is the protocol default when the URI doesn't give one
+    pub port: u16,
+}
+
+impl Authority {
+    /// Returns the host as written in a URI, with an IPv6 address in brackets.
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::Authority;
+    ///
+    /// let authority = Authority { host: "::1".to_string(), port: 8080 };
+    /// assert_eq!(authority.host_literal(), "[::1]");
+    /// ```
+    pub fn host_literal(&self) -> String {
+        if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        }
+    }
+}
+
+impl std::fmt::Display for Authority {
+    /// Formats the authority as `host:port`, bracketing an IPv6 address so that the
+    /// result can be passed to `ToSocketAddrs`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.host_literal(), self.port)
+    }
+}
@@ -0,0 +1,64 @@
+//! Request body representations.
+//!
+//! A body is either a buffer of bytes whose length is known up front, or a reader
+//! whose output is streamed to the server using chunked transfer encoding.
+
+use std::{
+    io::Read,
+    sync::{Arc, Mutex},
+};
+
+/// The body of an HTTP request.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Body {
+    /// A body whose bytes are known up front, sent with a `Content-Length` header
+    Bytes(Vec<u8>),
+    /// A body produced by a reader, sent with `Transfer-Encoding: chunked`
+    Stream(BodyReader),
+}
+
+/// A shared handle to a reader that produces a streaming request body.
+///
+/// Clones of the handle share the same reader, which can only be consumed once:
+/// after a request carrying it has been sent, later sends fail with
+/// `HttpError::BodyConsumed`.
+#[derive(Clone)]
+pub struct BodyReader {
+    reader: Arc<Mutex<Option<Box<dyn Read + Send>>>>,
+}
+
+impl BodyReader {
+    /// Wraps a reader so it can be sent as a request body.
+    ///
+    /// # Arguments
+    /// * `reader` - The reader producing the body
+    pub fn new<R>(reader: R) -> Self
+    where
+        R: Read + Send + 'static,
+    {
+        BodyReader {
+            reader: Arc::new(Mutex::new(Some(Box::new(reader)))),
+        }
+    }
+
+    /// Takes the reader out of the handle, leaving it consumed.
+    ///
+    /// # Returns
+    /// `None` if the reader has already been taken
+    pub fn take(&self) -> Option<Box<dyn Read + Send>> {
+        self.reader.lock().ok()?.take()
+    }
+}
+
+impl std::fmt::Debug for BodyReader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("BodyReader")
+    }
+}
+
+/// Two handles are equal when they share the same reader.
+impl PartialEq for BodyReader {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.reader, &other.reader)
+    }
+}
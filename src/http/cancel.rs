@@ -0,0 +1,95 @@
+//! Cancellation of requests from another thread.
+//!
+//! A `CancelHandle` attached to a request with `HttpRequest::cancel_on` can be
+//! triggered from any thread. The request then fails with `HttpError::Cancelled`, or
+//! a body being read with `ResponseError::Cancelled`, shortly afterwards.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// A flag that aborts the requests it is attached to once set.
+///
+/// Clones share the same flag, so one clone can be attached to a request while
+/// another is kept to cancel it.
+///
+/// # Example
+/// ```no_run
+/// use std::thread;
+/// use std::time::Duration;
+/// use clienter::{CancelHandle, HttpClient, HttpMethod, ResponseError};
+///
+/// let client = HttpClient::new();
+/// let cancel = CancelHandle::new();
+/// let request = client
+///     .request(HttpMethod::GET, "http://example.com/large-file")
+///     .cancel_on(cancel.clone());
+///
+/// // Give up on the download after a second, from another thread
+/// let canceller = cancel.clone();
+/// thread::spawn(move || {
+///     thread::sleep(Duration::from_secs(1));
+///     canceller.cancel();
+/// });
+///
+/// let mut response = client.send(&request).unwrap();
+/// match response.body() {
+///     Ok(body) => println!("{} bytes", body.len()),
+///     Err(ResponseError::Cancelled) => println!("cancelled"),
+///     Err(err) => println!("failed: {}", err),
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancelHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelHandle {
+    /// Creates a handle that has not been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels every request the handle is attached to, including ones in flight.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns whether `cancel` has been called on this handle or one of its clones.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Fails with an error recognised by `is_cancelled_error` if the handle was cancelled.
+    pub(crate) fn check(&self) -> std::io::Result<()> {
+        if self.is_cancelled() {
+            return Err(std::io::Error::other(Cancelled));
+        }
+        Ok(())
+    }
+}
+
+/// Two handles are equal when they share the same flag.
+impl PartialEq for CancelHandle {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.cancelled, &other.cancelled)
+    }
+}
+
+/// The error carried by I/O errors caused by a cancelled request.
+#[derive(Debug)]
+struct Cancelled;
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "request was cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Determines whether an I/O error was caused by a cancelled request.
+pub(crate) fn is_cancelled_error(err: &std::io::Error) -> bool {
+    err.get_ref().is_some_and(|inner| inner.is::<Cancelled>())
+}
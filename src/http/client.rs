@@ -1,7 +1,9 @@
 //! HTTP client implementation for making HTTP requests.
 //!
 //! This module provides a simple HTTP client that can be used to make HTTP requests
-//! over TCP connections. It supports custom headers and timeout configuration.
+//! over TCP connections. It supports custom headers, connection and read timeouts
+//! (30 seconds each by default) and an overall deadline for the whole request.
+//! Requests can also be performed on an already-connected stream via `send_on`.
 //!
 //! # Example
 //! ```
@@ -11,20 +13,177 @@
 //! let request = client.request(HttpMethod::GET, "http://example.com");
 //! let response = client.send(&request).expect("Failed to send request");
 //! ```
+//!
+//! # Sharing a client
+//!
+//! Every method that sends a request takes `&self`, and the client is `Send + Sync`,
+//! so a single client can be shared between threads behind an `Arc`. Any state the
+//! client keeps between requests (such as caches) is held behind interior mutability
+//! (a `Mutex`) rather than requiring `&mut self`, so this sharing keeps working as
+//! stateful features are added. Configuration fields are plain public fields and are
+//! expected to be set before the client is shared.
+//!
+//! The client is also `Clone`. Any state kept between requests is held behind an
+//! `Arc`, so a clone shares it with the original.
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use clienter::{HttpClient, HttpMethod};
+//!
+//! let client = Arc::new(HttpClient::new());
+//! let handles: Vec<_> = (0..4)
+//!     .map(|_| {
+//!         let client = client.clone();
+//!         std::thread::spawn(move || {
+//!             let request = client.request(HttpMethod::GET, "http://example.com");
+//!             client.send(&request).map(|response| response.status_code())
+//!         })
+//!     })
+//!     .collect();
+//! ```
+
+use std::io::{Cursor, ErrorKind, Read, Write};
+use std::net::SocketAddr;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::internal::{write_chunked, DeadlineHandle, DeadlineStream};
+
+use super::cancel::is_cancelled_error;
+use super::dns::DnsCache;
+use super::encoding::accept_encoding_value;
+use super::pool::ConnectionCounters;
+use super::response::{ResponseError, MAX_HEADER_LINES};
+use super::{
+    AddressFamily, Body, ClientEvent, Connector, Encoding, EventHook, HttpHeaders, HttpMethod,
+    HttpRequest, HttpResponse, PoolStats, Protocol, ReadWrite, StatusCode, TcpConnector, Uri,
+};
 
-use std::io::Write;
-use std::net::{TcpStream, ToSocketAddrs};
+/// The largest body sent in the same write as the request head.
+const COALESCED_BODY_LIMIT: usize = 16 * 1024;
 
-use super::{HttpHeaders, HttpMethod, HttpRequest, HttpResponse, Uri};
+/// How long `HttpClient::new` lets connecting, or a single read or write, take.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How long `HttpClient::new` waits for `100 Continue` before sending a body anyway,
+/// as curl does.
+const DEFAULT_CONTINUE_TIMEOUT: Duration = Duration::from_secs(1);
 
 /// A configurable HTTP client for making HTTP requests.
 ///
-/// The client supports setting custom headers and connection timeout.
+/// The client supports setting custom headers, a connection timeout, a read timeout
+/// and a total timeout.
+#[derive(Clone)]
 pub struct HttpClient {
-    /// Optional timeout duration for connections
+    /// Optional timeout duration for connections, 30 seconds by default.
+    ///
+    /// Set it to `None` to wait as long as the operating system allows.
     pub timeout: Option<std::time::Duration>,
-    /// Default headers to be included in every request
+    /// Optional limit on how long a single read or write on the connection may wait,
+    /// 30 seconds by default.
+    ///
+    /// A server that stops sending makes the request fail with a timeout instead of
+    /// hanging, while a slow but steady download is not cut short. Set it to `None`
+    /// to wait indefinitely. A custom `connector`'s connections are only covered when
+    /// they are a `TcpStream`.
+    pub read_timeout: Option<std::time::Duration>,
+    /// Optional limit on the duration of an entire request.
+    ///
+    /// The deadline is computed when `send` starts and covers connecting, writing the
+    /// request and reading the response, including the body.
+    pub total_timeout: Option<std::time::Duration>,
+    /// Which IP address family resolved addresses must belong to
+    pub address_family: AddressFamily,
+    /// Optional callback notified at each phase of a request, see `ClientEvent`
+    pub on_event: Option<EventHook>,
+    /// Whether responses must be strictly RFC 7230 compliant.
+    ///
+    /// Responses are parsed leniently by default. In strict mode a status or header line
+    /// that isn't terminated by CRLF, has a malformed status line or header name, or
+    /// contains control characters fails with `ResponseError::InvalidStatusLine` or
+    /// `ResponseError::InvalidHeader`. Use `Uri::parse_strict` for the URI side.
+    pub strict: bool,
+    /// Whether a `ClientEvent::HeadersMerged` event reports how the default headers
+    /// were merged with each request's headers.
+    ///
+    /// Disabled by default, so the report is not computed unless asked for. Has no
+    /// effect without an `on_event` hook. See `HttpHeaders::merge_report`.
+    pub report_header_merge: bool,
+    /// How long to wait for the server to answer a request head sent with
+    /// `Expect: 100-continue` before sending the body anyway, 1 second by default.
+    ///
+    /// Servers that don't implement `Expect` never send `100 Continue`, so without
+    /// this limit the upload would stall until `read_timeout`. `None` waits for an
+    /// answer however long it takes. Only applies to connections that are a
+    /// `TcpStream`, including ones given to `send_on`.
+    pub continue_timeout: Option<std::time::Duration>,
+    /// Ends the request line and headers with a bare `\n` instead of `\r\n`.
+    ///
+    /// **For protocol-compliance testing only.** Servers must accept `\r\n`, and many
+    /// reject bare `\n`; this lets tools check how a server treats the malformed form.
+    /// Disabled by default. Chunked body framing and `raw_override` bytes are not
+    /// affected.
+    pub bare_lf_line_endings: bool,
+    /// The most header lines a response may have, `MAX_HEADER_LINES` (100) by default.
+    ///
+    /// A response with more fails with `ResponseError::HeadersTooLarge`. This guards
+    /// against servers sending many small headers, however long each one is.
+    pub max_headers: usize,
+    /// Whether responses keep a copy of their body once it has been read.
+    ///
+    /// Disabled by default, since it doubles the memory used by each body. When
+    /// enabled, `HttpResponse::raw_body` returns the bytes after `body()` or
+    /// `body_as_string()` has consumed them.
+    pub retain_body: bool,
+    /// Opens the connections requests are sent over, see `Connector`.
+    ///
+    /// When `None`, the default, a direct TCP connection is made to an address of
+    /// the request's host matching `address_family`. A custom connector is given the
+    /// request's `host:port` instead, so no `DnsResolved` event is emitted and
+    /// `address_family` is left to the connector. `total_timeout` and cancellation
+    /// interrupt a blocking read when the connector returns a `TcpStream`; on other
+    /// streams they are checked between reads.
+    pub connector: Option<Arc<dyn Connector>>,
+    /// Idle time after which TCP keepalive probes are sent on a connection.
+    ///
+    /// Probes let a dead peer be detected on a long-lived connection, such as one
+    /// streaming a response, instead of waiting until a write fails. `None`, the
+    /// default, leaves the operating system's setting. Only applies to connections
+    /// made without a custom `connector`.
+    #[cfg(feature = "socket-options")]
+    pub tcp_keepalive: Option<std::time::Duration>,
+    /// How long closing a connection may block to send unsent data (`SO_LINGER`).
+    ///
+    /// `None`, the default, leaves the operating system's setting. Only applies to
+    /// connections made without a custom `connector`.
+    #[cfg(feature = "socket-options")]
+    pub tcp_linger: Option<std::time::Duration>,
+    /// Query parameters appended to the query of every request, as unencoded pairs.
+    ///
+    /// A parameter is left out when the request's own query already has one with the
+    /// same name. See `default_query_param`.
+    pub default_query: Vec<(String, String)>,
+    /// Default headers to be included in every request.
+    ///
+    /// Headers set on an individual `HttpRequest` take precedence over these. These are
+    /// `HttpHeaders::browser_like()` unless replaced, for example with
+    /// `HttpHeaders::minimal()` or `HttpHeaders::api_json()`.
     pub headers: HttpHeaders,
+    /// How long resolved addresses of a host are reused, `None` (the default) to
+    /// resolve the host for every request.
+    ///
+    /// Resolving again on every request lets a host's address change take effect
+    /// immediately, but adds the resolver's latency to each request. The duration is
+    /// not taken from the DNS records. A host's cached addresses are dropped when
+    /// connecting to them fails, and `clear_dns_cache` drops them all. The cache is
+    /// shared with clones of the client and is not used with a custom `connector`.
+    pub dns_cache_ttl: Option<std::time::Duration>,
+    /// Counters of the connections opened by this client and its clones
+    connections: Arc<ConnectionCounters>,
+    /// Addresses resolved by this client and its clones, see `dns_cache_ttl`
+    dns_cache: Arc<DnsCache>,
 }
 
 /// Represents possible errors that can occur during HTTP operations.
@@ -32,24 +191,237 @@ pub struct HttpClient {
 pub enum HttpError {
     /// The provided URI is invalid or cannot be parsed
     InvalidUri,
+    /// The provided method name is not a known HTTP method
+    InvalidMethod,
     /// Failed to establish a TCP connection to the server
-    ConnectionFailed,
+    ConnectionFailed {
+        /// The resolved address that the connection was attempted to
+        addr: std::net::SocketAddr,
+        /// The kind of I/O error the connection attempt failed with
+        kind: std::io::ErrorKind,
+    },
+    /// The client's custom `connector` failed to open a connection
+    ConnectorFailed(std::io::ErrorKind),
+    /// The request uses `https`, but this build has no TLS support. Such requests can
+    /// still be sent through a custom `connector` that performs the TLS handshake.
+    TlsUnsupported,
+    /// The request did not complete before the client's `total_timeout`
+    Timeout,
+    /// The request's streaming body was already consumed by an earlier send
+    BodyConsumed,
+    /// The request has a body but its method forbids one, as TRACE does
+    BodyNotAllowed(HttpMethod),
+    /// The server's response could not be parsed
+    InvalidResponse(ResponseError),
+    /// The request was cancelled through its `CancelHandle`
+    Cancelled,
+    /// The response passed to `follow` is not a redirect with a `Location` header
+    NotRedirect(StatusCode),
     /// An unexpected error occurred during the operation
     UnknownError,
 }
 
+impl std::fmt::Display for HttpError {
+    /// Formats the error as a human readable message.
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::HttpError;
+    ///
+    /// let err = HttpError::ConnectionFailed {
+    ///     addr: "[2001:db8::1]:443".parse().unwrap(),
+    ///     kind: std::io::ErrorKind::ConnectionRefused,
+    /// };
+    /// assert_eq!(err.to_string(), "connect to [2001:db8::1]:443 failed: connection refused");
+    /// ```
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HttpError::InvalidUri => write!(f, "invalid URI"),
+            HttpError::InvalidMethod => write!(f, "invalid HTTP method"),
+            HttpError::ConnectionFailed { addr, kind } => {
+                write!(f, "connect to {} failed: {}", addr, kind)
+            }
+            HttpError::ConnectorFailed(kind) => write!(f, "connector failed: {}", kind),
+            HttpError::TlsUnsupported => write!(f, "https requires TLS, which is not supported"),
+            HttpError::Timeout => write!(f, "request timed out"),
+            HttpError::BodyConsumed => write!(f, "request body was already consumed"),
+            HttpError::BodyNotAllowed(method) => {
+                write!(f, "{} requests cannot have a body", method)
+            }
+            HttpError::InvalidResponse(err) => write!(f, "invalid response: {}", err),
+            HttpError::Cancelled => write!(f, "request was cancelled"),
+            HttpError::NotRedirect(status) => write!(f, "{} is not a redirect", status),
+            HttpError::UnknownError => write!(f, "unknown error"),
+        }
+    }
+}
+
+impl std::error::Error for HttpError {}
+
+impl From<std::io::Error> for HttpError {
+    fn from(err: std::io::Error) -> Self {
+        if is_cancelled_error(&err) {
+            return HttpError::Cancelled;
+        }
+        match err.kind() {
+            std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock => HttpError::Timeout,
+            _ => HttpError::UnknownError,
+        }
+    }
+}
+
+impl From<ResponseError> for HttpError {
+    fn from(err: ResponseError) -> Self {
+        match err {
+            ResponseError::Timeout => HttpError::Timeout,
+            ResponseError::Cancelled => HttpError::Cancelled,
+            err => HttpError::InvalidResponse(err),
+        }
+    }
+}
+
 impl HttpClient {
     /// Creates a new HTTP client with default configuration.
     ///
     /// # Returns
-    /// A new `HttpClient` instance with no timeout and empty default headers.
+    /// A new `HttpClient` instance whose connections time out after 30 seconds of
+    /// connecting or of waiting on a single read or write, with no total timeout.
     pub fn new() -> Self {
         HttpClient {
-            timeout: None,
+            timeout: Some(DEFAULT_TIMEOUT),
+            read_timeout: Some(DEFAULT_TIMEOUT),
+            total_timeout: None,
+            address_family: AddressFamily::Any,
+            on_event: None,
+            strict: false,
+            report_header_merge: false,
+            continue_timeout: Some(DEFAULT_CONTINUE_TIMEOUT),
+            bare_lf_line_endings: false,
+            max_headers: MAX_HEADER_LINES,
+            retain_body: false,
+            connector: None,
+            #[cfg(feature = "socket-options")]
+            tcp_keepalive: None,
+            #[cfg(feature = "socket-options")]
+            tcp_linger: None,
+            default_query: Vec::new(),
             headers: HttpHeaders::default(),
+            dns_cache_ttl: None,
+            connections: Arc::default(),
+            dns_cache: Arc::default(),
         }
     }
 
+    /// Returns the default headers included in every request sent by this client.
+    ///
+    /// These are kept separate from each request's own headers. When a request is sent
+    /// the two sets are merged with `HttpHeaders::combine`, so a header set on the
+    /// request replaces the default with the same name.
+    pub fn default_headers(&self) -> &HttpHeaders {
+        &self.headers
+    }
+
+    /// Replaces all of the client's default headers.
+    ///
+    /// Headers set on a request still take precedence over these.
+    ///
+    /// # Parameters
+    /// * `headers` - The new default headers, such as one of the `HttpHeaders` presets
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::{HttpClient, HttpHeaders};
+    ///
+    /// let mut client = HttpClient::new();
+    /// client.set_default_headers(HttpHeaders::api_json());
+    /// assert_eq!(client.default_headers(), &HttpHeaders::api_json());
+    /// ```
+    pub fn set_default_headers(&mut self, headers: HttpHeaders) {
+        self.headers = headers;
+    }
+
+    /// Layers headers onto the client's default headers.
+    ///
+    /// Each header in `headers` replaces every default value with the same name, and
+    /// headers removed from `headers` with `HttpHeaders::remove` are removed from the
+    /// defaults. Other defaults are kept. Headers set on a request still take
+    /// precedence over the result.
+    ///
+    /// # Parameters
+    /// * `headers` - The headers to add to or replace in the defaults
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::{HttpClient, HttpHeaders};
+    ///
+    /// let mut client = HttpClient::new();
+    /// client.set_default_headers(HttpHeaders::api_json());
+    ///
+    /// let mut overrides = HttpHeaders::new();
+    /// overrides.insert("Accept".to_string(), "application/xml".to_string());
+    /// overrides.remove("User-Agent");
+    /// client.merge_default_headers(overrides);
+    ///
+    /// assert_eq!(client.default_headers().to_wire_string(), "Accept: application/xml\r\n");
+    /// ```
+    pub fn merge_default_headers(&mut self, headers: HttpHeaders) {
+        self.headers = self.headers.combine(&headers);
+    }
+
+    /// Adds a query parameter to every request sent by this client.
+    ///
+    /// This suits APIs that require a parameter such as an `api_key` on every endpoint.
+    /// Parameters are appended, encoded, after those of the request's own query, which
+    /// takes precedence: a default is not sent when the request already has a
+    /// parameter with the same name. The request's `uri` itself is not changed.
+    ///
+    /// # Parameters
+    /// * `key` - The unencoded parameter name
+    /// * `value` - The unencoded parameter value
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::HttpClient;
+    ///
+    /// let mut client = HttpClient::new();
+    /// client.default_query_param("api_key", "s3cr3t");
+    /// assert_eq!(client.default_query, [("api_key".to_string(), "s3cr3t".to_string())]);
+    /// ```
+    pub fn default_query_param(&mut self, key: &str, value: &str) {
+        self.default_query
+            .push((key.to_string(), value.to_string()));
+    }
+
+    /// Sets the default `Accept-Encoding` header from codings and their quality values.
+    ///
+    /// Codings are listed in the order given. A quality of `1` is the default and is
+    /// left out of the header. The coding the server picked is reported by
+    /// `HttpResponse::content_encoding`, and `HttpResponse::decompressed_body` decodes
+    /// the body accordingly.
+    ///
+    /// # Parameters
+    /// * `encodings` - The accepted codings, each with a quality value between 0 and 1
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::{Encoding, HttpClient};
+    ///
+    /// let mut client = HttpClient::new();
+    /// client.set_accept_encodings(&[
+    ///     (Encoding::Gzip, 1.0),
+    ///     (Encoding::Deflate, 0.5),
+    ///     (Encoding::Identity, 0.1),
+    /// ]);
+    /// assert_eq!(
+    ///     client.default_headers().get("Accept-Encoding").map(String::as_str),
+    ///     Some("gzip, deflate;q=0.5, identity;q=0.1")
+    /// );
+    /// ```
+    pub fn set_accept_encodings(&mut self, encodings: &[(Encoding, f32)]) {
+        self.headers
+            .set_accept_encoding(accept_encoding_value(encodings));
+    }
+
     /// Creates a new HTTP request with the specified method and URI.
     ///
     /// # Parameters
@@ -65,11 +437,48 @@ impl HttpClient {
         HttpRequest::new(method, uri)
     }
 
+    /// Creates a new HTTP request from a method name and URL given as strings.
+    ///
+    /// Unlike `request`, both inputs are parsed fallibly, which suits methods and URLs
+    /// that come from user input.
+    ///
+    /// # Parameters
+    /// * `method` - The method name, such as "GET" (case-insensitive)
+    /// * `url` - The target URL
+    ///
+    /// # Returns
+    /// * `Ok(HttpRequest)` - If both the method and URL are valid
+    /// * `Err(HttpError::InvalidMethod)` - If the method is not a known HTTP method
+    /// * `Err(HttpError::InvalidUri)` - If the URL cannot be parsed
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::{HttpClient, HttpError, HttpMethod};
+    ///
+    /// let client = HttpClient::new();
+    /// let request = client.request_str("post", "http://example.com/items").unwrap();
+    /// assert_eq!(request.method, HttpMethod::POST);
+    ///
+    /// assert_eq!(client.request_str("FETCH", "http://example.com").err(), Some(HttpError::InvalidMethod));
+    /// assert_eq!(client.request_str("GET", "ftp://example.com").err(), Some(HttpError::InvalidUri));
+    /// ```
+    pub fn request_str(&self, method: &str, url: &str) -> Result<HttpRequest, HttpError> {
+        let method = method
+            .trim()
+            .parse::<HttpMethod>()
+            .map_err(|_| HttpError::InvalidMethod)?;
+        let uri = url
+            .trim()
+            .parse::<Uri>()
+            .map_err(|_| HttpError::InvalidUri)?;
+        Ok(HttpRequest::new(method, uri))
+    }
+
     /// Sends an HTTP request and returns the response.
     ///
     /// This method will:
     /// 1. Establish a TCP connection to the server
-    /// 2. Send the request line and headers
+    /// 2. Send the request line, headers and body
     /// 3. Read and parse the response
     ///
     /// # Parameters
@@ -77,34 +486,981 @@ impl HttpClient {
     ///
     /// # Returns
     /// A `Result` containing either the `HttpResponse` or an `HttpError`
+    ///
+    /// # Expect: 100-continue
+    /// When a request with a body has an `Expect: 100-continue` header, only the
+    /// request head is sent at first. The body follows once the server answers
+    /// `100 Continue`, or when it has said nothing within `continue_timeout`; any other
+    /// final response is returned without sending the body.
+    /// If the server answers `417 Expectation Failed`, the request is sent again
+    /// without the `Expect` header, on the same connection unless the server closes it.
+    /// It is sent again at most once, so a second 417 is returned as is.
+    ///
+    /// # HTTPS
+    /// This crate has no TLS support, so `https` requests fail immediately with
+    /// `HttpError::TlsUnsupported` instead of being sent in plain text. Setting a
+    /// `connector` that performs the TLS handshake allows them to be sent.
     pub fn send(&self, request: &HttpRequest) -> Result<HttpResponse, HttpError> {
-        let addr = request
-            .uri
-            .get_addr()
-            .to_socket_addrs()
-            .map_err(|_| HttpError::InvalidUri)?
-            .next()
-            .ok_or(HttpError::InvalidUri)?;
+        Ok(self.send_tracking_expect(request)?.0)
+    }
 
-        let mut stream = match self.timeout {
-            Some(x) => TcpStream::connect_timeout(&addr, x),
-            None => TcpStream::connect(addr),
+    /// Sends a request as `send` does.
+    ///
+    /// # Parameters
+    /// * `request` - The `HttpRequest` to send
+    ///
+    /// # Returns
+    /// The response, and whether the request it answers was sent with the `Expect`
+    /// header, if it has one, rather than resent without it after a 417
+    fn send_tracking_expect(
+        &self,
+        request: &HttpRequest,
+    ) -> Result<(HttpResponse, bool), HttpError> {
+        Self::check_body_allowed(request)?;
+        let (response, expectation) = self.send_expecting(request, true)?;
+        match expectation {
+            Expectation::Met => Ok((response, true)),
+            Expectation::Dropped => Ok((response, false)),
+            // The server closed the connection instead of letting us retry on it
+            Expectation::Refused => Ok((self.send_expecting(request, false)?.0, false)),
         }
-        .map_err(|_| HttpError::ConnectionFailed)?;
+    }
 
-        let request_line = request.get_request_line();
-        write!(stream, "{}\r\n", request_line).map_err(|_| HttpError::UnknownError)?;
+    /// Connects to the server and performs the exchange for `send`.
+    ///
+    /// # Parameters
+    /// * `request` - The `HttpRequest` to send
+    /// * `send_expect` - Whether an `Expect` header is sent, if the request has one
+    ///
+    /// # Returns
+    /// The response, and what the server made of the `Expect` header, as for `exchange`
+    fn send_expecting(
+        &self,
+        request: &HttpRequest,
+        send_expect: bool,
+    ) -> Result<(HttpResponse, Expectation), HttpError> {
+        let started = Instant::now();
+        let deadline = self.total_timeout.map(|x| started + x);
+        if let Some(cancel) = &request.cancel {
+            cancel.check()?;
+        }
+
+        if let Some(connector) = &self.connector {
+            let stream = connector
+                .connect(&request.uri.get_addr(), self.connect_timeout(deadline)?)
+                .map_err(|err| match deadline {
+                    Some(x) if Instant::now() >= x => HttpError::Timeout,
+                    _ => HttpError::ConnectorFailed(err.kind()),
+                })?;
+            return self.exchange_until(stream, request, started, deadline, send_expect);
+        }
+
+        if request.uri.protocol == Protocol::HTTPS {
+            return Err(HttpError::TlsUnsupported);
+        }
+
+        let host = request.uri.get_addr();
+        let addrs: Vec<SocketAddr> = self
+            .dns_cache
+            .resolve(&host, self.dns_cache_ttl)
+            .map_err(|_| HttpError::InvalidUri)?
+            .into_iter()
+            .filter(|addr| self.address_family.matches(addr))
+            .collect();
+        let Some(&first) = addrs.first() else {
+            return Err(HttpError::InvalidUri);
+        };
+        self.emit(ClientEvent::DnsResolved {
+            addr: first,
+            elapsed: started.elapsed(),
+        });
+
+        let (addr, stream) = TcpConnector::connect_any(&addrs, self.connect_timeout(deadline)?)
+            .map_err(|(addr, err)| {
+                // The host may have moved, so resolve it again next time
+                self.dns_cache.invalidate(&host);
+                match deadline {
+                    Some(x) if Instant::now() >= x => HttpError::Timeout,
+                    _ => HttpError::ConnectionFailed {
+                        addr,
+                        kind: err.kind(),
+                    },
+                }
+            })?;
+        #[cfg(feature = "socket-options")]
+        self.configure_socket(&stream)
+            .map_err(|err| HttpError::ConnectionFailed {
+                addr,
+                kind: err.kind(),
+            })?;
+        stream
+            .set_read_timeout(self.read_timeout)
+            .and_then(|_| stream.set_write_timeout(self.read_timeout))
+            .map_err(|err| HttpError::ConnectionFailed {
+                addr,
+                kind: err.kind(),
+            })?;
+        self.exchange_until(Box::new(stream), request, started, deadline, send_expect)
+    }
+
+    /// Performs the exchange for `send` on a newly opened connection, failing once
+    /// `deadline` passes or the request is cancelled.
+    ///
+    /// # Parameters
+    /// * `stream` - The connected stream
+    /// * `request` - The `HttpRequest` to send
+    /// * `started` - When the request started, used for event timings
+    /// * `deadline` - When the request times out as a whole, or `None` for no limit
+    /// * `send_expect` - Whether an `Expect` header is sent, if the request has one
+    fn exchange_until(
+        &self,
+        stream: Box<dyn ReadWrite>,
+        request: &HttpRequest,
+        started: Instant,
+        deadline: Option<Instant>,
+        send_expect: bool,
+    ) -> Result<(HttpResponse, Expectation), HttpError> {
+        let connection = self.connections.open();
+        self.emit(ClientEvent::Connected {
+            elapsed: started.elapsed(),
+        });
+
+        // Also covers a connector's `TcpStream`, which may have no timeouts of its own
+        let stream = DeadlineStream::new(stream, deadline, request.cancel.clone())
+            .with_idle_timeout(self.read_timeout);
+        let handle = stream.deadline_handle();
+        let (mut response, expectation) =
+            self.exchange(stream, request, started, send_expect, Some(&handle))?;
+        response.set_connection(connection);
+        response.set_body_deadline(handle);
+        Ok((response, expectation))
+    }
+
+    /// Applies the client's `tcp_keepalive` and `tcp_linger` options to a connection.
+    #[cfg(feature = "socket-options")]
+    fn configure_socket(&self, stream: &std::net::TcpStream) -> std::io::Result<()> {
+        let socket = socket2::SockRef::from(stream);
+        if let Some(idle) = self.tcp_keepalive {
+            socket.set_tcp_keepalive(&socket2::TcpKeepalive::new().with_time(idle))?;
+        }
+        if let Some(linger) = self.tcp_linger {
+            socket.set_linger(Some(linger))?;
+        }
+        Ok(())
+    }
 
-        let headers = self.headers.combine(&request.headers);
-        for (key, value) in headers.iter() {
-            write!(stream, "{}: {}\r\n", *key, *value).map_err(|_| HttpError::UnknownError)?;
+    /// Returns how long connecting may take.
+    ///
+    /// The client's `timeout` is capped by whatever remains of the overall deadline.
+    ///
+    /// # Returns
+    /// * `Ok(Option<Duration>)` - The timeout, or `None` for no limit
+    /// * `Err(HttpError::Timeout)` - If the deadline has already passed
+    fn connect_timeout(&self, deadline: Option<Instant>) -> Result<Option<Duration>, HttpError> {
+        let remaining = deadline.map(|x| x.saturating_duration_since(Instant::now()));
+        if remaining.is_some_and(|x| x.is_zero()) {
+            return Err(HttpError::Timeout);
         }
+        Ok(match (self.timeout, remaining) {
+            (Some(x), Some(y)) => Some(x.min(y)),
+            (x, y) => x.or(y),
+        })
+    }
+
+    /// Checks that a server is reachable by sending it a `HEAD` request.
+    ///
+    /// Only the status line and headers are read, so a server that never finishes a body
+    /// cannot stall the check. The client's timeouts apply as for `send`.
+    ///
+    /// # Parameters
+    /// * `uri` - The URI to probe
+    ///
+    /// # Returns
+    /// * `Ok(StatusCode)` - The status the server responded with
+    /// * `Err(HttpError)` - If the server could not be reached or its response was invalid
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use clienter::HttpClient;
+    ///
+    /// let mut client = HttpClient::new();
+    /// client.timeout = Some(Duration::from_secs(2));
+    ///
+    /// for peer in ["http://10.0.0.1/health", "http://10.0.0.2/health"] {
+    ///     match client.check(peer) {
+    ///         Ok(status) => println!("{}: {}", peer, status),
+    ///         Err(err) => println!("{}: down ({})", peer, err),
+    ///     }
+    /// }
+    /// ```
+    pub fn check<T>(&self, uri: T) -> Result<StatusCode, HttpError>
+    where
+        T: Into<Uri>,
+    {
+        let request = self.request(HttpMethod::HEAD, uri);
+        Ok(self.send(&request)?.status_code())
+    }
+
+    /// Sends an HTTP request and returns the request as it was sent, with the response.
+    ///
+    /// The returned request has the client's default query parameters added to its
+    /// URI, and headers that include the client's default headers, the `Host` header
+    /// and the headers describing the body. This makes it possible to log exactly what
+    /// produced a response, for example after following a redirect with `follow`.
+    ///
+    /// A request with `raw_override` set is returned with its override, as those bytes
+    /// are what was sent. If a `417 Expectation Failed` response made the client resend
+    /// the request, the returned request has no `Expect` header, like the one resent.
+    /// A streamed body has already been read, so the returned request cannot be sent
+    /// again.
+    ///
+    /// # Parameters
+    /// * `request` - The `HttpRequest` to send
+    ///
+    /// # Returns
+    /// A `Result` containing either the sent `HttpRequest` and the `HttpResponse`, or an
+    /// `HttpError`
+    ///
+    /// # Example
+    /// ```no_run
+    /// use clienter::{HttpClient, HttpMethod};
+    ///
+    /// let mut client = HttpClient::new();
+    /// client.default_query_param("api_key", "secret");
+    /// let request = client.request(HttpMethod::GET, "http://example.com/items");
+    /// let (sent, response) = client.send_detailed(&request).unwrap();
+    /// println!("{} -> {}", sent.get_request_line(), response.status);
+    /// ```
+    pub fn send_detailed(
+        &self,
+        request: &HttpRequest,
+    ) -> Result<(HttpRequest, HttpResponse), HttpError> {
+        let (response, sent_expect) = self.send_tracking_expect(request)?;
+        let (uri, headers) = self.effective_head(request, sent_expect);
+        let sent = HttpRequest {
+            uri,
+            headers,
+            ..request.clone()
+        };
+        Ok((sent, response))
+    }
+
+    /// Follows a single redirect by sending the request it points to.
+    ///
+    /// The `Location` of `response` is resolved against the URI the response came
+    /// from, and `original` is sent there with the changes browsers make:
+    /// * `303 See Other` turns any method but HEAD into a body-less GET, as do
+    ///   `301` and `302` for a POST. `307` and `308` keep the method and body.
+    /// * When the redirect leaves the origin, the `Authorization`, `Proxy-Authorization`
+    ///   and `Cookie` headers and any host override are dropped, including ones that
+    ///   would come from the client's default headers.
+    ///
+    /// The returned response's `redirect_history` ends with the URI that was redirected
+    /// from, so passing it back to `follow` with the same `original` follows a chain one
+    /// step at a time.
+    ///
+    /// # Parameters
+    /// * `response` - The redirect response
+    /// * `original` - The request that produced `response`
+    ///
+    /// # Returns
+    /// * `Ok(HttpResponse)` - The response to the redirected request
+    /// * `Err(HttpError::NotRedirect)` - If `response` is not a 301, 302, 303, 307 or
+    ///   308 with a `Location` header
+    /// * `Err(HttpError::InvalidUri)` - If the `Location` cannot be resolved
+    /// * `Err(HttpError::BodyConsumed)` - If a 307 or 308 would resend a streamed body
+    ///
+    /// # Example
+    /// ```no_run
+    /// use clienter::{HttpClient, HttpMethod};
+    ///
+    /// let client = HttpClient::new();
+    /// let request = client.request(HttpMethod::GET, "http://example.com/old");
+    /// let response = client.send(&request).unwrap();
+    /// if response.headers.get("Location").is_some() {
+    ///     let response = client.follow(&response, &request).unwrap();
+    ///     println!("{:?} -> {}", response.redirect_history, response.status);
+    /// }
+    /// ```
+    pub fn follow(
+        &self,
+        response: &HttpResponse,
+        original: &HttpRequest,
+    ) -> Result<HttpResponse, HttpError> {
+        let from = response.final_uri.as_ref().unwrap_or(&original.uri);
+        let request = redirect_request(response, original, from)?;
+        let mut next = self.send(&request)?;
+        next.redirect_history = response.redirect_history.clone();
+        next.redirect_history.push(from.clone());
+        Ok(next)
+    }
 
-        write!(stream, "\r\n\r\n").map_err(|_| HttpError::UnknownError)?;
-        stream.flush().map_err(|_| HttpError::UnknownError)?;
+    /// Forgets every address in the DNS cache, so each host is resolved again.
+    ///
+    /// This also affects clones of the client, which share the cache. See
+    /// `dns_cache_ttl`.
+    pub fn clear_dns_cache(&self) {
+        self.dns_cache.clear();
+    }
+
+    /// Returns a snapshot of the connections opened by this client.
+    ///
+    /// Clones of a client share these counters. Only connections opened by `send` are
+    /// counted; streams passed to `send_on` are managed by the caller.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use clienter::{HttpClient, HttpMethod};
+    ///
+    /// let client = HttpClient::new();
+    /// let request = client.request(HttpMethod::GET, "http://example.com");
+    /// let response = client.send(&request).unwrap();
+    /// assert_eq!(client.pool_stats().in_use, 1);
+    ///
+    /// drop(response);
+    /// assert_eq!(client.pool_stats().in_use, 0);
+    /// ```
+    pub fn pool_stats(&self) -> PoolStats {
+        self.connections.stats()
+    }
+
+    /// Sends an HTTP request on a background thread.
+    ///
+    /// The client and request are cloned and the blocking `send` runs on a newly
+    /// spawned thread, so several requests can be in flight at once without managing
+    /// threads by hand. The result is delivered over the returned channel once the
+    /// response head has been read; the body is read from the receiving thread as usual.
+    ///
+    /// # Parameters
+    /// * `request` - The `HttpRequest` to send
+    ///
+    /// # Returns
+    /// A `Receiver` that yields the result of the request exactly once
+    ///
+    /// # Example
+    /// ```no_run
+    /// use clienter::{HttpClient, HttpMethod};
+    ///
+    /// let client = HttpClient::new();
+    /// let receivers: Vec<_> = ["http://example.com/a", "http://example.com/b"]
+    ///     .into_iter()
+    ///     .map(|url| client.send_async(&client.request(HttpMethod::GET, url)))
+    ///     .collect();
+    ///
+    /// for receiver in receivers {
+    ///     let response = receiver.recv().unwrap();
+    /// }
+    /// ```
+    pub fn send_async(&self, request: &HttpRequest) -> Receiver<Result<HttpResponse, HttpError>> {
+        let (sender, receiver) = mpsc::channel();
+        let client = self.clone();
+        let request = request.clone();
+        thread::spawn(move || {
+            // The receiver may have been dropped, in which case nobody wants the result
+            let _ = sender.send(client.send(&request));
+        });
+        receiver
+    }
+
+    /// Sends several HTTP requests, running up to `concurrency` of them at once.
+    ///
+    /// Requests are handed out to a fixed set of worker threads in input order. Each
+    /// worker reads the whole response body before moving on, so the returned
+    /// responses hold their body in memory and no reading happens on the connection
+    /// after this method returns.
+    ///
+    /// # Parameters
+    /// * `requests` - The requests to send
+    /// * `concurrency` - The maximum number of requests in flight at once, at least 1
+    ///
+    /// # Returns
+    /// The result of each request, in the same order as `requests`
+    ///
+    /// # Example
+    /// ```no_run
+    /// use clienter::{HttpClient, HttpMethod};
+    ///
+    /// let client = HttpClient::new();
+    /// let requests = ["http://10.0.0.1/health", "http://10.0.0.2/health"]
+    ///     .into_iter()
+    ///     .map(|url| client.request(HttpMethod::GET, url))
+    ///     .collect();
+    ///
+    /// for result in client.send_batch(requests, 8) {
+    ///     match result {
+    ///         Ok(response) => println!("{}", response.status_code()),
+    ///         Err(err) => println!("{}", err),
+    ///     }
+    /// }
+    /// ```
+    pub fn send_batch(
+        &self,
+        requests: Vec<HttpRequest>,
+        concurrency: usize,
+    ) -> Vec<Result<HttpResponse, HttpError>> {
+        let count = requests.len();
+        let pending = Mutex::new(requests.into_iter().enumerate());
+        let results = Mutex::new((0..count).map(|_| None).collect::<Vec<_>>());
+
+        thread::scope(|scope| {
+            for _ in 0..concurrency.clamp(1, count.max(1)) {
+                scope.spawn(|| loop {
+                    let next = pending.lock().unwrap().next();
+                    let Some((index, request)) = next else {
+                        break;
+                    };
+
+                    let result = self.send(&request).and_then(|mut response| {
+                        response.buffer_body()?;
+                        Ok(response)
+                    });
+                    results.lock().unwrap()[index] = Some(result);
+                });
+            }
+        });
 
-        let response = HttpResponse::build(stream).map_err(|_| HttpError::UnknownError)?;
+        results
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .map(|result| result.unwrap_or(Err(HttpError::UnknownError)))
+            .collect()
+    }
 
+    /// Performs an HTTP exchange on an already-connected stream.
+    ///
+    /// No connection is established by this method; the request line, headers and
+    /// body are written directly to `stream` and the response is read back from it.
+    /// This allows requests to be carried over any transport, such as a tunnel or an
+    /// in-memory pipe in tests.
+    ///
+    /// The stream's own timeouts are kept. On a `TcpStream`, the body of a request sent
+    /// with `Expect: 100-continue` follows after `continue_timeout` if the server has
+    /// not answered; other streams wait for an answer as long as they allow.
+    ///
+    /// # Parameters
+    /// * `stream` - A connected stream, such as a `TcpStream`
+    /// * `request` - The `HttpRequest` to send
+    ///
+    /// # Returns
+    /// A `Result` containing either the `HttpResponse` or an `HttpError`
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::net::TcpStream;
+    /// use clienter::{HttpClient, HttpMethod};
+    ///
+    /// let client = HttpClient::new();
+    /// let stream = TcpStream::connect("127.0.0.1:8080").unwrap();
+    /// let request = client.request(HttpMethod::GET, "http://localhost:8080/status");
+    /// let response = client.send_on(stream, &request).unwrap();
+    /// ```
+    pub fn send_on<S>(&self, stream: S, request: &HttpRequest) -> Result<HttpResponse, HttpError>
+    where
+        S: ReadWrite + 'static,
+    {
+        Self::check_body_allowed(request)?;
+        // Keeps the stream's own timeouts, and bounds the wait for `100 Continue`
+        let stream = DeadlineStream::new(Box::new(stream), None, request.cancel.clone());
+        let handle = stream.deadline_handle();
+        let (mut response, _) =
+            self.exchange(stream, request, Instant::now(), true, Some(&handle))?;
+        response.set_body_deadline(handle);
         Ok(response)
     }
+
+    /// Writes the request to the stream and reads the response head back.
+    ///
+    /// # Parameters
+    /// * `stream` - A connected stream
+    /// * `request` - The `HttpRequest` to send
+    /// * `started` - When the request started, used for event timings
+    /// * `send_expect` - Whether an `Expect` header is sent, if the request has one
+    /// * `handle` - Bounds the wait for `100 Continue` by `continue_timeout`, or `None`
+    ///   to wait as long as the stream allows
+    ///
+    /// # Returns
+    /// The response, and what the server made of the `Expect` header. A 417 to a
+    /// request sent without `Expect` is never to be resent, so it is `Expectation::Met`.
+    fn exchange<S>(
+        &self,
+        mut stream: S,
+        request: &HttpRequest,
+        started: Instant,
+        send_expect: bool,
+        handle: Option<&DeadlineHandle>,
+    ) -> Result<(HttpResponse, Expectation), HttpError>
+    where
+        S: ReadWrite + 'static,
+    {
+        let expect_continue = send_expect && self.expects_continue(request);
+        if self.report_header_merge && self.on_event.is_some() && request.raw_override.is_none() {
+            self.emit(ClientEvent::HeadersMerged {
+                report: self.headers.merge_report(&request.headers),
+                elapsed: started.elapsed(),
+            });
+        }
+        let sent_at = Instant::now();
+        match &request.raw_override {
+            Some(raw) => stream.write_all(raw)?,
+            None if expect_continue => self.write_head(&mut stream, request, true)?,
+            None => self.write_request(&mut stream, request, send_expect)?,
+        }
+        stream.flush()?;
+
+        let mut expectation = Expectation::Met;
+        let mut response = if expect_continue {
+            match self.await_continue(&mut stream, handle)? {
+                Some(received) => {
+                    let stream = Prefilled {
+                        received: Cursor::new(received),
+                        stream,
+                    };
+                    let interim = self.read_response(stream, request)?;
+                    let (response, outcome) = self.continue_after(interim, request, started)?;
+                    expectation = outcome;
+                    response
+                }
+                None => {
+                    // The server said nothing, so it may not implement Expect
+                    self.write_body(&mut stream, request)?;
+                    stream.flush()?;
+                    self.emit(ClientEvent::RequestSent {
+                        elapsed: started.elapsed(),
+                    });
+                    let response = self.read_response(stream, request)?;
+                    if response.status == StatusCode::Continue100 {
+                        // A late `100 Continue`, followed by the final response
+                        self.read_response(response.into_stream(), request)?
+                    } else {
+                        response
+                    }
+                }
+            }
+        } else {
+            self.emit(ClientEvent::RequestSent {
+                elapsed: started.elapsed(),
+            });
+            self.read_response(stream, request)?
+        };
+
+        if let Some(first_byte_at) = response.first_byte_at() {
+            self.emit(ClientEvent::FirstByteReceived {
+                elapsed: first_byte_at.saturating_duration_since(started),
+            });
+        }
+        response.set_event_hook(self.on_event.clone(), started);
+        response.set_sent_at(sent_at);
+        response.set_retain_body(self.retain_body);
+        response.final_uri = Some(request.uri.clone());
+
+        Ok((response, expectation))
+    }
+
+    /// Waits for the server to start answering a request head sent with
+    /// `Expect: 100-continue`, for at most `continue_timeout`.
+    ///
+    /// # Parameters
+    /// * `stream` - The stream the head was written to
+    /// * `handle` - Sets the deadline of the wait on `stream`, or `None` to wait as
+    ///   long as the stream allows
+    ///
+    /// # Returns
+    /// * `Ok(Some(Vec<u8>))` - The first bytes of the answer, empty if the server
+    ///   closed the connection
+    /// * `Ok(None)` - If the server said nothing before `continue_timeout`
+    /// * `Err(HttpError)` - If reading failed otherwise
+    fn await_continue<S: Read>(
+        &self,
+        stream: &mut S,
+        handle: Option<&DeadlineHandle>,
+    ) -> Result<Option<Vec<u8>>, HttpError> {
+        let (Some(handle), Some(timeout)) = (handle, self.continue_timeout) else {
+            return Ok(Some(Vec::new()));
+        };
+        handle.set(Some(Instant::now() + timeout));
+        let mut received = vec![0; 1024];
+        let read = stream.read(&mut received);
+        handle.set(None);
+        match read {
+            Ok(read) => {
+                received.truncate(read);
+                Ok(Some(received))
+            }
+            // Past the request's own deadline, sending the body fails with a timeout
+            Err(err) if matches!(err.kind(), ErrorKind::TimedOut | ErrorKind::WouldBlock) => {
+                Ok(None)
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Finishes a request whose head was sent with `Expect: 100-continue`.
+    ///
+    /// # Parameters
+    /// * `interim` - The first response the server sent after the request head
+    /// * `request` - The `HttpRequest` being sent
+    /// * `started` - When the request started, used for event timings
+    ///
+    /// # Returns
+    /// The final response, and what the server made of the `Expect` header. A
+    /// `417 Expectation Failed` response is returned as is if the server closes the
+    /// connection, since the request cannot be retried on it.
+    fn continue_after(
+        &self,
+        mut interim: HttpResponse,
+        request: &HttpRequest,
+        started: Instant,
+    ) -> Result<(HttpResponse, Expectation), HttpError> {
+        match interim.status {
+            StatusCode::Continue100 => {
+                let mut stream = interim.into_stream();
+                self.write_body(&mut stream, request)?;
+                stream.flush()?;
+                self.emit(ClientEvent::RequestSent {
+                    elapsed: started.elapsed(),
+                });
+                Ok((self.read_response(stream, request)?, Expectation::Met))
+            }
+            StatusCode::ExpectationFailed417 if interim.headers.has_connection_option("close") => {
+                Ok((interim, Expectation::Refused))
+            }
+            StatusCode::ExpectationFailed417 => {
+                // Skip the 417 body so the retried request's response is read next
+                interim.body()?;
+                let mut stream = interim.into_stream();
+                self.write_request(&mut stream, request, false)?;
+                stream.flush()?;
+                self.emit(ClientEvent::RequestSent {
+                    elapsed: started.elapsed(),
+                });
+                Ok((self.read_response(stream, request)?, Expectation::Dropped))
+            }
+            _ => Ok((interim, Expectation::Met)),
+        }
+    }
+
+    /// Reads the head of the response to `request` from the stream.
+    ///
+    /// # Parameters
+    /// * `stream` - The stream the request was written to
+    /// * `request` - The `HttpRequest` that was sent
+    fn read_response<S>(
+        &self,
+        stream: S,
+        request: &HttpRequest,
+    ) -> Result<HttpResponse, ResponseError>
+    where
+        S: ReadWrite + 'static,
+    {
+        HttpResponse::build_for(
+            stream,
+            Some(&request.method),
+            self.strict,
+            self.sends_close(request),
+            self.max_headers,
+        )
+    }
+
+    /// Determines whether `request` is sent with `Connection: close`.
+    ///
+    /// A `Connection` header on the request overrides the client's default one.
+    fn sends_close(&self, request: &HttpRequest) -> bool {
+        request.raw_override.is_none()
+            && self
+                .headers
+                .combine(&request.headers)
+                .has_connection_option("close")
+    }
+
+    /// Determines whether `request` asks the server to confirm it wants the body first.
+    fn expects_continue(&self, request: &HttpRequest) -> bool {
+        if request.raw_override.is_some()
+            || request.body.is_none()
+            || request.method == HttpMethod::CONNECT
+        {
+            return false;
+        }
+        self.headers
+            .combine(&request.headers)
+            .get("Expect")
+            .is_some_and(|value| value.trim().eq_ignore_ascii_case("100-continue"))
+    }
+
+    /// Serializes the request line, headers and body of `request` to the stream.
+    ///
+    /// A body of up to `COALESCED_BODY_LIMIT` bytes is sent in the same write as the
+    /// head, so a small POST doesn't wait on Nagle's algorithm and delayed ACKs between
+    /// the two. Larger and streamed bodies are written separately.
+    ///
+    /// # Parameters
+    /// * `stream` - The stream to write to
+    /// * `request` - The `HttpRequest` to serialize
+    /// * `send_expect` - Whether an `Expect` header is sent, if the request has one
+    fn write_request<S>(
+        &self,
+        stream: &mut S,
+        request: &HttpRequest,
+        send_expect: bool,
+    ) -> Result<(), HttpError>
+    where
+        S: ReadWrite,
+    {
+        let mut head = self.head_bytes(request, send_expect);
+        match sent_body(request) {
+            Some(Body::Bytes(body)) if body.len() <= COALESCED_BODY_LIMIT => {
+                head.extend_from_slice(body);
+                stream.write_all(&head)?;
+                Ok(())
+            }
+            _ => {
+                stream.write_all(&head)?;
+                self.write_body(stream, request)
+            }
+        }
+    }
+
+    /// Serializes the request line and headers of `request` to the stream.
+    ///
+    /// # Parameters
+    /// * `stream` - The stream to write to
+    /// * `request` - The `HttpRequest` to serialize
+    /// * `send_expect` - Whether an `Expect` header is sent, if the request has one
+    fn write_head<S>(
+        &self,
+        stream: &mut S,
+        request: &HttpRequest,
+        send_expect: bool,
+    ) -> Result<(), HttpError>
+    where
+        S: ReadWrite,
+    {
+        stream.write_all(&self.head_bytes(request, send_expect))?;
+        Ok(())
+    }
+
+    /// Serializes the request line and headers of `request`, up to and including the
+    /// blank line that ends them.
+    ///
+    /// # Parameters
+    /// * `request` - The `HttpRequest` to serialize
+    /// * `send_expect` - Whether an `Expect` header is sent, if the request has one
+    fn head_bytes(&self, request: &HttpRequest, send_expect: bool) -> Vec<u8> {
+        let (uri, headers) = self.effective_head(request, send_expect);
+        let mut head = format!(
+            "{}\r\n{}\r\n",
+            request.request_line_for(&uri),
+            headers.to_wire_string()
+        );
+        if self.bare_lf_line_endings {
+            head = head.replace("\r\n", "\n");
+        }
+        head.into_bytes()
+    }
+
+    /// Computes the URI and headers that are sent for a request.
+    ///
+    /// The client's default query parameters are added to the request's URI, and its
+    /// default headers merged with the request's, along with the `Host` header and the
+    /// headers describing the body.
+    ///
+    /// # Parameters
+    /// * `request` - The `HttpRequest` being sent
+    /// * `send_expect` - Whether an `Expect` header is sent, if the request has one
+    fn effective_head(&self, request: &HttpRequest, send_expect: bool) -> (Uri, HttpHeaders) {
+        let defaults: Vec<(&str, &str)> = self
+            .default_query
+            .iter()
+            .filter(|(key, _)| !request.uri.has_query_param(key))
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect();
+        let mut uri = request.uri.clone();
+        if !defaults.is_empty() {
+            uri.append_query_pairs(&defaults);
+        }
+
+        let mut headers = self.headers.combine(&request.headers);
+        if request.host_override.is_some() || headers.get("Host").is_none() {
+            headers.insert("Host".to_string(), request.get_host());
+        }
+        if !send_expect {
+            headers.remove("Expect");
+        }
+
+        match sent_body(request) {
+            Some(Body::Bytes(body)) => {
+                if headers.get("Content-Length").is_none() {
+                    headers.insert("Content-Length".to_string(), body.len().to_string());
+                }
+                if let Some(content_type) = request.sniffed_content_type() {
+                    if headers.get("Content-Type").is_none() {
+                        headers.insert("Content-Type".to_string(), content_type.to_string());
+                    }
+                }
+            }
+            Some(Body::Stream(_)) => {
+                headers.insert("Transfer-Encoding".to_string(), "chunked".to_string());
+            }
+            None => {}
+        }
+        (uri, headers)
+    }
+
+    /// Writes the body of `request` to the stream, framed as announced by `write_head`.
+    ///
+    /// # Parameters
+    /// * `stream` - The stream to write to
+    /// * `request` - The `HttpRequest` whose body to write
+    fn write_body<S>(&self, stream: &mut S, request: &HttpRequest) -> Result<(), HttpError>
+    where
+        S: ReadWrite,
+    {
+        match sent_body(request) {
+            Some(Body::Bytes(body)) => stream.write_all(body)?,
+            Some(Body::Stream(reader)) => {
+                let mut reader = reader.take().ok_or(HttpError::BodyConsumed)?;
+                write_chunked(&mut reader, stream)?;
+            }
+            None => {}
+        }
+        Ok(())
+    }
+
+    /// Rejects requests whose method forbids a body but that have one.
+    ///
+    /// A TRACE request must not have a body (RFC 7231 section 4.3.8). CONNECT bodies
+    /// have no defined meaning either, but are dropped when writing rather than
+    /// rejected. Requests with a raw override are sent as given.
+    fn check_body_allowed(request: &HttpRequest) -> Result<(), HttpError> {
+        if request.method == HttpMethod::TRACE
+            && request.body.is_some()
+            && request.raw_override.is_none()
+        {
+            return Err(HttpError::BodyNotAllowed(HttpMethod::TRACE));
+        }
+        Ok(())
+    }
+
+    /// Notifies the event hook, if one is set.
+    fn emit(&self, event: ClientEvent) {
+        if let Some(on_event) = &self.on_event {
+            on_event(event);
+        }
+    }
+}
+
+/// Returns the body that is sent for `request`.
+///
+/// A CONNECT request only opens a tunnel, so any body it has is not sent.
+fn sent_body(request: &HttpRequest) -> &Option<Body> {
+    match request.method {
+        HttpMethod::CONNECT => &None,
+        _ => &request.body,
+    }
+}
+
+/// What a server made of the `Expect: 100-continue` header of a request.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Expectation {
+    /// The request was not refused for its `Expect` header
+    Met,
+    /// A `417 Expectation Failed` made the client send the request again without
+    /// `Expect`, on the same connection
+    Dropped,
+    /// A `417 Expectation Failed` came with the server closing the connection, so the
+    /// request has to be sent again without `Expect` on a new one
+    Refused,
+}
+
+/// A stream whose first bytes were already read from it, see `HttpClient::await_continue`.
+struct Prefilled<S> {
+    /// The bytes read, returned before anything else is read from `stream`
+    received: Cursor<Vec<u8>>,
+    stream: S,
+}
+
+impl<S: Read> Read for Prefilled<S> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.received.read(buf)?;
+        if read > 0 {
+            return Ok(read);
+        }
+        self.stream.read(buf)
+    }
+}
+
+impl<S: Write> Write for Prefilled<S> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.stream.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.stream.flush()
+    }
+}
+
+/// Builds the request that a redirect response points to, see `HttpClient::follow`.
+fn redirect_request(
+    response: &HttpResponse,
+    original: &HttpRequest,
+    from: &Uri,
+) -> Result<HttpRequest, HttpError> {
+    let location = match (response.status, response.headers.get("Location")) {
+        (
+            StatusCode::MovedPermanently301
+            | StatusCode::Found302
+            | StatusCode::SeeOther303
+            | StatusCode::TemporaryRedirect307
+            | StatusCode::PermanentRedirect308,
+            Some(location),
+        ) => location,
+        (status, _) => return Err(HttpError::NotRedirect(status)),
+    };
+    let target = from.join(location).map_err(|_| HttpError::InvalidUri)?;
+
+    let becomes_get = match response.status {
+        StatusCode::SeeOther303 => original.method != HttpMethod::HEAD,
+        StatusCode::MovedPermanently301 | StatusCode::Found302 => {
+            original.method == HttpMethod::POST
+        }
+        _ => false,
+    };
+    let mut request = if becomes_get {
+        let mut headers = original.headers.clone();
+        for header in ["Content-Type", "Content-Length", "Transfer-Encoding"] {
+            headers.remove(header);
+        }
+        HttpRequest {
+            method: HttpMethod::GET,
+            uri: original.uri.clone(),
+            http_version: original.http_version,
+            headers,
+            timeout: original.timeout,
+            body: None,
+            sniff_content_type: original.sniff_content_type,
+            absolute_form: original.absolute_form,
+            cancel: original.cancel.clone(),
+            host_override: original.host_override.clone(),
+            raw_override: None,
+        }
+    } else {
+        original.try_clone().ok_or(HttpError::BodyConsumed)?
+    };
+
+    if !from.same_origin(&target) {
+        for header in ["Authorization", "Proxy-Authorization", "Cookie"] {
+            request.headers.remove(header);
+        }
+        request.host_override = None;
+    }
+    request.uri = target;
+    request.raw_override = None;
+    Ok(request)
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        Self::new()
+    }
 }
@@ -0,0 +1,105 @@
+//! Pluggable connection establishment.
+//!
+//! By default `HttpClient` opens a TCP connection to the host of each request. A
+//! `Connector` replaces that step, so requests can be carried over a SOCKS proxy, a
+//! Unix socket, or an in-memory stream in tests, without changing any HTTP logic.
+
+use std::io::ErrorKind;
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use super::ReadWrite;
+
+/// Opens the connection a request is sent over.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use std::time::Duration;
+/// use clienter::{Connector, HttpClient, HttpMethod, ReadWrite, TcpConnector};
+///
+/// /// Sends every request to a fixed gateway, whatever its host.
+/// struct Gateway(String);
+///
+/// impl Connector for Gateway {
+///     fn connect(&self, _addr: &str, timeout: Option<Duration>) -> std::io::Result<Box<dyn ReadWrite>> {
+///         TcpConnector.connect(&self.0, timeout)
+///     }
+/// }
+///
+/// let mut client = HttpClient::new();
+/// client.connector = Some(Arc::new(Gateway("10.0.0.1:8080".to_string())));
+/// let request = client.request(HttpMethod::GET, "http://internal.example/status");
+/// let response = client.send(&request).unwrap();
+/// ```
+pub trait Connector: Send + Sync {
+    /// Opens a connection to a server.
+    ///
+    /// # Arguments
+    /// * `addr` - The server to connect to, as `host:port`
+    /// * `timeout` - How long connecting may take, or `None` for no limit
+    ///
+    /// # Returns
+    /// * `Ok(Box<dyn ReadWrite>)` - The connected stream
+    /// * `Err(std::io::Error)` - If the connection could not be established
+    fn connect(&self, addr: &str, timeout: Option<Duration>)
+        -> std::io::Result<Box<dyn ReadWrite>>;
+}
+
+/// Connects directly over TCP, trying each resolved address in turn.
+///
+/// `HttpClient` connects the same way when no connector is set, and in addition
+/// resolves through its DNS cache (`dns_cache_ttl`), skips addresses that don't match
+/// its `address_family`, and applies `tcp_keepalive` and `tcp_linger`. Its
+/// `read_timeout`, `total_timeout` and cancellation apply to connections from either.
+/// This can be used by custom connectors that need to reach a proxy first.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct TcpConnector;
+
+impl TcpConnector {
+    /// Connects to each of `addrs` in turn until a connection is established.
+    ///
+    /// # Arguments
+    /// * `addrs` - The addresses to try, in order
+    /// * `timeout` - How long each attempt may take, or `None` for no limit
+    ///
+    /// # Returns
+    /// * `Ok((SocketAddr, TcpStream))` - The address connected to, and the connection
+    /// * `Err((SocketAddr, std::io::Error))` - The last address tried and why it failed,
+    ///   or `ErrorKind::NotFound` with an unspecified address if `addrs` is empty
+    pub(crate) fn connect_any(
+        addrs: &[SocketAddr],
+        timeout: Option<Duration>,
+    ) -> Result<(SocketAddr, TcpStream), (SocketAddr, std::io::Error)> {
+        let mut last_error = (
+            SocketAddr::from(([0, 0, 0, 0], 0)),
+            std::io::Error::new(ErrorKind::NotFound, "address resolved to nothing"),
+        );
+        for &addr in addrs {
+            let stream = match timeout {
+                Some(timeout) => TcpStream::connect_timeout(&addr, timeout),
+                None => TcpStream::connect(addr),
+            };
+            match stream {
+                Ok(stream) => return Ok((addr, stream)),
+                Err(err) => last_error = (addr, err),
+            }
+        }
+        Err(last_error)
+    }
+}
+
+impl Connector for TcpConnector {
+    fn connect(
+        &self,
+        addr: &str,
+        timeout: Option<Duration>,
+    ) -> std::io::Result<Box<dyn ReadWrite>> {
+        let addrs: Vec<SocketAddr> = addr.to_socket_addrs()?.collect();
+        match TcpConnector::connect_any(&addrs, timeout) {
+            Ok((_, stream)) => Ok(Box::new(stream)),
+            Err((_, err)) => Err(err),
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Cookie parsing for `Set-Cookie` response headers.
+//!
+//! This module provides a structured representation of a cookie as sent by a server,
+//! including its attributes, as described in RFC 6265.
+//!
+//! # Example
+//! ```
+//! use clienter::{Cookie, SameSite};
+//!
+//! let cookie: Cookie = "session=abc123; Path=/; HttpOnly; SameSite=Lax".parse().unwrap();
+//! assert_eq!(cookie.name, "session");
+//! assert_eq!(cookie.value, "abc123");
+//! assert_eq!(cookie.path.as_deref(), Some("/"));
+//! assert!(cookie.http_only);
+//! assert_eq!(cookie.same_site, Some(SameSite::Lax));
+//! ```
+
+use std::str::FromStr;
+
+use crate::utils;
+
+/// The `SameSite` attribute of a cookie.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SameSite {
+    /// The cookie is only sent with same-site requests
+    Strict,
+    /// The cookie is sent with same-site requests and top-level navigations
+    Lax,
+    /// The cookie is sent with all requests
+    None,
+}
+
+impl FromStr for SameSite {
+    type Err = ();
+
+    /// Converts an attribute value into a `SameSite`, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("strict") {
+            Ok(SameSite::Strict)
+        } else if s.eq_ignore_ascii_case("lax") {
+            Ok(SameSite::Lax)
+        } else if s.eq_ignore_ascii_case("none") {
+            Ok(SameSite::None)
+        } else {
+            Err(())
+        }
+    }
+}
+
+/// A cookie as described by a `Set-Cookie` header.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Cookie {
+    /// The cookie name
+    pub name: String,
+    /// The cookie value
+    pub value: String,
+    /// The `Domain` attribute, without a leading dot
+    pub domain: Option<String>,
+    /// The `Path` attribute
+    pub path: Option<String>,
+    /// Whether the `Secure` attribute was present
+    pub secure: bool,
+    /// Whether the `HttpOnly` attribute was present
+    pub http_only: bool,
+    /// The `SameSite` attribute
+    pub same_site: Option<SameSite>,
+    /// The raw `Expires` attribute date
+    pub expires: Option<String>,
+    /// The `Max-Age` attribute in seconds
+    pub max_age: Option<i64>,
+}
+
+impl FromStr for Cookie {
+    type Err = ();
+
+    /// Parses the value of a `Set-Cookie` header.
+    ///
+    /// Attribute names are matched case-insensitively and unknown or malformed
+    /// attributes are ignored.
+    ///
+    /// # Returns
+    /// * `Ok(Cookie)` - If the header contains a `name=value` pair with a non-empty name
+    /// * `Err(())` - If the name-value pair is missing or the name is empty
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(';');
+
+        let (name, value) = utils::tuple_split(parts.next().ok_or(())?, "=").ok_or(())?;
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(());
+        }
+
+        let mut cookie = Cookie {
+            name: name.to_string(),
+            value: value.trim().to_string(),
+            domain: None,
+            path: None,
+            secure: false,
+            http_only: false,
+            same_site: None,
+            expires: None,
+            max_age: None,
+        };
+
+        for attribute in parts {
+            let (key, value) = match utils::tuple_split(attribute, "=") {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => (attribute.trim(), ""),
+            };
+
+            if key.eq_ignore_ascii_case("domain") {
+                cookie.domain = Some(value.trim_start_matches('.').to_string());
+            } else if key.eq_ignore_ascii_case("path") {
+                cookie.path = Some(value.to_string());
+            } else if key.eq_ignore_ascii_case("secure") {
+                cookie.secure = true;
+            } else if key.eq_ignore_ascii_case("httponly") {
+                cookie.http_only = true;
+            } else if key.eq_ignore_ascii_case("samesite") {
+                cookie.same_site = value.parse().ok();
+            } else if key.eq_ignore_ascii_case("expires") {
+                cookie.expires = Some(value.to_string());
+            } else if key.eq_ignore_ascii_case("max-age") {
+                cookie.max_age = value.parse().ok();
+            }
+        }
+
+        Ok(cookie)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cookie_attributes() {
+        let cookie: Cookie = "id=a3fWa; Expires=Thu, 21 Oct 2021 07:28:00 GMT; Max-Age=3600; \
+                              Domain=.example.com; Path=/docs; Secure; HttpOnly; SameSite=Strict"
+            .parse()
+            .unwrap();
+        assert_eq!(cookie.name, "id");
+        assert_eq!(cookie.value, "a3fWa");
+        assert_eq!(
+            cookie.expires.as_deref(),
+            Some("Thu, 21 Oct 2021 07:28:00 GMT")
+        );
+        assert_eq!(cookie.max_age, Some(3600));
+        assert_eq!(cookie.domain.as_deref(), Some("example.com"));
+        assert_eq!(cookie.path.as_deref(), Some("/docs"));
+        assert!(cookie.secure);
+        assert!(cookie.http_only);
+        assert_eq!(cookie.same_site, Some(SameSite::Strict));
+    }
+
+    #[test]
+    fn test_cookie_attributes_case_insensitive() {
+        let cookie: Cookie = "a=b; secure; HTTPONLY; samesite=none; PATH=/"
+            .parse()
+            .unwrap();
+        assert!(cookie.secure);
+        assert!(cookie.http_only);
+        assert_eq!(cookie.same_site, Some(SameSite::None));
+        assert_eq!(cookie.path.as_deref(), Some("/"));
+    }
+
+    #[test]
+    fn test_cookie_errors() {
+        assert_eq!("".parse::<Cookie>(), Err(()));
+        assert_eq!("novalue".parse::<Cookie>(), Err(()));
+        assert_eq!("=value".parse::<Cookie>(), Err(()));
+    }
+}
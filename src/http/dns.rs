@@ -0,0 +1,140 @@
+//! Caching of resolved addresses.
+//!
+//! Resolving a host name can take longer than the rest of a short request, so a client
+//! that talks to the same hosts repeatedly can keep the addresses it resolved for a
+//! while, see `HttpClient::dns_cache_ttl`. The cache is shared by a client and its
+//! clones.
+
+use std::collections::HashMap;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Addresses resolved for a `host:port`, and when they stop being used.
+struct CachedAddrs {
+    addrs: Vec<SocketAddr>,
+    expires: Instant,
+}
+
+/// Resolved addresses keyed by the `host:port` they were resolved for.
+#[derive(Default)]
+pub(crate) struct DnsCache {
+    entries: Mutex<HashMap<String, CachedAddrs>>,
+}
+
+impl DnsCache {
+    /// Resolves `addr`, reusing the addresses resolved for it within the last `ttl`.
+    ///
+    /// # Arguments
+    /// * `addr` - The `host:port` to resolve
+    /// * `ttl` - How long resolved addresses are reused, or `None` to always resolve
+    ///   without touching the cache
+    ///
+    /// # Returns
+    /// * `Ok(Vec<SocketAddr>)` - The addresses, in the order the resolver returned them
+    /// * `Err(std::io::Error)` - If resolution failed, which is never cached
+    pub(crate) fn resolve(
+        &self,
+        addr: &str,
+        ttl: Option<Duration>,
+    ) -> std::io::Result<Vec<SocketAddr>> {
+        let Some(ttl) = ttl else {
+            return Ok(addr.to_socket_addrs()?.collect());
+        };
+
+        let now = Instant::now();
+        if let Some(cached) = self.entries.lock().unwrap().get(addr) {
+            if cached.expires > now {
+                return Ok(cached.addrs.clone());
+            }
+        }
+
+        // Resolve without holding the lock, so other hosts aren't held up
+        let addrs: Vec<SocketAddr> = addr.to_socket_addrs()?.collect();
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, cached| cached.expires > now);
+        entries.insert(
+            addr.to_string(),
+            CachedAddrs {
+                addrs: addrs.clone(),
+                expires: now + ttl,
+            },
+        );
+        Ok(addrs)
+    }
+
+    /// Forgets the addresses cached for `addr`, so the next request resolves it again.
+    pub(crate) fn invalidate(&self, addr: &str) {
+        self.entries.lock().unwrap().remove(addr);
+    }
+
+    /// Forgets every cached address.
+    pub(crate) fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    /// Returns whether addresses are cached for `addr`, expired or not.
+    #[cfg(test)]
+    fn contains(&self, addr: &str) -> bool {
+        self.entries.lock().unwrap().contains_key(addr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_without_ttl_nothing_is_cached() {
+        let cache = DnsCache::default();
+        let addrs = cache.resolve("127.0.0.1:80", None).unwrap();
+        assert_eq!(addrs, ["127.0.0.1:80".parse().unwrap()]);
+        assert!(!cache.contains("127.0.0.1:80"));
+    }
+
+    #[test]
+    fn test_cached_until_invalidated() {
+        let cache = DnsCache::default();
+        let ttl = Some(Duration::from_secs(60));
+        cache.resolve("127.0.0.1:80", ttl).unwrap();
+        assert!(cache.contains("127.0.0.1:80"));
+
+        cache.invalidate("127.0.0.1:80");
+        assert!(!cache.contains("127.0.0.1:80"));
+
+        cache.resolve("127.0.0.1:80", ttl).unwrap();
+        cache.resolve("[::1]:80", ttl).unwrap();
+        cache.clear();
+        assert!(!cache.contains("127.0.0.1:80"));
+        assert!(!cache.contains("[::1]:80"));
+    }
+
+    #[test]
+    fn test_expired_entries_are_replaced() {
+        let cache = DnsCache::default();
+        cache.entries.lock().unwrap().insert(
+            "127.0.0.1:80".to_string(),
+            CachedAddrs {
+                addrs: vec!["10.0.0.1:80".parse().unwrap()],
+                expires: Instant::now(),
+            },
+        );
+        let addrs = cache
+            .resolve("127.0.0.1:80", Some(Duration::from_secs(60)))
+            .unwrap();
+        assert_eq!(addrs, ["127.0.0.1:80".parse().unwrap()]);
+
+        // A fresh entry is used as is
+        cache.entries.lock().unwrap().insert(
+            "127.0.0.1:80".to_string(),
+            CachedAddrs {
+                addrs: vec!["10.0.0.1:80".parse().unwrap()],
+                expires: Instant::now() + Duration::from_secs(60),
+            },
+        );
+        let addrs = cache
+            .resolve("127.0.0.1:80", Some(Duration::from_secs(60)))
+            .unwrap();
+        assert_eq!(addrs, ["10.0.0.1:80".parse().unwrap()]);
+    }
+}
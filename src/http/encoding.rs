@@ -0,0 +1,126 @@
+//! Content codings used for `Accept-Encoding` and `Content-Encoding`.
+//!
+//! This module provides an enumeration of the content codings a client can negotiate
+//! with a server, as registered in RFC 9110 section 8.4.1.
+
+use std::str::FromStr;
+
+/// A content coding applied to a response body.
+///
+/// # Examples
+///
+/// ```
+/// use clienter::Encoding;
+///
+/// assert_eq!(Encoding::Brotli.to_string(), "br");
+/// assert_eq!("GZIP".parse::<Encoding>(), Ok(Encoding::Gzip));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Encoding {
+    /// The gzip file format of RFC 1952
+    Gzip,
+    /// The zlib format of RFC 1950 containing a deflate stream
+    Deflate,
+    /// The Brotli format of RFC 7932
+    Brotli,
+    /// No coding, the body is sent as is
+    Identity,
+}
+
+impl Encoding {
+    /// Returns the token used for this coding in HTTP headers.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+            Encoding::Brotli => "br",
+            Encoding::Identity => "identity",
+        }
+    }
+}
+
+impl std::fmt::Display for Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = ();
+
+    /// Converts a coding token into an Encoding, ignoring ASCII case.
+    ///
+    /// The legacy `x-gzip` alias is accepted for gzip.
+    ///
+    /// # Returns
+    /// * `Ok(Encoding)` - If the token names a known coding
+    /// * `Err(())` - If the coding is not supported
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("gzip") || s.eq_ignore_ascii_case("x-gzip") {
+            Ok(Encoding::Gzip)
+        } else if s.eq_ignore_ascii_case("deflate") {
+            Ok(Encoding::Deflate)
+        } else if s.eq_ignore_ascii_case("br") {
+            Ok(Encoding::Brotli)
+        } else if s.eq_ignore_ascii_case("identity") {
+            Ok(Encoding::Identity)
+        } else {
+            Err(())
+        }
+    }
+}
+
+/// Builds an `Accept-Encoding` header value from codings and their quality values.
+///
+/// Quality values are clamped to `0.0..=1.0` and written with at most three decimals.
+/// A quality of `1` is the default and is left out.
+///
+/// # Arguments
+/// * `encodings` - The codings in order of preference, each with its quality value
+///
+/// # Returns
+/// The header value, such as `gzip, deflate;q=0.5`
+pub(crate) fn accept_encoding_value(encodings: &[(Encoding, f32)]) -> String {
+    encodings
+        .iter()
+        .map(|(encoding, quality)| {
+            let quality = format!("{:.3}", quality.clamp(0.0, 1.0));
+            let quality = quality.trim_end_matches('0').trim_end_matches('.');
+            if quality == "1" {
+                encoding.to_string()
+            } else {
+                format!("{};q={}", encoding, quality)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encoding_from_str() {
+        assert_eq!("gzip".parse::<Encoding>(), Ok(Encoding::Gzip));
+        assert_eq!("x-gzip".parse::<Encoding>(), Ok(Encoding::Gzip));
+        assert_eq!("Deflate".parse::<Encoding>(), Ok(Encoding::Deflate));
+        assert_eq!(" br ".parse::<Encoding>(), Ok(Encoding::Brotli));
+        assert_eq!("identity".parse::<Encoding>(), Ok(Encoding::Identity));
+        assert_eq!("compress".parse::<Encoding>(), Err(()));
+    }
+
+    #[test]
+    fn test_accept_encoding_value() {
+        let value = accept_encoding_value(&[
+            (Encoding::Gzip, 1.0),
+            (Encoding::Deflate, 0.5),
+            (Encoding::Identity, 0.1),
+        ]);
+        assert_eq!(value, "gzip, deflate;q=0.5, identity;q=0.1");
+
+        let value = accept_encoding_value(&[(Encoding::Brotli, 2.0), (Encoding::Gzip, 0.0)]);
+        assert_eq!(value, "br, gzip;q=0");
+    }
+}
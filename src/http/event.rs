@@ -0,0 +1,79 @@
+//! Lifecycle events emitted while a request is performed.
+//!
+//! A client can be given a callback through `HttpClient::on_event` to observe each
+//! phase of a request along with how long it took to reach it, which is useful for
+//! breaking down where the time of a slow request went.
+//!
+//! # Example
+//! ```
+//! use std::sync::Arc;
+//! use clienter::{ClientEvent, HttpClient};
+//!
+//! let mut client = HttpClient::new();
+//! client.on_event = Some(Arc::new(|event: ClientEvent| {
+//!     println!("{:?} after {:?}", event, event.elapsed());
+//! }));
+//! ```
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use super::HeaderMergeReport;
+
+/// A callback invoked with each lifecycle event of a request.
+pub type EventHook = Arc<dyn Fn(ClientEvent) + Send + Sync>;
+
+/// A phase of a request's lifecycle.
+///
+/// Every event carries the time elapsed since the request started.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ClientEvent {
+    /// The host name was resolved to the address that will be connected to
+    DnsResolved {
+        /// The address selected for the connection
+        addr: SocketAddr,
+        /// Time elapsed since the request started
+        elapsed: Duration,
+    },
+    /// The TCP connection to the server was established
+    Connected {
+        /// Time elapsed since the request started
+        elapsed: Duration,
+    },
+    /// The client's default headers were merged with the request's, reported only
+    /// when `HttpClient::report_header_merge` is set
+    HeadersMerged {
+        /// Which headers came from where
+        report: HeaderMergeReport,
+        /// Time elapsed since the request started
+        elapsed: Duration,
+    },
+    /// The request line, headers and body were written to the stream
+    RequestSent {
+        /// Time elapsed since the request started
+        elapsed: Duration,
+    },
+    /// The first byte of the response was received
+    FirstByteReceived {
+        /// Time elapsed since the request started
+        elapsed: Duration,
+    },
+    /// The response body was read in full
+    ResponseComplete {
+        /// Time elapsed since the request started
+        elapsed: Duration,
+    },
+}
+
+impl ClientEvent {
+    /// Returns the time elapsed between the start of the request and this event.
+    pub fn elapsed(&self) -> Duration {
+        match self {
+            ClientEvent::DnsResolved { elapsed, .. }
+            | ClientEvent::HeadersMerged { elapsed, .. }
+            | ClientEvent::Connected { elapsed }
+            | ClientEvent::RequestSent { elapsed }
+            | ClientEvent::FirstByteReceived { elapsed }
+            | ClientEvent::ResponseComplete { elapsed } => *elapsed,
+        }
+    }
+}
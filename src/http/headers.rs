@@ -1,7 +1,14 @@
 //! HTTP headers implementation for managing request and response headers.
 //!
 //! This module provides a container for HTTP headers with convenience methods
-//! for setting common headers and combining header sets.
+//! for setting common headers and combining header sets. Headers keep the order
+//! they were added in, and a header name may appear more than once (as is common
+//! with `Set-Cookie`).
+//!
+//! Header names are case-insensitive (RFC 7230 section 3.2): `get("content-type")`
+//! finds a `Content-Type` header. Names are compared in place with
+//! `eq_ignore_ascii_case`, so lookups don't allocate, and keep the case they were
+//! added with when written out.
 //!
 //! # Example
 //! ```
@@ -18,43 +25,235 @@ use std::collections::HashMap;
 /// managing and manipulating HTTP header fields.
 #[derive(Debug, PartialEq, Clone)]
 pub struct HttpHeaders {
-    /// Internal storage for header key-value pairs
-    data: HashMap<String, String>,
+    /// Internal storage for header key-value pairs, in insertion order
+    data: Vec<(String, String)>,
+    /// Names passed to `remove`, which suppress defaults when combining
+    removed: Vec<String>,
+}
+
+/// Where each header of a combined set came from, see `HttpHeaders::merge_report`.
+///
+/// Each list holds header names once, in the order they are first found, spelled as in
+/// the set whose value is sent.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct HeaderMergeReport {
+    /// Defaults that are sent because the other set doesn't have them
+    pub from_defaults: Vec<String>,
+    /// Headers of the other set that have no default
+    pub from_request: Vec<String>,
+    /// Headers of the other set that replace a default of the same name
+    pub overridden: Vec<String>,
+    /// Defaults left out because they were removed from the other set
+    pub suppressed: Vec<String>,
+}
+
+/// Adds `key` to `keys` unless a name equal to it, ignoring case, is already there.
+fn push_unique(keys: &mut Vec<String>, key: &str) {
+    if !keys.iter().any(|k| k.eq_ignore_ascii_case(key)) {
+        keys.push(key.to_string());
+    }
 }
 
 impl HttpHeaders {
     /// Creates a new empty headers container.
     pub fn new() -> Self {
         HttpHeaders {
-            data: HashMap::new(),
+            data: Vec::new(),
+            removed: Vec::new(),
+        }
+    }
+
+    /// Creates the headers a browser navigating to a page would send.
+    ///
+    /// Includes:
+    /// * `User-Agent: Clienter/1.0 (Rust)`
+    /// * `Accept: */*`
+    /// * `Accept-Language: en-US`
+    /// * `Accept-Encoding: gzip`
+    /// * `Connection: keep-alive`
+    /// * `Upgrade-Insecure-Requests: 1`
+    /// * `Sec-Fetch-Dest: document`
+    ///
+    /// This is the `Default` for `HttpHeaders`, and so the default headers of `HttpClient`.
+    pub fn browser_like() -> Self {
+        HttpHeaders {
+            data: Vec::from([
+                ("User-Agent".to_string(), "Clienter/1.0 (Rust)".to_string()),
+                ("Accept".to_string(), "*/*".to_string()),
+                ("Accept-Language".to_string(), "en-US".to_string()),
+                ("Accept-Encoding".to_string(), "gzip".to_string()),
+                ("Connection".to_string(), "keep-alive".to_string()),
+                ("Upgrade-Insecure-Requests".to_string(), "1".to_string()),
+                ("Sec-Fetch-Dest".to_string(), "document".to_string()),
+            ]),
+            removed: Vec::new(),
+        }
+    }
+
+    /// Creates the smallest useful set of headers.
+    ///
+    /// Includes only `User-Agent: Clienter/1.0 (Rust)`. The `Host` header is always
+    /// added when a request is sent, so requests carry just these two headers plus any
+    /// needed to frame the body.
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::{HttpClient, HttpHeaders};
+    ///
+    /// let mut client = HttpClient::new();
+    /// client.headers = HttpHeaders::minimal();
+    /// assert_eq!(client.default_headers().to_wire_string(), "User-Agent: Clienter/1.0 (Rust)\r\n");
+    /// ```
+    pub fn minimal() -> Self {
+        HttpHeaders {
+            data: Vec::from([("User-Agent".to_string(), "Clienter/1.0 (Rust)".to_string())]),
+            removed: Vec::new(),
+        }
+    }
+
+    /// Creates headers for talking to a JSON API.
+    ///
+    /// Includes:
+    /// * `User-Agent: Clienter/1.0 (Rust)`
+    /// * `Accept: application/json`
+    ///
+    /// No `Content-Type` is included, since most API requests have no body. Set one on
+    /// the request, or enable `HttpRequest::sniff_content_type`, when sending JSON.
+    pub fn api_json() -> Self {
+        HttpHeaders {
+            data: Vec::from([
+                ("User-Agent".to_string(), "Clienter/1.0 (Rust)".to_string()),
+                ("Accept".to_string(), "application/json".to_string()),
+            ]),
+            removed: Vec::new(),
         }
     }
 
     /// Combines two header sets, with the other set taking precedence for duplicate keys.
     ///
+    /// Every value of a key present in `other` replaces all values of that key in this set,
+    /// and keys removed from `other` with `remove` are left out.
+    /// When sending, the client's default headers are combined with the request's headers
+    /// as `defaults.combine(&request.headers)`, so the request's values win, and removing
+    /// a header from a request keeps the client's default for it from being sent.
+    ///
     /// # Parameters
     /// * `other` - Another headers container to merge with this one
     ///
     /// # Returns
     /// A new `HttpHeaders` instance containing the merged headers
     pub fn combine(&self, other: &HttpHeaders) -> HttpHeaders {
-        let mut data = self.data.clone();
-        for (key, value) in other.data.iter() {
-            data.insert(key.clone(), value.clone());
+        let mut data: Vec<(String, String)> = self
+            .data
+            .iter()
+            .filter(|(key, _)| other.get(key).is_none() && !other.was_removed(key))
+            .cloned()
+            .collect();
+        data.extend(other.data.iter().cloned());
+        HttpHeaders {
+            data,
+            removed: Vec::new(),
+        }
+    }
+
+    /// Explains how `combine` merges `other` into this set, without combining them.
+    ///
+    /// This shows which of a client's default headers a request's headers replaced, for
+    /// example when a custom header seems to have no effect. Set
+    /// `HttpClient::report_header_merge` to receive a report for each request sent.
+    ///
+    /// # Parameters
+    /// * `other` - The headers that take precedence, as for `combine`
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::HttpHeaders;
+    ///
+    /// let defaults = HttpHeaders::minimal();
+    /// let mut request = HttpHeaders::new();
+    /// request.set_user_agent("MyApp/1.0".to_string());
+    /// request.set_accept("*/*".to_string());
+    ///
+    /// let report = defaults.merge_report(&request);
+    /// assert_eq!(report.overridden, ["User-Agent"]);
+    /// assert_eq!(report.from_request, ["Accept"]);
+    /// assert!(report.from_defaults.is_empty());
+    /// ```
+    pub fn merge_report(&self, other: &HttpHeaders) -> HeaderMergeReport {
+        let mut report = HeaderMergeReport::default();
+        for (key, _) in &self.data {
+            if other.get(key).is_some() {
+                continue;
+            }
+            if other.was_removed(key) {
+                push_unique(&mut report.suppressed, key);
+            } else {
+                push_unique(&mut report.from_defaults, key);
+            }
         }
-        HttpHeaders { data }
+        for (key, _) in &other.data {
+            if self.get(key).is_some() {
+                push_unique(&mut report.overridden, key);
+            } else {
+                push_unique(&mut report.from_request, key);
+            }
+        }
+        report
     }
 
-    /// Inserts a header key-value pair into the container.
+    /// Inserts a header key-value pair into the container, replacing any existing values.
     ///
     /// # Parameters
     /// * `key` - The header field name
     /// * `value` - The header field value
     pub fn insert(&mut self, key: String, value: String) {
-        self.data.insert(key, value);
+        self.data.retain(|(k, _)| !k.eq_ignore_ascii_case(&key));
+        self.removed.retain(|k| !k.eq_ignore_ascii_case(&key));
+        self.data.push((key, value));
+    }
+
+    /// Appends a header key-value pair, keeping any existing values for the same key.
+    ///
+    /// # Parameters
+    /// * `key` - The header field name
+    /// * `value` - The header field value
+    pub fn append(&mut self, key: String, value: String) {
+        self.removed.retain(|k| !k.eq_ignore_ascii_case(&key));
+        self.data.push((key, value));
+    }
+
+    /// Removes every value of a header.
+    ///
+    /// The removal is remembered until the header is added again, so that `combine`
+    /// leaves out the header from the set being combined with. Removing `User-Agent`
+    /// from a request's headers therefore sends the request without one, even though
+    /// the client has a default.
+    ///
+    /// # Parameters
+    /// * `key` - The header field name to remove
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::HttpHeaders;
+    ///
+    /// let defaults = HttpHeaders::minimal();
+    /// let mut headers = HttpHeaders::new();
+    /// headers.remove("user-agent");
+    /// assert_eq!(defaults.combine(&headers).get("User-Agent"), None);
+    /// ```
+    pub fn remove(&mut self, key: &str) {
+        self.data.retain(|(k, _)| !k.eq_ignore_ascii_case(key));
+        if !self.was_removed(key) {
+            self.removed.push(key.to_string());
+        }
+    }
+
+    /// Determines whether a header was removed and not added again since.
+    fn was_removed(&self, key: &str) -> bool {
+        self.removed.iter().any(|k| k.eq_ignore_ascii_case(key))
     }
 
-    /// Retrieves the value of a header by its key.
+    /// Retrieves the first value of a header by its key.
     ///
     /// # Parameters
     /// * `key` - The header field name to look up
@@ -62,7 +261,57 @@ impl HttpHeaders {
     /// # Returns
     /// An Option containing a reference to the header value if it exists
     pub fn get(&self, key: &str) -> Option<&String> {
-        self.data.get(key)
+        self.data
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+    }
+
+    /// Retrieves the first value of a header by its key as a string slice.
+    ///
+    /// # Parameters
+    /// * `key` - The header field name to look up, in any case
+    ///
+    /// # Returns
+    /// The header value if it exists
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::HttpHeaders;
+    ///
+    /// let mut headers = HttpHeaders::new();
+    /// headers.set_accept("text/html".to_string());
+    /// assert_eq!(headers.get_str("accept"), Some("text/html"));
+    /// ```
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.get(key).map(|value| value.as_str())
+    }
+
+    /// Retrieves every value of a header by its key, in the order they were added.
+    ///
+    /// # Parameters
+    /// * `key` - The header field name to look up
+    ///
+    /// # Returns
+    /// A Vec of references to the header values, empty if the header is absent
+    pub fn get_all(&self, key: &str) -> Vec<&String> {
+        self.data
+            .iter()
+            .filter(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v)
+            .collect()
+    }
+
+    /// Determines whether any `Connection` header lists `option`, such as `close`.
+    ///
+    /// Every `Connection` header is split into its comma-separated options, which are
+    /// compared case-insensitively.
+    pub(crate) fn has_connection_option(&self, option: &str) -> bool {
+        self.get_all("Connection").into_iter().any(|value| {
+            value
+                .split(',')
+                .any(|token| token.trim().eq_ignore_ascii_case(option))
+        })
     }
 
     /// Sets the Host header.
@@ -85,48 +334,286 @@ impl HttpHeaders {
         self.insert("Accept-Language".to_string(), accept_language);
     }
 
+    /// Sets the Accept-Charset header.
+    ///
+    /// None of the presets include it, as browsers no longer send it and most
+    /// servers ignore it.
+    pub fn set_accept_charset(&mut self, accept_charset: String) {
+        self.insert("Accept-Charset".to_string(), accept_charset);
+    }
+
     /// Sets the Accept-Encoding header.
     pub fn set_accept_encoding(&mut self, accept_encoding: String) {
         self.insert("Accept-Encoding".to_string(), accept_encoding);
     }
 
+    /// Adds a client address to the X-Forwarded-For header.
+    ///
+    /// Each proxy a request passes through appends the address it received the request
+    /// from, so the address is added to the end of an existing list instead of
+    /// replacing it. Several X-Forwarded-For headers are joined into one.
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::HttpHeaders;
+    ///
+    /// let mut headers = HttpHeaders::new();
+    /// headers.set_forwarded_for("203.0.113.7");
+    /// headers.set_forwarded_for("10.0.0.2");
+    /// assert_eq!(headers.get_str("X-Forwarded-For"), Some("203.0.113.7, 10.0.0.2"));
+    /// ```
+    pub fn set_forwarded_for(&mut self, ip: &str) {
+        self.append_to_list("X-Forwarded-For", ip);
+    }
+
+    /// Adds an element to the standard Forwarded header (RFC 7239).
+    ///
+    /// As with `set_forwarded_for`, the element is appended to any existing ones. It is
+    /// added as given, so IPv6 addresses and values with special characters must be
+    /// quoted, as in `for="[2001:db8::1]"`.
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::HttpHeaders;
+    ///
+    /// let mut headers = HttpHeaders::new();
+    /// headers.set_forwarded("for=192.0.2.60;proto=http;by=203.0.113.43");
+    /// headers.set_forwarded(r#"for="[2001:db8::1]""#);
+    /// assert_eq!(
+    ///     headers.get_str("Forwarded"),
+    ///     Some(r#"for=192.0.2.60;proto=http;by=203.0.113.43, for="[2001:db8::1]""#)
+    /// );
+    /// ```
+    pub fn set_forwarded(&mut self, value: &str) {
+        self.append_to_list("Forwarded", value);
+    }
+
+    /// Adds an element to the comma-separated list held by every `key` header.
+    fn append_to_list(&mut self, key: &str, element: &str) {
+        let mut elements: Vec<&str> = self
+            .get_all(key)
+            .into_iter()
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+            .collect();
+        elements.push(element.trim());
+        let value = elements.join(", ");
+        self.insert(key.to_string(), value);
+    }
+
+    /// Renders the headers as they are written on the wire.
+    ///
+    /// Each header becomes a `Key: Value\r\n` line, in the order the headers were added.
+    /// The blank line that ends a header block is not included, so callers control framing.
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::HttpHeaders;
+    ///
+    /// let mut headers = HttpHeaders::new();
+    /// headers.set_host("example.com".to_string());
+    /// headers.set_accept("*/*".to_string());
+    /// assert_eq!(headers.to_wire_string(), "Host: example.com\r\nAccept: */*\r\n");
+    /// ```
+    pub fn to_wire_string(&self) -> String {
+        self.data
+            .iter()
+            .map(|(key, value)| format!("{}: {}\r\n", key, value))
+            .collect()
+    }
+
     /// Returns an iterator over the header key-value pairs.
-    pub fn iter(&self) -> std::collections::hash_map::Iter<String, String> {
-        self.data.iter()
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.into_iter()
     }
 }
 
-/// Provides default headers commonly used in HTTP requests.
+/// Provides the `browser_like` preset, which clients use unless configured otherwise.
 impl Default for HttpHeaders {
     fn default() -> Self {
-        HttpHeaders {
-            data: HashMap::from([
-                ("User-Agent".to_string(), "Clienter/1.0 (Rust)".to_string()),
-                ("Accept".to_string(), "*/*".to_string()),
-                ("Accept-Language".to_string(), "en-US".to_string()),
-                ("Accept-Encoding".to_string(), "gzip".to_string()),
-                ("Connection".to_string(), "keep-alive".to_string()),
-                ("Upgrade-Insecure-Requests".to_string(), "1".to_string()),
-                ("Sec-Fetch-Dest".to_string(), "document".to_string()),
-                ("Host".to_string(), "localhost".to_string()),
-            ]),
-        }
+        Self::browser_like()
     }
 }
 
 /// Allows creation of HttpHeaders from a HashMap.
 impl From<HashMap<String, String>> for HttpHeaders {
     fn from(data: HashMap<String, String>) -> Self {
-        HttpHeaders { data }
+        HttpHeaders {
+            data: data.into_iter().collect(),
+            removed: Vec::new(),
+        }
     }
 }
 
 /// Enables iteration over header key-value pairs.
 impl IntoIterator for HttpHeaders {
     type Item = (String, String);
-    type IntoIter = std::collections::hash_map::IntoIter<String, String>;
+    type IntoIter = std::vec::IntoIter<(String, String)>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.data.into_iter()
     }
 }
+
+/// Enables borrowing iteration over header key-value pairs, as `iter` does.
+///
+/// # Example
+/// ```
+/// use clienter::HttpHeaders;
+///
+/// let headers = HttpHeaders::minimal();
+/// for (key, value) in &headers {
+///     assert_eq!(key, "User-Agent");
+///     assert_eq!(value, "Clienter/1.0 (Rust)");
+/// }
+/// ```
+impl<'a> IntoIterator for &'a HttpHeaders {
+    type Item = (&'a String, &'a String);
+    type IntoIter = std::iter::Map<
+        std::slice::Iter<'a, (String, String)>,
+        fn(&'a (String, String)) -> (&'a String, &'a String),
+    >;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.iter().map(|(k, v)| (k, v))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_report() {
+        let mut defaults = HttpHeaders::new();
+        defaults.set_user_agent("default".to_string());
+        defaults.append("Cookie".to_string(), "a=1".to_string());
+        defaults.append("cookie".to_string(), "b=2".to_string());
+        defaults.set_accept("*/*".to_string());
+        defaults.insert("X-Trace".to_string(), "on".to_string());
+
+        let mut request = HttpHeaders::new();
+        request.insert("user-agent".to_string(), "mine".to_string());
+        request.insert("X-Request-Id".to_string(), "1".to_string());
+        request.remove("X-Trace");
+
+        let report = defaults.merge_report(&request);
+        assert_eq!(report.from_defaults, ["Cookie", "Accept"]);
+        assert_eq!(report.from_request, ["X-Request-Id"]);
+        assert_eq!(report.overridden, ["user-agent"]);
+        assert_eq!(report.suppressed, ["X-Trace"]);
+    }
+
+    #[test]
+    fn test_forwarded_for_joins_existing_headers() {
+        let mut headers = HttpHeaders::new();
+        headers.append("x-forwarded-for".to_string(), "198.51.100.1".to_string());
+        headers.append("X-Forwarded-For".to_string(), " 198.51.100.2 ".to_string());
+        headers.set_forwarded_for("10.0.0.1");
+        assert_eq!(
+            headers.get_all("X-Forwarded-For"),
+            ["198.51.100.1, 198.51.100.2, 10.0.0.1"]
+        );
+    }
+
+    #[test]
+    fn test_set_accept_charset_replaces() {
+        let mut headers = HttpHeaders::new();
+        headers.set_accept_charset("iso-8859-1".to_string());
+        headers.set_accept_charset("utf-8, iso-8859-1;q=0.5".to_string());
+        assert_eq!(
+            headers.get_all("accept-charset"),
+            ["utf-8, iso-8859-1;q=0.5"]
+        );
+    }
+
+    #[test]
+    fn test_borrowed_iteration() {
+        let mut headers = HttpHeaders::new();
+        headers.append("Accept".to_string(), "a".to_string());
+        headers.append("Accept".to_string(), "b".to_string());
+
+        let mut seen = Vec::new();
+        for (key, value) in &headers {
+            seen.push(format!("{}={}", key, value));
+        }
+        assert_eq!(seen, ["Accept=a", "Accept=b"]);
+        assert!(headers.iter().eq(&headers));
+    }
+
+    #[test]
+    fn test_to_wire_string_keeps_order_and_repeats() {
+        let mut headers = HttpHeaders::new();
+        headers.insert("B".to_string(), "2".to_string());
+        headers.insert("A".to_string(), "1".to_string());
+        headers.append("B".to_string(), "3".to_string());
+        assert_eq!(headers.to_wire_string(), "B: 2\r\nA: 1\r\nB: 3\r\n");
+    }
+
+    #[test]
+    fn test_names_are_case_insensitive() {
+        let mut headers = HttpHeaders::new();
+        headers.insert("Content-Type".to_string(), "text/plain".to_string());
+        headers.append("set-cookie".to_string(), "a=1".to_string());
+        headers.append("SET-COOKIE".to_string(), "b=2".to_string());
+
+        assert_eq!(headers.get("content-type").unwrap(), "text/plain");
+        assert_eq!(headers.get_all("Set-Cookie"), ["a=1", "b=2"]);
+
+        headers.insert("CONTENT-TYPE".to_string(), "application/json".to_string());
+        assert_eq!(headers.get_all("Content-Type"), ["application/json"]);
+        assert_eq!(
+            headers.to_wire_string(),
+            "set-cookie: a=1\r\nSET-COOKIE: b=2\r\nCONTENT-TYPE: application/json\r\n"
+        );
+
+        headers.remove("Set-Cookie");
+        assert!(headers.get("set-cookie").is_none());
+    }
+
+    #[test]
+    fn test_combine_overrides_regardless_of_case() {
+        let mut defaults = HttpHeaders::new();
+        defaults.insert("User-Agent".to_string(), "Default".to_string());
+        let mut overrides = HttpHeaders::new();
+        overrides.insert("user-agent".to_string(), "Custom".to_string());
+
+        let combined = defaults.combine(&overrides);
+        assert_eq!(combined.get_all("User-Agent"), ["Custom"]);
+    }
+
+    #[test]
+    fn test_connection_options_are_found_in_any_header() {
+        let mut headers = HttpHeaders::new();
+        assert!(!headers.has_connection_option("close"));
+
+        headers.insert("Connection".to_string(), "Keep-Alive, Upgrade".to_string());
+        headers.append("connection".to_string(), " CLOSE ".to_string());
+        assert!(headers.has_connection_option("keep-alive"));
+        assert!(headers.has_connection_option("upgrade"));
+        assert!(headers.has_connection_option("close"));
+        assert!(!headers.has_connection_option("keep"));
+    }
+
+    #[test]
+    fn test_to_wire_string_empty() {
+        assert_eq!(HttpHeaders::new().to_wire_string(), "");
+    }
+
+    #[test]
+    fn test_presets() {
+        assert_eq!(HttpHeaders::default(), HttpHeaders::browser_like());
+        assert_eq!(
+            HttpHeaders::browser_like().get("Sec-Fetch-Dest").unwrap(),
+            "document"
+        );
+
+        let minimal = HttpHeaders::minimal();
+        assert_eq!(minimal.iter().count(), 1);
+        assert!(minimal.get("User-Agent").is_some());
+
+        let api = HttpHeaders::api_json();
+        assert_eq!(api.get("Accept").unwrap(), "application/json");
+        assert!(api.get("Sec-Fetch-Dest").is_none());
+    }
+}
@@ -2,6 +2,8 @@
 //!
 //! This module provides an enumeration of standard HTTP methods used in HTTP/1.1 requests.
 
+use std::str::FromStr;
+
 /// Represents standard HTTP methods as defined in RFC 7231.
 ///
 /// # Examples
@@ -42,6 +44,51 @@ pub enum HttpMethod {
     /// The TRACE method performs a message loop-back test along the path to
     /// the target resource.
     TRACE,
+    /// A method that is not one of the standard methods above, such as a WebDAV
+    /// `PROPFIND`, holding the method name as given
+    Other(String),
+}
+
+impl HttpMethod {
+    /// Returns whether the method is safe, meaning it is essentially read-only
+    /// (RFC 7231 section 4.2.1).
+    ///
+    /// # Returns
+    /// `true` for GET, HEAD, OPTIONS and TRACE. Unknown methods are not assumed to be safe.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clienter::HttpMethod;
+    ///
+    /// assert!(HttpMethod::GET.is_safe());
+    /// assert!(!HttpMethod::POST.is_safe());
+    /// ```
+    pub fn is_safe(&self) -> bool {
+        matches!(self, Self::GET | Self::HEAD | Self::OPTIONS | Self::TRACE)
+    }
+
+    /// Returns whether the method is idempotent, meaning sending the same request
+    /// several times has the same effect as sending it once (RFC 7231 section 4.2.2).
+    ///
+    /// Idempotent requests can safely be retried if the connection fails
+    /// before the response is read.
+    ///
+    /// # Returns
+    /// `true` for the safe methods, PUT and DELETE. Unknown methods are not assumed
+    /// to be idempotent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clienter::HttpMethod;
+    ///
+    /// assert!(HttpMethod::PUT.is_idempotent());
+    /// assert!(!HttpMethod::PATCH.is_idempotent());
+    /// ```
+    pub fn is_idempotent(&self) -> bool {
+        self.is_safe() || matches!(self, Self::PUT | Self::DELETE)
+    }
 }
 
 /// Implements string representation for HTTP methods.
@@ -60,7 +107,48 @@ impl std::fmt::Display for HttpMethod {
             Self::OPTIONS => "OPTIONS",
             Self::CONNECT => "CONNECT",
             Self::TRACE => "TRACE",
+            Self::Other(method) => method,
         };
         f.write_str(s)
     }
 }
+
+impl FromStr for HttpMethod {
+    type Err = ();
+
+    /// Converts a method name into an HttpMethod, ignoring ASCII case.
+    ///
+    /// # Arguments
+    /// * `s` - A method name such as "GET" or "post"
+    ///
+    /// # Returns
+    /// * `Ok(HttpMethod)` - If the name matches a standard method
+    /// * `Err(())` - If the name doesn't match any standard method. Construct
+    ///   `HttpMethod::Other` directly to use a nonstandard method.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clienter::HttpMethod;
+    ///
+    /// assert_eq!("GET".parse::<HttpMethod>(), Ok(HttpMethod::GET));
+    /// assert_eq!("delete".parse::<HttpMethod>(), Ok(HttpMethod::DELETE));
+    /// assert!("FETCH".parse::<HttpMethod>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            Self::GET,
+            Self::POST,
+            Self::PUT,
+            Self::DELETE,
+            Self::PATCH,
+            Self::HEAD,
+            Self::OPTIONS,
+            Self::CONNECT,
+            Self::TRACE,
+        ]
+        .into_iter()
+        .find(|method| method.to_string().eq_ignore_ascii_case(s))
+        .ok_or(())
+    }
+}
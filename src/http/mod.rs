@@ -3,18 +3,61 @@
 //! This module contains all the essential components needed to construct and handle
 //! HTTP requests and responses, including headers, methods, URIs, and status codes.
 
+/// IP address family preference for connections
+mod address_family;
+pub use address_family::AddressFamily;
+
+/// The host and port of a URI
+mod authority;
+pub use authority::Authority;
+
+/// `WWW-Authenticate` challenges
+mod auth;
+pub use auth::AuthChallenge;
+
+/// Request body representations
+mod body;
+pub use body::{Body, BodyReader};
+
+/// Cancellation of requests from another thread
+mod cancel;
+pub use cancel::CancelHandle;
+
 /// Client implementation for making HTTP requests
 mod client;
-pub use client::HttpClient;
+pub use client::{HttpClient, HttpError};
+
+/// Pluggable connection establishment
+mod connector;
+pub use connector::{Connector, TcpConnector};
+
+/// Cookies received via `Set-Cookie`
+mod cookie;
+pub use cookie::{Cookie, SameSite};
+
+/// Caching of resolved addresses
+mod dns;
+
+/// Content codings for `Accept-Encoding` and `Content-Encoding`
+mod encoding;
+pub use encoding::Encoding;
+
+/// Request lifecycle events
+mod event;
+pub use event::{ClientEvent, EventHook};
 
 /// HTTP headers management
 mod headers;
-pub use headers::HttpHeaders;
+pub use headers::{HeaderMergeReport, HttpHeaders};
 
 /// HTTP methods (GET, POST, etc.)
 mod method;
 pub use method::HttpMethod;
 
+/// Connection accounting
+mod pool;
+pub use pool::PoolStats;
+
 /// Protocol definitions (HTTP/1.1, HTTP/2)
 mod protocol;
 pub use protocol::Protocol;
@@ -25,12 +68,28 @@ pub use request::HttpRequest;
 
 /// HTTP response handling
 mod response;
-pub use response::HttpResponse;
+pub use response::{HttpResponse, ResponseError, MAX_HEADER_LINES};
+
+/// Request targets for the request line
+mod target;
+pub use target::RequestTarget;
+
+/// Transport abstraction over connected streams
+mod stream;
+pub use stream::ReadWrite;
 
 /// HTTP status codes and categories
 mod status_code;
 pub use status_code::StatusCode;
 
+/// HTTP versions for the request line
+mod version;
+pub use version::HttpVersion;
+
 /// URI parsing and manipulation
 mod uri;
-pub use uri::Uri;
+pub use uri::{Uri, UriError};
+
+/// Building URIs with percent-encoding
+mod uri_builder;
+pub use uri_builder::UriBuilder;
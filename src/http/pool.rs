@@ -0,0 +1,69 @@
+//! Connection accounting for diagnostics.
+//!
+//! The client keeps counters of the connections it opens so that connection usage
+//! can be inspected with `HttpClient::pool_stats`. Connections are not pooled yet:
+//! every request opens a new connection, which is closed once its response is
+//! dropped, so no connection is ever idle or reused.
+
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+/// A snapshot of the connections opened by a client.
+///
+/// # Example
+/// ```
+/// use clienter::HttpClient;
+///
+/// let client = HttpClient::new();
+/// let stats = client.pool_stats();
+/// assert_eq!(stats.created, 0);
+/// assert_eq!(stats.in_use, 0);
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct PoolStats {
+    /// Open connections waiting to be reused, always 0 until pooling is supported
+    pub idle: usize,
+    /// Open connections held by a response that has not been dropped yet
+    pub in_use: usize,
+    /// Connections opened since the client was created
+    pub created: usize,
+    /// Requests sent on a previously used connection, always 0 until pooling is supported
+    pub reused: usize,
+}
+
+/// Counters shared by a client and its clones.
+#[derive(Debug, Default)]
+pub(crate) struct ConnectionCounters {
+    created: AtomicUsize,
+    in_use: AtomicUsize,
+}
+
+impl ConnectionCounters {
+    /// Records a newly opened connection, which stays in use until the guard is dropped.
+    pub(crate) fn open(self: &Arc<Self>) -> ConnectionGuard {
+        self.created.fetch_add(1, Ordering::Relaxed);
+        self.in_use.fetch_add(1, Ordering::Relaxed);
+        ConnectionGuard(self.clone())
+    }
+
+    /// Returns the current values of the counters.
+    pub(crate) fn stats(&self) -> PoolStats {
+        PoolStats {
+            idle: 0,
+            in_use: self.in_use.load(Ordering::Relaxed),
+            created: self.created.load(Ordering::Relaxed),
+            reused: 0,
+        }
+    }
+}
+
+/// Marks a connection as in use for as long as it is alive.
+pub(crate) struct ConnectionGuard(Arc<ConnectionCounters>);
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.0.in_use.fetch_sub(1, Ordering::Relaxed);
+    }
+}
@@ -47,13 +47,25 @@ impl Protocol {
 
     /// Returns the HTTP version string for the protocol
     ///
+    /// The crate only speaks HTTP/1.x framing, so both schemes use HTTP/1.1. HTTP/2
+    /// is a binary protocol negotiated during the TLS handshake, not something a
+    /// request line can announce. The version actually sent is `HttpRequest::http_version`.
+    ///
     /// # Returns
-    /// * "HTTP/1.1" for HTTP
-    /// * "HTTP/2" for HTTPS
+    /// * "HTTP/1.1" for both HTTP and HTTPS
     pub fn get_http_version(&self) -> &'static str {
         match self {
-            Protocol::HTTP => "HTTP/1.1",
-            Protocol::HTTPS => "HTTP/2",
+            Protocol::HTTP | Protocol::HTTPS => "HTTP/1.1",
+        }
+    }
+}
+
+impl std::fmt::Display for Protocol {
+    /// Formats the protocol as its URI scheme, "http" or "https".
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Protocol::HTTP => f.write_str("http"),
+            Protocol::HTTPS => f.write_str("https"),
         }
     }
 }
@@ -3,27 +3,57 @@
 //! This module contains the core `HttpRequest` struct and its implementations for
 //! handling HTTP requests in a type-safe manner.
 
+use std::io::Read;
+
+use super::body::{Body, BodyReader};
+use super::cancel::CancelHandle;
 use super::headers::HttpHeaders;
 use super::method::HttpMethod;
+use super::target::RequestTarget;
 use super::uri::Uri;
+use super::version::HttpVersion;
+use crate::utils;
 
 /// Represents an HTTP request with its components.
 ///
 /// # Fields
 /// * `method` - The HTTP method (GET, POST, etc.)
 /// * `uri` - The target URI of the request
+/// * `http_version` - The HTTP version sent in the request line
 /// * `headers` - HTTP headers associated with the request
 /// * `timeout` - Optional timeout duration for the request
+/// * `body` - Optional body sent after the headers
+/// * `sniff_content_type` - Whether to guess a missing `Content-Type` from the body
+/// * `absolute_form` - Whether the request line carries the whole URI
+/// * `cancel` - Optional handle that aborts the request when cancelled
+/// * `host_override` - Optional `Host` header value that differs from the URI's host
+/// * `raw_override` - Optional bytes written in place of the serialized request
 #[derive(Debug, PartialEq, Clone)]
 pub struct HttpRequest {
     /// The HTTP method to be used for this request
     pub method: HttpMethod,
     /// The target URI for this request
     pub uri: Uri,
-    /// Headers to be sent with this request
+    /// The HTTP version sent in the request line, HTTP/1.1 unless changed
+    pub http_version: HttpVersion,
+    /// Headers specific to this request, which override the client's default headers
     pub headers: HttpHeaders,
     /// Optional timeout duration for this request
     pub timeout: Option<std::time::Duration>,
+    /// Optional body to be sent with this request
+    pub body: Option<Body>,
+    /// Whether a `Content-Type` should be guessed from the body when none is set
+    pub sniff_content_type: bool,
+    /// Whether the request line carries the whole URI, as requests sent through a
+    /// forward proxy must, see `RequestTarget`
+    pub absolute_form: bool,
+    /// The handle that aborts the request, including reading its response, when
+    /// cancelled, see `cancel_on`
+    pub cancel: Option<CancelHandle>,
+    /// The `Host` header value to send instead of the one derived from the URI
+    pub host_override: Option<String>,
+    /// Bytes to write instead of serializing the method, URI, headers and body
+    pub raw_override: Option<Vec<u8>>,
 }
 
 impl HttpRequest {
@@ -34,7 +64,8 @@ impl HttpRequest {
     /// * `uri` - The target URI, which will be converted into a Uri type
     ///
     /// # Returns
-    /// A new HttpRequest instance with default headers and no timeout
+    /// A new HttpRequest instance with no headers of its own and no timeout.
+    /// The client's default headers are added when the request is sent.
     pub fn new<T>(method: HttpMethod, uri: T) -> Self
     where
         T: Into<Uri>,
@@ -42,19 +73,421 @@ impl HttpRequest {
         HttpRequest {
             method,
             uri: uri.into(),
-            headers: HttpHeaders::default(),
+            http_version: HttpVersion::default(),
+            headers: HttpHeaders::new(),
             timeout: None,
+            body: None,
+            sniff_content_type: false,
+            absolute_form: false,
+            cancel: None,
+            host_override: None,
+            raw_override: None,
+        }
+    }
+
+    /// Sets the HTTP version sent in the request line.
+    ///
+    /// Requests default to HTTP/1.1 whatever the URI scheme. Only the request line
+    /// changes; headers and body framing are sent the same way, so a streamed body
+    /// (which is sent chunked) should not be used with HTTP/1.0.
+    ///
+    /// # Arguments
+    /// * `version` - The version to announce
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::{HttpMethod, HttpRequest, HttpVersion};
+    ///
+    /// let request = HttpRequest::new(HttpMethod::GET, "http://example.com/legacy")
+    ///     .http_version(HttpVersion::Http10);
+    /// assert_eq!(request.get_request_line(), "GET /legacy HTTP/1.0");
+    /// ```
+    pub fn http_version(mut self, version: HttpVersion) -> Self {
+        self.http_version = version;
+        self
+    }
+
+    /// Sends a different `Host` header value without changing where the request connects.
+    ///
+    /// By default the `Host` header is derived from the URI. This is useful for reaching
+    /// a specific backend directly (for example by IP address) while presenting the
+    /// virtual host it expects.
+    ///
+    /// # Arguments
+    /// * `host` - The value of the `Host` header
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::{HttpMethod, HttpRequest};
+    ///
+    /// let request = HttpRequest::new(HttpMethod::GET, "http://127.0.0.1:8080/health")
+    ///     .host_override("api.example.com");
+    /// assert_eq!(request.get_host(), "api.example.com");
+    /// assert_eq!(request.uri.get_addr(), "127.0.0.1:8080");
+    /// ```
+    pub fn host_override(mut self, host: &str) -> Self {
+        self.host_override = Some(host.to_string());
+        self
+    }
+
+    /// Replaces the serialized request with the given bytes.
+    ///
+    /// When set, the request is sent by writing exactly these bytes to the connection.
+    /// The URI is still used to resolve and connect to the server, but the method,
+    /// headers, body and the client's default headers are ignored.
+    ///
+    /// **This bypasses all validation and framing.** Nothing checks that the bytes form
+    /// a valid request, which makes it suitable for crafting deliberately malformed
+    /// requests to test how a server copes with them. The response is still parsed
+    /// as usual.
+    ///
+    /// # Arguments
+    /// * `raw` - The bytes to send, including the request line and headers
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::{HttpMethod, HttpRequest};
+    ///
+    /// let request = HttpRequest::new(HttpMethod::GET, "http://127.0.0.1:8080/")
+    ///     .raw_override(b"GET / HTTP/1.1\r\nContent-Length: 1\r\nContent-Length: 2\r\n\r\n".to_vec());
+    /// ```
+    pub fn raw_override(mut self, raw: Vec<u8>) -> Self {
+        self.raw_override = Some(raw);
+        self
+    }
+
+    /// Creates a copy of this request that can be sent independently of it, such as
+    /// for a retry.
+    ///
+    /// `clone` also copies a request with a streaming body, but both copies then share
+    /// one reader, so whichever is sent second fails with `HttpError::BodyConsumed`.
+    /// This returns `None` for such requests instead.
+    ///
+    /// # Returns
+    /// A copy of the request, or `None` if its body is streamed from a reader
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use clienter::{HttpMethod, HttpRequest};
+    ///
+    /// let mut request = HttpRequest::new(HttpMethod::POST, "http://example.com/items");
+    /// request.set_body("{}");
+    /// assert!(request.try_clone().is_some());
+    ///
+    /// let request = request.chunked_body(Cursor::new(b"data".to_vec()));
+    /// assert!(request.try_clone().is_none());
+    /// ```
+    pub fn try_clone(&self) -> Option<HttpRequest> {
+        match self.body {
+            Some(Body::Stream(_)) => None,
+            _ => Some(self.clone()),
+        }
+    }
+
+    /// Returns the `Host` header value for this request.
+    ///
+    /// # Returns
+    /// The host override if one is set, otherwise the host derived from the URI
+    pub fn get_host(&self) -> String {
+        match &self.host_override {
+            Some(host) => host.clone(),
+            None => self.uri.get_host(),
+        }
+    }
+
+    /// Sets the body of the request.
+    ///
+    /// A `Content-Length` header matching the body is added when the request is sent.
+    /// No `Content-Type` is set unless sniffing is enabled with `sniff_content_type`.
+    ///
+    /// # Arguments
+    /// * `body` - The body, as anything convertible into bytes such as a `&str` or `Vec<u8>`
+    pub fn set_body<T>(&mut self, body: T)
+    where
+        T: Into<Vec<u8>>,
+    {
+        self.body = Some(Body::Bytes(body.into()));
+    }
+
+    /// Sets the body to a string.
+    ///
+    /// The `Content-Type` header is set to `text/plain; charset=utf-8` unless the
+    /// request already has one, so this also suits JSON or other text sent with an
+    /// explicit type. A matching `Content-Length` is added when the request is sent.
+    ///
+    /// # Arguments
+    /// * `text` - The body
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::{Body, HttpMethod, HttpRequest};
+    ///
+    /// let request = HttpRequest::new(HttpMethod::POST, "http://example.com/notes").text("remember the milk");
+    /// assert_eq!(request.headers.get_str("Content-Type"), Some("text/plain; charset=utf-8"));
+    /// assert_eq!(request.body, Some(Body::Bytes(b"remember the milk".to_vec())));
+    /// ```
+    pub fn text<T>(mut self, text: T) -> Self
+    where
+        T: Into<String>,
+    {
+        if self.headers.get("Content-Type").is_none() {
+            self.headers.insert(
+                "Content-Type".to_string(),
+                "text/plain; charset=utf-8".to_string(),
+            );
+        }
+        self.set_body(text.into());
+        self
+    }
+
+    /// Sets the body to form data encoded as `application/x-www-form-urlencoded`.
+    ///
+    /// Each key and value is percent-encoded (with spaces written as `+`), and the
+    /// `Content-Type` header is set to `application/x-www-form-urlencoded`. Pairs are
+    /// sorted by key so that unordered inputs such as a `HashMap` always produce the
+    /// same body; pairs with the same key keep their relative order.
+    ///
+    /// # Arguments
+    /// * `pairs` - The fields, such as a slice of tuples or a `HashMap`
+    ///
+    /// # Example
+    /// ```
+    /// use std::collections::HashMap;
+    /// use clienter::{Body, HttpMethod, HttpRequest};
+    ///
+    /// let fields = HashMap::from([("name", "Jo Bloggs"), ("city", "Zürich")]);
+    /// let request = HttpRequest::new(HttpMethod::POST, "http://example.com/signup").form(fields);
+    /// assert_eq!(
+    ///     request.body,
+    ///     Some(Body::Bytes(b"city=Z%C3%BCrich&name=Jo+Bloggs".to_vec()))
+    /// );
+    /// ```
+    pub fn form<I, K, V>(mut self, pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let mut pairs: Vec<(K, V)> = pairs.into_iter().collect();
+        pairs.sort_by(|(a, _), (b, _)| a.as_ref().cmp(b.as_ref()));
+
+        let body = pairs
+            .iter()
+            .map(|(key, value)| {
+                format!(
+                    "{}={}",
+                    utils::form_urlencode(key.as_ref()),
+                    utils::form_urlencode(value.as_ref())
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("&");
+        self.headers.insert(
+            "Content-Type".to_string(),
+            "application/x-www-form-urlencoded".to_string(),
+        );
+        self.set_body(body);
+        self
+    }
+
+    /// Requests only part of the resource, by setting the `Range` header.
+    ///
+    /// The server answers with `206 Partial Content` and the requested bytes, whose
+    /// position is given by `HttpResponse::content_range`. A server that doesn't
+    /// support ranges may ignore the header and send the whole resource with `200 OK`.
+    ///
+    /// # Arguments
+    /// * `start` - The offset of the first byte to request
+    /// * `end` - The offset of the last byte to request, inclusive, or `None` for
+    ///   everything from `start` to the end of the resource
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::{HttpMethod, HttpRequest};
+    ///
+    /// let request = HttpRequest::new(HttpMethod::GET, "http://example.com/video.mp4").range(0, Some(499));
+    /// assert_eq!(request.headers.get_str("Range"), Some("bytes=0-499"));
+    ///
+    /// // Resume a download after the first 500 bytes
+    /// let request = HttpRequest::new(HttpMethod::GET, "http://example.com/video.mp4").range(500, None);
+    /// assert_eq!(request.headers.get_str("Range"), Some("bytes=500-"));
+    /// ```
+    pub fn range(mut self, start: u64, end: Option<u64>) -> Self {
+        let end = end.map(|end| end.to_string()).unwrap_or_default();
+        self.headers
+            .insert("Range".to_string(), format!("bytes={}-{}", start, end));
+        self
+    }
+
+    /// Sets a body produced by a reader, for when its length isn't known up front.
+    ///
+    /// The body is sent with `Transfer-Encoding: chunked`: everything the reader
+    /// produces is framed into chunks and terminated with a zero-length chunk. The
+    /// reader can only be consumed once, so clones of this request share it, and a
+    /// request with a streaming body can't be retried: `try_clone` returns `None` for
+    /// it. Use `set_body` for requests that may need to be sent more than once.
+    ///
+    /// # Arguments
+    /// * `reader` - The reader producing the body
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use clienter::{HttpMethod, HttpRequest};
+    ///
+    /// let request = HttpRequest::new(HttpMethod::POST, "http://example.com/upload")
+    ///     .chunked_body(Cursor::new(b"generated data".to_vec()));
+    /// ```
+    pub fn chunked_body<R>(mut self, reader: R) -> Self
+    where
+        R: Read + Send + 'static,
+    {
+        self.body = Some(Body::Stream(BodyReader::new(reader)));
+        self
+    }
+
+    /// Enables or disables guessing the `Content-Type` from the body.
+    ///
+    /// When enabled and no `Content-Type` header is set on the request or client, the
+    /// body is inspected when the request is sent:
+    /// * A body starting with `{` or `[` is sent as `application/json`
+    /// * Any other valid UTF-8 body is sent as `text/plain`
+    /// * Anything else is sent as `application/octet-stream`
+    ///
+    /// An explicitly set `Content-Type` is never replaced.
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::{HttpMethod, HttpRequest};
+    ///
+    /// let mut request = HttpRequest::new(HttpMethod::POST, "http://example.com/items")
+    ///     .sniff_content_type(true);
+    /// request.set_body(r#"{"key": "value"}"#);
+    /// assert_eq!(request.sniffed_content_type(), Some("application/json"));
+    /// ```
+    pub fn sniff_content_type(mut self, enabled: bool) -> Self {
+        self.sniff_content_type = enabled;
+        self
+    }
+
+    /// Sets whether the request line carries the whole URI instead of only its path
+    /// and query.
+    ///
+    /// This absolute-form is what a forward proxy expects, for example when a
+    /// `connector` sends every request to the proxy. CONNECT and `OPTIONS *` requests
+    /// keep their own forms.
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::{HttpMethod, HttpRequest};
+    ///
+    /// let request = HttpRequest::new(HttpMethod::GET, "http://example.com/status")
+    ///     .absolute_form(true);
+    /// assert_eq!(request.get_request_line(), "GET http://example.com/status HTTP/1.1");
+    /// ```
+    pub fn absolute_form(mut self, enabled: bool) -> Self {
+        self.absolute_form = enabled;
+        self
+    }
+
+    /// Lets the request be aborted from another thread.
+    ///
+    /// Once `handle` (or a clone of it) is cancelled, sending the request fails with
+    /// `HttpError::Cancelled` and reading its response body with
+    /// `ResponseError::Cancelled`. Cancellation is noticed within about 100ms, even
+    /// while waiting on a silent server. With a custom `connector`, it is only checked
+    /// before connecting.
+    ///
+    /// # Arguments
+    /// * `handle` - The handle to watch, see `CancelHandle`
+    pub fn cancel_on(mut self, handle: CancelHandle) -> Self {
+        self.cancel = Some(handle);
+        self
+    }
+
+    /// Returns the `Content-Type` that sniffing would assign to the current body.
+    ///
+    /// # Returns
+    /// `None` if sniffing is disabled or there is no body, or the body is streamed
+    pub fn sniffed_content_type(&self) -> Option<&'static str> {
+        if !self.sniff_content_type {
+            return None;
         }
+
+        let Some(Body::Bytes(body)) = &self.body else {
+            return None;
+        };
+        let content_type = match std::str::from_utf8(body) {
+            Ok(text) if text.trim_start().starts_with(['{', '[']) => "application/json",
+            Ok(_) => "text/plain",
+            Err(_) => "application/octet-stream",
+        };
+        Some(content_type)
+    }
+
+    /// Returns the target written in the request line.
+    ///
+    /// CONNECT requests use the authority-form (`host:port`) and an OPTIONS request to
+    /// the path `*` (such as `http://example.com/*`) uses the asterisk-form. Other
+    /// requests use the absolute-form if `absolute_form` is set, and the origin-form
+    /// (the encoded path and query) otherwise.
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::{HttpMethod, HttpRequest, RequestTarget};
+    ///
+    /// let request = HttpRequest::new(HttpMethod::OPTIONS, "http://example.com/*");
+    /// assert_eq!(request.request_target(), RequestTarget::Asterisk);
+    /// assert_eq!(request.get_request_line(), "OPTIONS * HTTP/1.1");
+    /// ```
+    pub fn request_target(&self) -> RequestTarget {
+        RequestTarget::for_uri(&self.method, &self.uri, self.absolute_form)
     }
 
     /// Generates the request line for the HTTP request.
     ///
     /// # Returns
     /// A String containing the formatted request line in the format:
-    /// "{METHOD} /{PATH} {HTTP_VERSION}"
+    /// "{METHOD} {TARGET} {HTTP_VERSION}", where the target is described by
+    /// `request_target`
     pub fn get_request_line(&self) -> String {
-        let uri = format!("/{}", self.uri.get_encoded_path());
-        let version = self.uri.protocol.get_http_version();
-        format!("{} {} {}", self.method, uri, version)
+        self.request_line_for(&self.uri)
+    }
+
+    /// Generates the request line for sending this request to a different URI, such as
+    /// one with extra query parameters.
+    pub(crate) fn request_line_for(&self, uri: &Uri) -> String {
+        let target = RequestTarget::for_uri(&self.method, uri, self.absolute_form);
+        format!("{} {} {}", self.method, target, self.http_version)
+    }
+}
+
+impl std::fmt::Display for HttpRequest {
+    /// Formats the request line and the request's own headers as they are written on
+    /// the wire, one `\r\n`-terminated line each.
+    ///
+    /// The client's default headers, the `Host` header and the body are not shown, as
+    /// they are only added when the request is sent. Use `HttpClient::send_detailed`
+    /// to see the request as it was sent.
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::{HttpMethod, HttpRequest};
+    ///
+    /// let request = HttpRequest::new(HttpMethod::POST, "http://example.com/items?page=2")
+    ///     .text("hello");
+    /// assert_eq!(
+    ///     request.to_string(),
+    ///     "POST /items?page=2 HTTP/1.1\r\nContent-Type: text/plain; charset=utf-8\r\n"
+    /// );
+    /// ```
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}\r\n{}",
+            self.get_request_line(),
+            self.headers.to_wire_string()
+        )
     }
 }
@@ -2,15 +2,41 @@
 //!
 //! This module provides functionality for parsing and handling HTTP responses
 //! received from a server over a TCP connection.
+//!
+//! The end of the body is found as described in RFC 7230 section 3.3.3: responses to
+//! `HEAD` requests and `1xx`, `204` and `304` responses have no body, chunked bodies
+//! are decoded, and otherwise `Content-Length` is used. A body with neither is read
+//! until the server closes the connection, unless the server announced
+//! `Connection: keep-alive`, in which case reading it fails with
+//! `ResponseError::UnframedBody` instead of waiting forever. Sending a request with
+//! `Connection: close` avoids this: the server will close the connection, so such a
+//! body is read until it does.
+//!
+//! Reading a framed body never reads past its end: whatever the server sent after
+//! it, such as the start of a pipelined response, stays buffered. It is returned
+//! first by the stream from `HttpResponse::into_stream`, and after the response in
+//! the trailing bytes of `HttpResponse::parse`.
+//!
+//! By default, lines of the status line, headers and chunked framing may end with
+//! either CRLF or a bare LF, as sent by some embedded servers. `HttpClient::strict`
+//! requires CRLF for the status line and headers.
 
-use std::net::TcpStream;
+use std::{
+    io::{Cursor, Read, Write},
+    ops::ControlFlow,
+    time::{Duration, Instant},
+};
 
 use crate::{
-    internal::StreamBuffer,
-    utils::{triple_split, tuple_split},
+    internal::{BodyMode, DeadlineHandle, StreamBuffer},
+    utils::{self, tuple_split},
+    ReadWrite,
 };
 
-use super::{HttpHeaders, StatusCode};
+use super::{
+    cancel::is_cancelled_error, pool::ConnectionGuard, AuthChallenge, ClientEvent, Cookie,
+    Encoding, EventHook, HttpHeaders, HttpMethod, Protocol, StatusCode, Uri,
+};
 
 /// Represents an HTTP response received from a server.
 ///
@@ -21,90 +47,1300 @@ pub struct HttpResponse {
     pub status: StatusCode,
     /// The HTTP headers included in the response
     pub headers: HttpHeaders,
+    /// The URI of the request that produced this response, after any redirects.
+    ///
+    /// `None` for responses parsed directly with `HttpResponse::build`.
+    pub final_uri: Option<Uri>,
+    /// The URIs that were redirected from on the way to `final_uri`, oldest first.
+    ///
+    /// The client does not follow redirects by itself, so this is empty unless
+    /// they were followed with `HttpClient::follow` or on the caller's behalf.
+    pub redirect_history: Vec<Uri>,
 
     /// Internal buffer for reading response data
     buffer: StreamBuffer,
+    /// Callback notified once the body has been read
+    on_event: Option<EventHook>,
+    /// When the request that produced this response started
+    started: Instant,
+    /// When the request that produced this response started being written
+    sent_at: Option<Instant>,
+    /// When the first byte of the response was received
+    first_byte_at: Option<Instant>,
+    /// Keeps the connection counted as in use while the response is alive
+    connection: Option<ConnectionGuard>,
+    /// How the end of the body is determined
+    framing: Framing,
+    /// Whether a copy of the body is kept once it has been read
+    retain_body: bool,
+    /// The copy of the body kept when `retain_body` is set
+    retained_body: Vec<u8>,
+    /// Bounds blocking reads of the connection while `body_with_timeout` runs
+    body_deadline: Option<DeadlineHandle>,
+}
+
+/// How the end of a response body is determined, following RFC 7230 section 3.3.3.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Framing {
+    /// The response cannot have a body
+    Empty,
+    /// The body is exactly this many bytes long
+    Length(usize),
+    /// The body uses chunked transfer encoding
+    Chunked,
+    /// The body ends when the server closes the connection
+    UntilClose,
+    /// The server keeps the connection open but gave no way to find the end of the body
+    Unframed,
 }
 
 /// Errors that can occur while parsing an HTTP response.
 #[derive(Debug, PartialEq)]
 pub enum ResponseError {
-    /// The status line was malformed or could not be parsed
-    InvalidStatusLine,
-    /// One or more headers were malformed or could not be parsed
-    InvalidHeader,
+    /// The status line was malformed or could not be parsed, carrying the line as received
+    InvalidStatusLine(String),
+    /// A header was malformed or could not be parsed, carrying the line as received
+    InvalidHeader(String),
     /// The response body could not be read or parsed
     InvalidBody,
+    /// The server did not respond before the deadline
+    Timeout,
+    /// The request was cancelled through its `CancelHandle` while the response was read
+    Cancelled,
+    /// The response has more header lines than allowed, `MAX_HEADER_LINES` unless
+    /// the client's `max_headers` says otherwise
+    HeadersTooLarge,
+    /// The response has neither a `Content-Length` nor chunked encoding while the server
+    /// keeps the connection alive, so the end of the body cannot be found
+    UnframedBody,
+    /// The body uses a content coding that cannot be decoded, carrying the
+    /// `Content-Encoding` header as received
+    UnsupportedEncoding(String),
+    /// The body is not valid JSON for the expected type, carrying the parser's message
+    InvalidJson(String),
+    /// The response has a status the operation doesn't apply to, such as a success
+    /// status when reading an error body
+    UnexpectedStatus(StatusCode),
+}
+
+impl ResponseError {
+    /// Maps an I/O error to a response error, using `fallback` unless the error is a timeout.
+    fn from_io(err: std::io::Error, fallback: ResponseError) -> ResponseError {
+        if is_cancelled_error(&err) {
+            return ResponseError::Cancelled;
+        }
+        match err.kind() {
+            std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock => ResponseError::Timeout,
+            _ => fallback,
+        }
+    }
+}
+
+impl std::fmt::Display for ResponseError {
+    /// Formats the error along with the offending line, if any.
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::ResponseError;
+    ///
+    /// let err = ResponseError::InvalidStatusLine("HTTP/1.1 garbage".to_string());
+    /// assert_eq!(err.to_string(), "InvalidStatusLine: 'HTTP/1.1 garbage'");
+    /// ```
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ResponseError::InvalidStatusLine(line) => write!(f, "InvalidStatusLine: '{}'", line),
+            ResponseError::InvalidHeader(line) => write!(f, "InvalidHeader: '{}'", line),
+            ResponseError::InvalidBody => write!(f, "InvalidBody"),
+            ResponseError::Timeout => write!(f, "Timeout"),
+            ResponseError::HeadersTooLarge => write!(f, "HeadersTooLarge"),
+            ResponseError::Cancelled => write!(f, "Cancelled"),
+            ResponseError::UnframedBody => write!(
+                f,
+                "UnframedBody: no Content-Length or chunked encoding on a keep-alive connection"
+            ),
+            ResponseError::UnsupportedEncoding(encoding) => {
+                write!(f, "UnsupportedEncoding: '{}'", encoding)
+            }
+            ResponseError::InvalidJson(message) => write!(f, "InvalidJson: {}", message),
+            ResponseError::UnexpectedStatus(status) => write!(f, "UnexpectedStatus: {}", status),
+        }
+    }
+}
+
+impl std::error::Error for ResponseError {}
+
+/// Returns the content of a raw line read without its `\n`, provided that it is
+/// CRLF terminated and free of control characters other than horizontal tabs.
+fn strict_line_content(raw: &[u8]) -> Option<&[u8]> {
+    let line = raw.strip_suffix(b"\r")?;
+    line.iter()
+        .all(|&byte| byte == b'\t' || !byte.is_ascii_control())
+        .then_some(line)
+}
+
+/// Checks a status line against RFC 7230: `HTTP/1.x`, a three digit status code and
+/// a reason phrase, separated by single spaces and terminated by CRLF.
+fn is_strict_status_line(raw: &[u8]) -> bool {
+    let Some(line) = strict_line_content(raw) else {
+        return false;
+    };
+    let Some(rest) = line
+        .strip_prefix(b"HTTP/1.1 ")
+        .or_else(|| line.strip_prefix(b"HTTP/1.0 "))
+    else {
+        return false;
+    };
+    rest.len() >= 4 && rest[..3].iter().all(u8::is_ascii_digit) && rest[3] == b' '
+}
+
+/// Checks a header line against RFC 7230: a token name directly followed by a colon,
+/// terminated by CRLF. Folded continuation lines are rejected.
+fn is_strict_header_line(raw: &[u8]) -> bool {
+    let Some(line) = strict_line_content(raw) else {
+        return false;
+    };
+    let Some(colon) = line.iter().position(|&byte| byte == b':') else {
+        return false;
+    };
+    let name = &line[..colon];
+    !name.is_empty()
+        && name
+            .iter()
+            .all(|&byte| byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte))
+}
+
+/// The default maximum number of header lines accepted in a response.
+///
+/// This bounds the header phase even if the server never sends the blank line that
+/// ends the headers. `HttpClient::max_headers` changes it for a client.
+pub const MAX_HEADER_LINES: usize = 100;
+
+/// Splits the parameters of a `Content-Disposition` value into names and values.
+///
+/// The disposition type before the first `;` is skipped. Quoted values may contain
+/// `;` and backslash escapes, which are resolved.
+///
+/// # Returns
+/// The parameters in order, or `None` if a quoted value is not terminated
+fn disposition_params(value: &str) -> Option<Vec<(String, String)>> {
+    let (_, mut rest) = value.split_once(';')?;
+    let mut params = Vec::new();
+    while let Some((name, after)) = rest.split_once('=') {
+        if let Some(end) = name.find(';') {
+            // Skip a parameter without a value
+            rest = &rest[end + 1..];
+            continue;
+        }
+        let name = name.trim().to_string();
+        let after = after.trim_start();
+
+        let value = if let Some(quoted) = after.strip_prefix('"') {
+            let mut value = String::new();
+            let mut chars = quoted.char_indices();
+            let end = loop {
+                match chars.next()? {
+                    (i, '"') => break i + 1,
+                    (_, '\\') => value.push(chars.next()?.1),
+                    (_, c) => value.push(c),
+                }
+            };
+            rest = quoted[end..].split_once(';').map_or("", |(_, rest)| rest);
+            value
+        } else {
+            let (value, remainder) = after.split_once(';').unwrap_or((after, ""));
+            rest = remainder;
+            value.trim().to_string()
+        };
+        params.push((name, value));
+    }
+    Some(params)
+}
+
+/// Decodes a raw status or header line, trimming surrounding whitespace.
+///
+/// Lines are decoded as UTF-8 when valid, falling back to ISO-8859-1 (where every
+/// byte maps to the character with the same code point) for legacy servers.
+fn decode_line(bytes: &[u8]) -> String {
+    let line = match std::str::from_utf8(bytes) {
+        Ok(line) => line.to_string(),
+        Err(_) => bytes.iter().map(|&byte| byte as char).collect(),
+    };
+    line.trim().to_string()
+}
+
+impl Framing {
+    /// Determines the framing of a response from its status and headers.
+    ///
+    /// A response without a `Content-Length` or chunked encoding is delimited by the
+    /// server closing the connection. If the server instead says it keeps the
+    /// connection alive, reading it would block forever, so it is `Unframed`, unless
+    /// the request asked for the connection to be closed (`request_closes`).
+    ///
+    /// `Transfer-Encoding` takes precedence over `Content-Length`. A `Content-Length`
+    /// that isn't a number, or several values that disagree, are rejected, since a
+    /// message with an ambiguous length can be used to smuggle a second response into
+    /// the body.
+    fn of(
+        status: StatusCode,
+        headers: &HttpHeaders,
+        request_closes: bool,
+    ) -> Result<Framing, ResponseError> {
+        if matches!(
+            status,
+            StatusCode::Continue100
+                | StatusCode::SwitchingProtocols101
+                | StatusCode::Processing102
+                | StatusCode::EarlyHints103
+                | StatusCode::NoContent204
+                | StatusCode::NotModified304
+        ) {
+            return Ok(Framing::Empty);
+        }
+        Framing::from_headers(headers, request_closes)
+    }
+
+    /// Returns the mode the buffer reads the body with, or `None` for an `Unframed`
+    /// body, which is never read.
+    fn body_mode(self) -> Option<BodyMode> {
+        match self {
+            Framing::Empty => Some(BodyMode::Length(0)),
+            Framing::Length(length) => Some(BodyMode::Length(length)),
+            Framing::Chunked => Some(BodyMode::Chunked),
+            Framing::UntilClose => Some(BodyMode::UntilClose),
+            Framing::Unframed => None,
+        }
+    }
+
+    /// Determines the framing of a body from the headers alone, as `of` does for a
+    /// status that allows a body.
+    fn from_headers(headers: &HttpHeaders, request_closes: bool) -> Result<Framing, ResponseError> {
+        // The codings of every Transfer-Encoding header apply in order
+        if let Some(transfer_encoding) = headers.get_all("Transfer-Encoding").last() {
+            let last = transfer_encoding.rsplit(',').next().unwrap_or_default();
+            return Ok(if last.trim().eq_ignore_ascii_case("chunked") {
+                Framing::Chunked
+            } else {
+                Framing::UntilClose
+            });
+        }
+
+        // Repeated values, in separate headers or a list, must all be valid and agree
+        let values = headers.get_all("Content-Length");
+        let invalid = || {
+            let values: Vec<&str> = values.iter().map(|value| value.as_str()).collect();
+            ResponseError::InvalidHeader(format!("Content-Length: {}", values.join(", ")))
+        };
+        let mut content_length = None;
+        for value in values.iter().flat_map(|value| value.split(',')) {
+            let value = value.trim();
+            if value.is_empty() || !value.bytes().all(|byte| byte.is_ascii_digit()) {
+                return Err(invalid());
+            }
+            let length = value.parse::<usize>().map_err(|_| invalid())?;
+            if content_length.is_some_and(|content_length| content_length != length) {
+                return Err(invalid());
+            }
+            content_length = Some(length);
+        }
+        if let Some(content_length) = content_length {
+            return Ok(Framing::Length(content_length));
+        }
+
+        if headers.has_connection_option("keep-alive") && !request_closes {
+            return Ok(Framing::Unframed);
+        }
+        Ok(Framing::UntilClose)
+    }
+}
+
+/// Decodes a body that was encoded with a content coding.
+type Decoder = fn(Vec<u8>) -> Option<Vec<u8>>;
+
+/// Returns the decoder for a content coding, if support for it was compiled in.
+///
+/// gzip and deflate require the `gzip` feature, br requires the `brotli` feature.
+fn decoder_for(encoding: Encoding) -> Option<Decoder> {
+    match encoding {
+        Encoding::Identity => Some(Some),
+        #[cfg(feature = "gzip")]
+        Encoding::Gzip => Some(|body| crate::internal::gunzip(&body)),
+        #[cfg(feature = "gzip")]
+        Encoding::Deflate => Some(|body| crate::internal::inflate(&body)),
+        #[cfg(feature = "brotli")]
+        Encoding::Brotli => Some(|body| crate::internal::unbrotli(&body)),
+        // Reached only when some of the features above are disabled
+        #[allow(unreachable_patterns)]
+        _ => None,
+    }
+}
+
+/// The connection of a response handed back to the caller, see `HttpResponse::into_stream`.
+struct UpgradedStream {
+    /// Bytes that were read past the response headers and not yet consumed
+    remainder: Cursor<Vec<u8>>,
+    stream: Box<dyn ReadWrite>,
+    /// Keeps the connection counted as in use until the stream is dropped
+    _connection: Option<ConnectionGuard>,
+}
+
+impl Read for UpgradedStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.remainder.read(buf)?;
+        if read > 0 {
+            return Ok(read);
+        }
+        self.stream.read(buf)
+    }
+}
+
+impl Write for UpgradedStream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.stream.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.stream.flush()
+    }
+}
+
+/// Passes each write to a callback, see `HttpResponse::for_each_chunk`.
+struct ChunkSink<F> {
+    callback: F,
+    /// Set once the callback has asked to stop
+    stopped: bool,
+}
+
+impl<F: FnMut(&[u8]) -> ControlFlow<()>> Write for ChunkSink<F> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if (self.callback)(buf).is_break() {
+            self.stopped = true;
+            return Err(std::io::Error::other("stopped by callback"));
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
 impl HttpResponse {
-    /// Builds a new HttpResponse from a TCP stream.
+    /// Builds a new HttpResponse from a connected stream.
     ///
     /// This method reads and parses the status line and headers from the stream.
     /// The body can be read later using the `body()` or `body_as_string()` methods.
     ///
     /// # Arguments
-    /// * `stream` - A TcpStream connected to the server
+    /// * `stream` - A stream connected to the server, such as a `TcpStream`
     ///
     /// # Returns
     /// * `Ok(HttpResponse)` if parsing was successful
     /// * `Err(ResponseError)` if any parsing errors occurred
-    pub fn build(stream: TcpStream) -> Result<Self, ResponseError> {
-        let mut buffer = StreamBuffer::new(stream);
-
-        let status_line = buffer
-            .read_line()
-            .map_err(|_| ResponseError::InvalidStatusLine)?;
-        let (_http_version, status, _) =
-            triple_split(&status_line, " ").ok_or(ResponseError::InvalidStatusLine)?;
-        let status = status
-            .parse::<u16>()
-            .map_err(|_| ResponseError::InvalidStatusLine)?;
-        let status = status
-            .try_into()
-            .map_err(|_| ResponseError::InvalidStatusLine)?;
+    pub fn build<S>(stream: S) -> Result<Self, ResponseError>
+    where
+        S: ReadWrite + 'static,
+    {
+        Self::build_for(stream, None, false, false, MAX_HEADER_LINES)
+    }
+
+    /// Parses a complete response, including its body, from bytes already in memory.
+    ///
+    /// This suits captured traffic and tests. The body is framed as it would be on a
+    /// connection, so any bytes after it, such as the next of several pipelined
+    /// responses, are returned separately. A body without a length or chunked
+    /// framing extends to the end of `bytes`, as it would until the connection closed.
+    ///
+    /// # Arguments
+    /// * `bytes` - The response, starting with its status line
+    ///
+    /// # Returns
+    /// * `Ok((HttpResponse, &[u8]))` - The response, whose body can be read with `body()`,
+    ///   and the bytes that follow it
+    /// * `Err(ResponseError)` - If the response is malformed or incomplete
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::{HttpResponse, StatusCode};
+    ///
+    /// let bytes = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nhiHTTP/1.1 204 No Content\r\n\r\n";
+    /// let (mut first, rest) = HttpResponse::parse(bytes).unwrap();
+    /// assert_eq!(first.body().unwrap(), b"hi");
+    ///
+    /// let (second, rest) = HttpResponse::parse(rest).unwrap();
+    /// assert_eq!(second.status, StatusCode::NoContent204);
+    /// assert!(rest.is_empty());
+    /// ```
+    pub fn parse(bytes: &[u8]) -> Result<(HttpResponse, &[u8]), ResponseError> {
+        let mut response = Self::build_for(
+            Cursor::new(bytes.to_vec()),
+            None,
+            false,
+            true,
+            MAX_HEADER_LINES,
+        )?;
+        let (mut stream, mut rest) = response.buffer_body()?.into_parts();
+        stream
+            .read_to_end(&mut rest)
+            .map_err(|err| ResponseError::from_io(err, ResponseError::InvalidBody))?;
+        Ok((response, &bytes[bytes.len() - rest.len()..]))
+    }
+
+    /// Creates a response from its parts, without reading it from a connection.
+    ///
+    /// This suits unit tests of code that consumes an `HttpResponse`, which can be fed a
+    /// canned response. The body is returned as is by `body()`, whatever the headers
+    /// say about its length or encoding.
+    ///
+    /// # Arguments
+    /// * `status` - The status code
+    /// * `headers` - The response headers
+    /// * `body` - The body
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::{HttpHeaders, HttpResponse, StatusCode};
+    ///
+    /// let mut headers = HttpHeaders::new();
+    /// headers.insert("Content-Type".to_string(), "application/json".to_string());
+    /// let mut response = HttpResponse::from_parts(StatusCode::Ok200, headers, b"{}".to_vec());
+    ///
+    /// assert_eq!(response.header("content-type"), Some("application/json"));
+    /// assert_eq!(response.body_as_string().unwrap(), "{}");
+    /// ```
+    pub fn from_parts(status: StatusCode, headers: HttpHeaders, body: Vec<u8>) -> Self {
+        HttpResponse {
+            status,
+            headers,
+            final_uri: None,
+            redirect_history: Vec::new(),
+            buffer: StreamBuffer::with_prefill(Box::new(std::io::empty()), body),
+            on_event: None,
+            started: Instant::now(),
+            sent_at: None,
+            first_byte_at: None,
+            connection: None,
+            framing: Framing::UntilClose,
+            retain_body: false,
+            retained_body: Vec::new(),
+            body_deadline: None,
+        }
+    }
+
+    /// Builds a new HttpResponse to a request made with `method`.
+    ///
+    /// Responses to `HEAD` requests never have a body, whatever their headers say.
+    ///
+    /// # Arguments
+    /// * `stream` - A stream connected to the server
+    /// * `method` - The method of the request, if known
+    /// * `strict` - Whether to reject status and header lines that aren't strictly
+    ///   RFC 7230 compliant, see `HttpClient::strict`
+    /// * `request_closes` - Whether the request was sent with `Connection: close`, in
+    ///   which case a body without a length is read until the server closes the
+    ///   connection, even if the response claims it stays open
+    /// * `max_headers` - The number of header lines above which parsing fails with
+    ///   `ResponseError::HeadersTooLarge`
+    pub(crate) fn build_for<S>(
+        stream: S,
+        method: Option<&HttpMethod>,
+        strict: bool,
+        request_closes: bool,
+        max_headers: usize,
+    ) -> Result<Self, ResponseError>
+    where
+        S: ReadWrite + 'static,
+    {
+        let mut buffer = StreamBuffer::new(Box::new(stream));
+
+        let raw_status_line = buffer.read_line_bytes().map_err(|err| {
+            ResponseError::from_io(err, ResponseError::InvalidStatusLine(String::new()))
+        })?;
+        let status_line = decode_line(&raw_status_line);
+        let invalid_status_line = || ResponseError::InvalidStatusLine(status_line.clone());
+        if strict && !is_strict_status_line(&raw_status_line) {
+            return Err(invalid_status_line());
+        }
+        // The reason phrase may be empty, and the space before it may then be trimmed
+        let status = status_line
+            .split(' ')
+            .nth(1)
+            .ok_or_else(invalid_status_line)?;
+        let status = status.parse::<u16>().map_err(|_| invalid_status_line())?;
+        let status = status.try_into().map_err(|_| invalid_status_line())?;
 
         let mut headers = HttpHeaders::new();
+        let mut header_lines = 0;
 
         loop {
-            let line = buffer
-                .read_line()
-                .map_err(|_| ResponseError::InvalidHeader)?;
-            let line = line.trim();
+            let raw_line = buffer.read_line_bytes().map_err(|err| {
+                ResponseError::from_io(err, ResponseError::InvalidHeader(String::new()))
+            })?;
+            let line = decode_line(&raw_line);
 
+            if strict && raw_line != b"\r" && !is_strict_header_line(&raw_line) {
+                return Err(ResponseError::InvalidHeader(line));
+            }
             if line.is_empty() {
                 break;
             }
+            header_lines += 1;
+            if header_lines > max_headers {
+                return Err(ResponseError::HeadersTooLarge);
+            }
 
-            let (key, value) = tuple_split(line, ":").ok_or(ResponseError::InvalidHeader)?;
+            let (key, value) = tuple_split(&line, ":")
+                .ok_or_else(|| ResponseError::InvalidHeader(line.clone()))?;
             let key = key.trim();
             let value = value.trim();
-            headers.insert(key.to_string(), value.to_string());
+            headers.append(key.to_string(), value.to_string());
         }
 
-        // Check for a Content-Length header to set the total bytes to read
-        if let Some(content_length) = headers.get("Content-Length") {
-            if let Ok(content_length) = content_length.parse::<usize>() {
-                buffer.set_total_bytes(content_length);
-            }
+        let framing = if method == Some(&HttpMethod::HEAD) {
+            Framing::Empty
+        } else {
+            Framing::of(status, &headers, request_closes)?
+        };
+        if let Some(mode) = framing.body_mode() {
+            buffer.set_body_mode(mode);
         }
 
+        // Kept apart from the buffer, which `buffer_body` replaces
+        let first_byte_at = buffer.first_read_at();
         Ok(HttpResponse {
             status,
             headers,
+            final_uri: None,
+            redirect_history: Vec::new(),
             buffer,
+            on_event: None,
+            started: Instant::now(),
+            sent_at: None,
+            first_byte_at,
+            connection: None,
+            framing,
+            retain_body: false,
+            retained_body: Vec::new(),
+            body_deadline: None,
         })
     }
 
+    /// Attaches the event hook of the request that produced this response.
+    ///
+    /// # Arguments
+    /// * `on_event` - The hook to notify when the body has been read
+    /// * `started` - When the request started, used to compute elapsed times
+    pub(crate) fn set_event_hook(&mut self, on_event: Option<EventHook>, started: Instant) {
+        self.on_event = on_event;
+        self.started = started;
+    }
+
+    /// Sets whether a copy of the body is kept for `raw_body` once it has been read.
+    pub(crate) fn set_retain_body(&mut self, retain_body: bool) {
+        self.retain_body = retain_body;
+    }
+
+    /// Attaches the accounting guard of the connection this response is read from.
+    pub(crate) fn set_connection(&mut self, connection: ConnectionGuard) {
+        self.connection = Some(connection);
+    }
+
+    /// Attaches the handle that bounds blocking reads of the connection this response
+    /// is read from, used by `body_with_timeout`.
+    pub(crate) fn set_body_deadline(&mut self, body_deadline: DeadlineHandle) {
+        self.body_deadline = Some(body_deadline);
+    }
+
+    /// Records when the request that produced this response started being written.
+    pub(crate) fn set_sent_at(&mut self, sent_at: Instant) {
+        self.sent_at = Some(sent_at);
+    }
+
+    /// Returns the instant at which the first byte of the response was received.
+    pub(crate) fn first_byte_at(&self) -> Option<Instant> {
+        self.first_byte_at
+    }
+
+    /// Returns the time to first byte: the delay between starting to send the request
+    /// and receiving the first byte of the response.
+    ///
+    /// This excludes connecting and reading the body, so it mostly reflects how long
+    /// the server took to process the request. For a request sent with
+    /// `Expect: 100-continue`, it is measured up to the first byte of the final response.
+    ///
+    /// # Returns
+    /// The time to first byte, or `None` for responses parsed directly with
+    /// `HttpResponse::build`
+    ///
+    /// # Example
+    /// ```no_run
+    /// use clienter::{HttpClient, HttpMethod};
+    ///
+    /// let client = HttpClient::new();
+    /// let request = client.request(HttpMethod::GET, "http://example.com");
+    /// let response = client.send(&request).unwrap();
+    /// println!("TTFB: {:?}", response.time_to_first_byte().unwrap());
+    /// ```
+    pub fn time_to_first_byte(&self) -> Option<Duration> {
+        let sent_at = self.sent_at?;
+        Some(self.first_byte_at?.saturating_duration_since(sent_at))
+    }
+
+    /// Returns the status code of the response by value.
+    ///
+    /// This allows the status to be captured before the body is read, which requires
+    /// a mutable borrow of the response.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use clienter::{HttpClient, HttpMethod};
+    ///
+    /// let client = HttpClient::new();
+    /// let request = client.request(HttpMethod::GET, "http://example.com");
+    /// let mut response = client.send(&request).unwrap();
+    ///
+    /// let status = response.status_code();
+    /// let body = response.body().unwrap();
+    /// println!("{} ({} bytes)", status, body.len());
+    /// ```
+    pub fn status_code(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Returns whether the response was reached by following at least one redirect.
+    pub fn was_redirected(&self) -> bool {
+        !self.redirect_history.is_empty()
+    }
+
+    /// Returns whether following redirects moved the request to a different origin.
+    ///
+    /// # Returns
+    /// `true` if the scheme, host or port of `final_uri` differs from the original URI
+    pub fn changed_origin(&self) -> bool {
+        match (self.redirect_history.first(), &self.final_uri) {
+            (Some(original), Some(last)) => !original.same_origin(last),
+            _ => false,
+        }
+    }
+
+    /// Returns whether following redirects downgraded the request from HTTPS to HTTP.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use clienter::HttpResponse;
+    ///
+    /// let raw = b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n".to_vec();
+    /// let mut response = HttpResponse::build(Cursor::new(raw)).ok().unwrap();
+    /// response.redirect_history = vec!["https://example.com/".parse().unwrap()];
+    /// response.final_uri = Some("http://example.com/".parse().unwrap());
+    /// assert!(response.was_downgraded());
+    /// assert!(response.changed_origin());
+    /// ```
+    pub fn was_downgraded(&self) -> bool {
+        let secure_before = self
+            .redirect_history
+            .iter()
+            .any(|uri| uri.protocol == Protocol::HTTPS);
+        let insecure_after = self
+            .final_uri
+            .as_ref()
+            .is_some_and(|uri| uri.protocol == Protocol::HTTP);
+        secure_before && insecure_after
+    }
+
+    /// Parses the challenges of every `WWW-Authenticate` header of the response.
+    ///
+    /// Servers send these with a `401 Unauthorized` response to say which
+    /// authentication schemes they accept. See `AuthChallenge::parse_list`.
+    ///
+    /// # Returns
+    /// A Vec of challenges in the order the server sent them
+    pub fn auth_challenges(&self) -> Vec<AuthChallenge> {
+        self.headers
+            .get_all("WWW-Authenticate")
+            .into_iter()
+            .flat_map(|value| AuthChallenge::parse_list(value))
+            .collect()
+    }
+
+    /// Returns the first value of a response header.
+    ///
+    /// # Parameters
+    /// * `key` - The header field name, in any case
+    ///
+    /// # Returns
+    /// The header value, or `None` if the response doesn't have the header
+    ///
+    /// # Example
+    /// ```no_run
+    /// use clienter::{HttpClient, HttpMethod};
+    ///
+    /// let client = HttpClient::new();
+    /// let request = client.request(HttpMethod::GET, "http://example.com");
+    /// let response = client.send(&request).unwrap();
+    /// if let Some(content_type) = response.header("content-type") {
+    ///     println!("{}", content_type);
+    /// }
+    /// ```
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers.get_str(key)
+    }
+
+    /// Parses the `Content-Range` header of a `206 Partial Content` response.
+    ///
+    /// # Returns
+    /// `(start, end, total)`, where `end` is the offset of the last byte sent
+    /// (inclusive) and `total` is the length of the whole resource, or `None` if the
+    /// server doesn't know it. `None` if the header is missing, malformed, or doesn't
+    /// describe a byte range, as with the `bytes */1234` form of a `416` response.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use clienter::HttpResponse;
+    ///
+    /// let raw = b"HTTP/1.1 206 Partial Content\r\nContent-Range: bytes 500-999/1234\r\nContent-Length: 500\r\n\r\n".to_vec();
+    /// let response = HttpResponse::build(Cursor::new(raw)).ok().unwrap();
+    /// assert_eq!(response.content_range(), Some((500, 999, Some(1234))));
+    /// ```
+    pub fn content_range(&self) -> Option<(u64, u64, Option<u64>)> {
+        let value = self.header("Content-Range")?.trim();
+        let (unit, range) = value.split_once(' ')?;
+        if !unit.eq_ignore_ascii_case("bytes") {
+            return None;
+        }
+
+        let (range, total) = range.trim().split_once('/')?;
+        let (start, end) = range.split_once('-')?;
+        let start = start.parse().ok()?;
+        let end = end.parse().ok()?;
+        let total = match total {
+            "*" => None,
+            total => Some(total.parse().ok()?),
+        };
+        (start <= end).then_some((start, end, total))
+    }
+
+    /// Returns the file name suggested by the `Content-Disposition` header.
+    ///
+    /// The extended `filename*` parameter (RFC 5987, such as
+    /// `filename*=UTF-8''na%C3%AFve.txt`) is preferred over `filename`, as RFC 6266
+    /// recommends. Extended values in UTF-8 and ISO-8859-1 are supported. Any directory
+    /// part of the name is removed, so the result can't point outside the directory
+    /// it is saved in.
+    ///
+    /// # Returns
+    /// The file name, or `None` if the header is missing, has no usable file name, or
+    /// cannot be parsed
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use clienter::HttpResponse;
+    ///
+    /// let raw = b"HTTP/1.1 200 OK\r\nContent-Disposition: attachment; filename=\"report.pdf\"; \
+    ///     filename*=UTF-8''r%C3%A9sum%C3%A9.pdf\r\nContent-Length: 0\r\n\r\n".to_vec();
+    /// let response = HttpResponse::build(Cursor::new(raw)).ok().unwrap();
+    /// assert_eq!(response.content_disposition_filename().as_deref(), Some("résumé.pdf"));
+    /// ```
+    pub fn content_disposition_filename(&self) -> Option<String> {
+        let params = disposition_params(self.header("Content-Disposition")?)?;
+        let param = |name: &str| {
+            params
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        };
+
+        let extended = param("filename*").and_then(|value| {
+            let (charset, rest) = value.split_once('\'')?;
+            let (_language, encoded) = rest.split_once('\'')?;
+            let bytes = utils::percent_decode(encoded);
+            if charset.eq_ignore_ascii_case("UTF-8") {
+                String::from_utf8(bytes).ok()
+            } else if charset.eq_ignore_ascii_case("ISO-8859-1") {
+                Some(bytes.into_iter().map(char::from).collect())
+            } else {
+                None
+            }
+        });
+        let name = extended.or_else(|| param("filename").map(str::to_string))?;
+
+        let name = name.rsplit(['/', '\\']).next().unwrap_or_default().trim();
+        match name {
+            "" | "." | ".." => None,
+            name => Some(name.to_string()),
+        }
+    }
+
+    /// Parses every `Set-Cookie` header of the response into a `Cookie`.
+    ///
+    /// Headers that cannot be parsed as a cookie are skipped.
+    ///
+    /// # Returns
+    /// A Vec of cookies in the order the server sent them
+    pub fn cookies(&self) -> Vec<Cookie> {
+        self.headers
+            .get_all("Set-Cookie")
+            .into_iter()
+            .filter_map(|value| value.parse().ok())
+            .collect()
+    }
+
+    /// Returns the content coding the server applied to the body.
+    ///
+    /// # Returns
+    /// * `Some(Encoding::Identity)` - If there is no `Content-Encoding` header
+    /// * `Some(Encoding)` - The coding named by the `Content-Encoding` header
+    /// * `None` - If the coding is not one of the known `Encoding`s, or several
+    ///   codings were applied
+    pub fn content_encoding(&self) -> Option<Encoding> {
+        match self.headers.get("Content-Encoding") {
+            None => Some(Encoding::Identity),
+            Some(value) if value.contains(',') => None,
+            Some(value) => value.parse().ok(),
+        }
+    }
+
+    /// Returns the methods the server allows on the resource, as listed in the
+    /// `Allow` header, typically sent in response to an `OPTIONS` request.
+    ///
+    /// If there is no `Allow` header, the `Access-Control-Allow-Methods` header of a
+    /// CORS preflight response is used instead. Names that aren't standard methods
+    /// are returned as `HttpMethod::Other`.
+    ///
+    /// # Returns
+    /// The allowed methods in the order listed, empty if neither header is present
+    ///
+    /// # Example
+    /// ```no_run
+    /// use clienter::{HttpClient, HttpMethod};
+    ///
+    /// let client = HttpClient::new();
+    /// let request = client.request(HttpMethod::OPTIONS, "http://example.com/items");
+    /// let response = client.send(&request).unwrap();
+    /// if response.allowed_methods().contains(&HttpMethod::DELETE) {
+    ///     println!("items can be deleted");
+    /// }
+    /// ```
+    pub fn allowed_methods(&self) -> Vec<HttpMethod> {
+        let mut values = self.headers.get_all("Allow");
+        if values.is_empty() {
+            values = self.headers.get_all("Access-Control-Allow-Methods");
+        }
+
+        values
+            .into_iter()
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .filter(|method| !method.is_empty())
+            .map(|method| {
+                method
+                    .parse()
+                    .unwrap_or_else(|_| HttpMethod::Other(method.to_string()))
+            })
+            .collect()
+    }
+
+    /// Returns whether the response may have a body to read.
+    ///
+    /// There is no body when the status is 1xx, 204 or 304, when the request was HEAD,
+    /// or when `Content-Length` is 0. A keep-alive response with neither a
+    /// `Content-Length` nor chunked encoding also counts as having none, since reading
+    /// it would fail with `ResponseError::UnframedBody` rather than return anything.
+    /// Chunked and close-delimited bodies may still turn out to be empty.
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use clienter::HttpResponse;
+    ///
+    /// let raw = b"HTTP/1.1 204 No Content\r\n\r\n".to_vec();
+    /// let mut response = HttpResponse::build(Cursor::new(raw)).ok().unwrap();
+    /// assert!(!response.has_body());
+    /// let body = if response.has_body() {
+    ///     response.body().unwrap()
+    /// } else {
+    ///     Vec::new()
+    /// };
+    /// assert!(body.is_empty());
+    /// ```
+    pub fn has_body(&self) -> bool {
+        !matches!(
+            self.framing,
+            Framing::Empty | Framing::Length(0) | Framing::Unframed
+        )
+    }
+
     /// Reads the response body as a vector of bytes.
     ///
     /// # Returns
     /// * `Ok(Vec<u8>)` containing the raw body data
     /// * `Err(ResponseError)` if the body cannot be read
     pub fn body(&mut self) -> Result<Vec<u8>, ResponseError> {
-        self.buffer
-            .read_all()
-            .map_err(|_| ResponseError::InvalidBody)
+        self.read_body(None)
+    }
+
+    /// Reads the body even if the response cannot have one, for debugging servers that
+    /// send a body where the protocol forbids it.
+    ///
+    /// The body of a response to a HEAD request, or with a 1xx, 204 or 304 status, is
+    /// normally empty whatever the server sends. This reads it as if the rule didn't
+    /// apply: as many bytes as `Content-Length` says, chunks if it is chunked, and
+    /// otherwise until the server closes the connection. Other responses are read as
+    /// with `body`.
+    ///
+    /// This may block until a timeout if the server did in fact send nothing, as a
+    /// conforming server answering HEAD with the `Content-Length` of the full resource
+    /// does. The client's `read_timeout` and `total_timeout` still bound the wait.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<u8>)` containing whatever body the server sent
+    /// * `Err(ResponseError)` if the body cannot be read
+    ///
+    /// # Example
+    /// ```
+    /// use std::io::Cursor;
+    /// use clienter::HttpResponse;
+    ///
+    /// let raw = b"HTTP/1.1 204 No Content\r\nContent-Length: 5\r\n\r\noops!".to_vec();
+    /// let mut response = HttpResponse::build(Cursor::new(raw)).ok().unwrap();
+    /// assert_eq!(response.force_read_body().unwrap(), b"oops!");
+    /// ```
+    pub fn force_read_body(&mut self) -> Result<Vec<u8>, ResponseError> {
+        if self.framing == Framing::Empty {
+            self.framing = Framing::from_headers(&self.headers, true)?;
+            if let Some(mode) = self.framing.body_mode() {
+                self.buffer.set_body_mode(mode);
+            }
+        }
+        self.read_body(None)
+    }
+
+    /// Reads the response body and decodes it according to its `Content-Encoding`.
+    ///
+    /// Bodies without a `Content-Encoding` (or with `identity`) are returned as is.
+    /// Decoding gzip and deflate bodies requires the `gzip` feature, and decoding
+    /// Brotli (`br`) bodies requires the `brotli` feature.
+    ///
+    /// # Returns
+    /// * `Ok(Vec<u8>)` containing the decoded body
+    /// * `Err(ResponseError::UnsupportedEncoding)` if the coding is unknown or its
+    ///   feature is not enabled, in which case the body is left unread
+    /// * `Err(ResponseError::InvalidBody)` if the body cannot be read or decoded, or a
+    ///   gzip body's CRC-32 or length trailer doesn't match, as for a truncated download
+    ///
+    /// # Example
+    /// ```no_run
+    /// use clienter::{Encoding, HttpClient, HttpMethod};
+    ///
+    /// let mut client = HttpClient::new();
+    /// client.set_accept_encodings(&[(Encoding::Gzip, 1.0), (Encoding::Identity, 0.5)]);
+    /// let request = client.request(HttpMethod::GET, "http://example.com");
+    /// let mut response = client.send(&request).unwrap();
+    /// let body = response.decompressed_body().unwrap();
+    /// ```
+    pub fn decompressed_body(&mut self) -> Result<Vec<u8>, ResponseError> {
+        let decoder = self
+            .content_encoding()
+            .and_then(decoder_for)
+            .ok_or_else(|| {
+                let encoding = self.headers.get("Content-Encoding");
+                ResponseError::UnsupportedEncoding(encoding.cloned().unwrap_or_default())
+            })?;
+
+        decoder(self.body()?).ok_or(ResponseError::InvalidBody)
+    }
+
+    /// Reads the response body as a vector of bytes, bounding the read by `timeout`.
+    ///
+    /// The limit applies to the body alone, whatever the timeouts configured on the
+    /// client: a read that blocks is interrupted once `timeout` has passed, even with no
+    /// `read_timeout`. On a stream that isn't a `TcpStream`, given to `HttpClient::send_on`
+    /// or returned by a custom `connector`, the limit is only checked between reads.
+    ///
+    /// # Arguments
+    /// * `timeout` - The maximum time to spend reading the body
+    ///
+    /// # Returns
+    /// * `Ok(Vec<u8>)` containing the raw body data
+    /// * `Err(ResponseError::Timeout)` if the body was not read in full within `timeout`
+    /// * `Err(ResponseError)` if the body cannot be read
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use clienter::{HttpClient, HttpMethod};
+    ///
+    /// let client = HttpClient::new();
+    /// let request = client.request(HttpMethod::GET, "http://example.com/large-file");
+    /// let mut response = client.send(&request).unwrap();
+    /// let body = response.body_with_timeout(Duration::from_secs(2)).unwrap();
+    /// ```
+    pub fn body_with_timeout(&mut self, timeout: Duration) -> Result<Vec<u8>, ResponseError> {
+        let deadline = Instant::now() + timeout;
+        if let Some(body_deadline) = &self.body_deadline {
+            body_deadline.set(Some(deadline));
+        }
+        let body = self.read_body(Some(deadline));
+        if let Some(body_deadline) = &self.body_deadline {
+            body_deadline.set(None);
+        }
+        body
+    }
+
+    /// Reads the whole body into memory so the response no longer depends on the connection.
+    ///
+    /// Later calls to `body()` return the buffered bytes without touching the network.
+    ///
+    /// # Returns
+    /// The buffer the body was read from, holding whatever followed the body
+    pub(crate) fn buffer_body(&mut self) -> Result<StreamBuffer, ResponseError> {
+        let body = self.body()?;
+        let buffer = std::mem::replace(
+            &mut self.buffer,
+            StreamBuffer::with_prefill(Box::new(std::io::empty()), body),
+        );
+        // The buffered body is already decoded, so it simply runs to the end
+        self.framing = Framing::UntilClose;
+        // The completion event has already been reported for this body
+        self.on_event = None;
+        // The connection is released along with the old buffer
+        self.connection = None;
+        Ok(buffer)
+    }
+
+    /// Returns the raw body bytes kept from the last read of the body.
+    ///
+    /// The body is only kept when the client's `retain_body` option is enabled. This
+    /// allows logging the exact bytes received after the body has been consumed by
+    /// `body_as_string` or `decompressed_body`. The bytes are as received, before any
+    /// content coding is decoded.
+    ///
+    /// # Returns
+    /// The body bytes, or an empty slice if `retain_body` is disabled, the body has not
+    /// been read yet, or it was streamed with `copy_to`
+    ///
+    /// # Example
+    /// ```no_run
+    /// use clienter::{HttpClient, HttpMethod};
+    ///
+    /// let mut client = HttpClient::new();
+    /// client.retain_body = true;
+    /// let request = client.request(HttpMethod::GET, "http://example.com");
+    /// let mut response = client.send(&request).unwrap();
+    ///
+    /// let text = response.body_as_string().unwrap();
+    /// println!("{} bytes: {}", response.raw_body().len(), text);
+    /// ```
+    pub fn raw_body(&self) -> &[u8] {
+        &self.retained_body
+    }
+
+    /// Streams the body into a writer without holding all of it in memory.
+    ///
+    /// The body is framed in the same way as for `body()`: chunked bodies are decoded
+    /// and `Content-Length` is respected. This suits downloading large bodies to disk.
+    ///
+    /// # Arguments
+    /// * `w` - The writer the body is copied to
+    ///
+    /// # Returns
+    /// * `Ok(u64)` - The number of body bytes copied
+    /// * `Err(std::io::Error)` - If reading the body or writing it fails. A body that
+    ///   cannot be framed fails with `ErrorKind::InvalidData` wrapping
+    ///   `ResponseError::UnframedBody`
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::fs::File;
+    /// use clienter::{HttpClient, HttpMethod};
+    ///
+    /// let client = HttpClient::new();
+    /// let request = client.request(HttpMethod::GET, "http://example.com/archive.tar");
+    /// let mut response = client.send(&request).unwrap();
+    /// let copied = response.copy_to(&mut File::create("archive.tar").unwrap()).unwrap();
+    /// println!("saved {} bytes", copied);
+    /// ```
+    pub fn copy_to(&mut self, w: &mut impl Write) -> Result<u64, std::io::Error> {
+        self.copy_body(w)
+    }
+
+    /// Reads the body piece by piece, passing each piece to a callback as it arrives.
+    ///
+    /// The body is framed in the same way as for `body()`, and the callback is never
+    /// given an empty slice. The pieces together make up the whole body, but how it is
+    /// split into pieces depends on how the data arrives and is not guaranteed to match
+    /// the chunks of a chunked body. Returning `ControlFlow::Break` stops reading; the
+    /// rest of the body is left unread.
+    ///
+    /// # Arguments
+    /// * `f` - The callback, called with each piece of the body
+    ///
+    /// # Returns
+    /// * `Ok(())` - If the whole body was read, or the callback stopped reading
+    /// * `Err(ResponseError)` - If the body cannot be read
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::ops::ControlFlow;
+    /// use clienter::{HttpClient, HttpMethod};
+    ///
+    /// let client = HttpClient::new();
+    /// let request = client.request(HttpMethod::GET, "http://example.com/events.ndjson");
+    /// let mut response = client.send(&request).unwrap();
+    ///
+    /// let mut lines = 0;
+    /// response
+    ///     .for_each_chunk(|chunk| {
+    ///         lines += chunk.iter().filter(|&&byte| byte == b'\n').count();
+    ///         if lines < 1000 {
+    ///             ControlFlow::Continue(())
+    ///         } else {
+    ///             ControlFlow::Break(())
+    ///         }
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn for_each_chunk(
+        &mut self,
+        f: impl FnMut(&[u8]) -> ControlFlow<()>,
+    ) -> Result<(), ResponseError> {
+        if self.framing == Framing::Unframed {
+            return Err(ResponseError::UnframedBody);
+        }
+
+        let mut sink = ChunkSink {
+            callback: f,
+            stopped: false,
+        };
+        match self.copy_body(&mut sink) {
+            Err(_) if sink.stopped => Ok(()),
+            Err(err) => Err(ResponseError::from_io(err, ResponseError::InvalidBody)),
+            Ok(_) => Ok(()),
+        }
+    }
+
+    /// Reads the rest of the body, stopping once `deadline` has passed.
+    fn read_body(&mut self, deadline: Option<Instant>) -> Result<Vec<u8>, ResponseError> {
+        if self.framing == Framing::Unframed {
+            return Err(ResponseError::UnframedBody);
+        }
+        let body = self
+            .buffer
+            .read_body(deadline)
+            .map_err(|err| ResponseError::from_io(err, ResponseError::InvalidBody))?;
+
+        if self.retain_body {
+            self.retained_body = body.clone();
+        }
+        self.complete();
+        Ok(body)
+    }
+
+    /// Copies the rest of the body to `out`.
+    fn copy_body(&mut self, out: &mut dyn Write) -> Result<u64, std::io::Error> {
+        if self.framing == Framing::Unframed {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                ResponseError::UnframedBody,
+            ));
+        }
+        let copied = self.buffer.copy_body(None, out)?;
+
+        self.complete();
+        Ok(copied)
+    }
+
+    /// Notifies the event hook that the whole body has been read.
+    fn complete(&self) {
+        if let Some(on_event) = &self.on_event {
+            on_event(ClientEvent::ResponseComplete {
+                elapsed: self.started.elapsed(),
+            });
+        }
+    }
+
+    /// Hands back the connection the response was read from, such as after a
+    /// `101 Switching Protocols` response, so another protocol can be spoken over it.
+    ///
+    /// Bytes the server sent after the response headers that were already read into the
+    /// response's buffer are returned first by the stream, so nothing is lost. Any unread
+    /// body is not skipped; it is the first thing read from the stream. Once a framed
+    /// body has been read, the stream starts with exactly the bytes that followed it,
+    /// so the next of several pipelined responses can be read with `HttpResponse::build`. The stream keeps
+    /// the client's `total_timeout`, if one was set.
+    ///
+    /// # Returns
+    /// The underlying stream
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::io::{Read, Write};
+    /// use clienter::{HttpClient, HttpMethod, StatusCode};
+    ///
+    /// let client = HttpClient::new();
+    /// let mut request = client.request(HttpMethod::GET, "http://example.com/chat");
+    /// request.headers.insert("Connection".to_string(), "Upgrade".to_string());
+    /// request.headers.insert("Upgrade".to_string(), "websocket".to_string());
+    /// let response = client.send(&request).unwrap();
+    ///
+    /// if response.status == StatusCode::SwitchingProtocols101 {
+    ///     let mut stream = response.into_stream();
+    ///     stream.write_all(b"hello").unwrap();
+    /// }
+    /// ```
+    pub fn into_stream(self) -> Box<dyn ReadWrite> {
+        let (stream, remainder) = self.buffer.into_parts();
+        Box::new(UpgradedStream {
+            remainder: Cursor::new(remainder),
+            stream,
+            _connection: self.connection,
+        })
+    }
+
+    /// Reads the body of an error response and deserializes it from JSON.
+    ///
+    /// APIs commonly describe a failed request in a JSON body sent with a 4xx or 5xx
+    /// status. This reads that body in one step once the status shows the request
+    /// failed. Requires the `json` feature.
+    ///
+    /// # Returns
+    /// * `Ok(T)` - The deserialized error payload
+    /// * `Err(ResponseError::UnexpectedStatus)` - If the status is not a 4xx or 5xx
+    ///   error, in which case the body is left unread
+    /// * `Err(ResponseError::InvalidJson)` - If the body is not valid JSON for `T`
+    /// * `Err(ResponseError)` - If the body cannot be read
+    ///
+    /// # Example
+    /// ```no_run
+    /// use clienter::{HttpClient, HttpMethod};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct ApiError {
+    ///     code: String,
+    ///     message: String,
+    /// }
+    ///
+    /// let client = HttpClient::new();
+    /// let request = client.request(HttpMethod::GET, "http://api.example.com/items/7");
+    /// let mut response = client.send(&request).unwrap();
+    /// if !response.status.is_success() {
+    ///     let error: ApiError = response.error_body_json().unwrap();
+    ///     eprintln!("{} failed with {}: {}", response.status, error.code, error.message);
+    /// }
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn error_body_json<T>(&mut self) -> Result<T, ResponseError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        if !(400..600).contains(&self.status.as_u16()) {
+            return Err(ResponseError::UnexpectedStatus(self.status));
+        }
+        serde_json::from_slice(&self.body()?)
+            .map_err(|err| ResponseError::InvalidJson(err.to_string()))
     }
 
     /// Reads the response body and converts it to a String.
@@ -113,8 +1349,6 @@ impl HttpResponse {
     /// * `Ok(String)` containing the body as a UTF-8 string
     /// * `Err(ResponseError)` if the body cannot be read or is not valid UTF-8
     pub fn body_as_string(&mut self) -> Result<String, ResponseError> {
-        self.buffer
-            .read_all_string()
-            .map_err(|_| ResponseError::InvalidBody)
+        String::from_utf8(self.body()?).map_err(|_| ResponseError::InvalidBody)
     }
 }
@@ -16,137 +16,248 @@ use std::fmt::Display;
 /// - 3xx: Redirection responses
 /// - 4xx: Client error responses
 /// - 5xx: Server error responses
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum StatusCode {
     /// 100 Continue
-    Continue100,
+    Continue100 = 100,
     /// 101 Switching Protocols
-    SwitchingProtocols101,
+    SwitchingProtocols101 = 101,
     /// 102 Processing
-    Processing102,
+    Processing102 = 102,
     /// 103 Early Hints
-    EarlyHints103,
+    EarlyHints103 = 103,
 
     /// 200 OK
-    Ok200,
+    Ok200 = 200,
     /// 201 Created
-    Created201,
+    Created201 = 201,
     /// 202 Accepted
-    Accepted202,
+    Accepted202 = 202,
     /// 203 Non-Authoritative Information
-    NonAuthoritativeInformation203,
+    NonAuthoritativeInformation203 = 203,
     /// 204 No Content
-    NoContent204,
+    NoContent204 = 204,
     /// 205 Reset Content
-    ResetContent205,
+    ResetContent205 = 205,
     /// 206 Partial Content
-    PartialContent206,
+    PartialContent206 = 206,
     /// 207 Multi-Status
-    MultiStatus207,
+    MultiStatus207 = 207,
     /// 208 Already Reported
-    AlreadyReported208,
+    AlreadyReported208 = 208,
     /// 226 IM Used
-    ImUsed226,
+    ImUsed226 = 226,
 
     /// 300 Multiple Choices
-    MultipleChoices300,
+    MultipleChoices300 = 300,
     /// 301 Moved Permanently
-    MovedPermanently301,
+    MovedPermanently301 = 301,
     /// 302 Found
-    Found302,
+    Found302 = 302,
     /// 303 See Other
-    SeeOther303,
+    SeeOther303 = 303,
     /// 304 Not Modified
-    NotModified304,
+    NotModified304 = 304,
     /// 305 Use Proxy
-    UseProxy305,
+    UseProxy305 = 305,
     /// 307 Temporary Redirect
-    TemporaryRedirect307,
+    TemporaryRedirect307 = 307,
     /// 308 Permanent Redirect
-    PermanentRedirect308,
+    PermanentRedirect308 = 308,
 
     /// 400 Bad Request
-    BadRequest400,
+    BadRequest400 = 400,
     /// 401 Unauthorized
-    Unauthorized401,
+    Unauthorized401 = 401,
     /// 402 Payment Required
-    PaymentRequired402,
+    PaymentRequired402 = 402,
     /// 403 Forbidden
-    Forbidden403,
+    Forbidden403 = 403,
     /// 404 Not Found
-    NotFound404,
+    NotFound404 = 404,
     /// 405 Method Not Allowed
-    MethodNotAllowed405,
+    MethodNotAllowed405 = 405,
     /// 406 Not Acceptable
-    NotAcceptable406,
+    NotAcceptable406 = 406,
     /// 407 Proxy Authentication Required
-    ProxyAuthenticationRequired407,
+    ProxyAuthenticationRequired407 = 407,
     /// 408 Request Timeout
-    RequestTimeout408,
+    RequestTimeout408 = 408,
     /// 409 Conflict
-    Conflict409,
+    Conflict409 = 409,
     /// 410 Gone
-    Gone410,
+    Gone410 = 410,
     /// 411 Length Required
-    LengthRequired411,
+    LengthRequired411 = 411,
     /// 412 Precondition Failed
-    PrecondiditionFailed412,
+    PrecondiditionFailed412 = 412,
     /// 413 Payload Too Large
-    PayloadTooLarge413,
+    PayloadTooLarge413 = 413,
     /// 414 URI Too Long
-    UriTooLong414,
+    UriTooLong414 = 414,
     /// 415 Unsupported Media Type
-    UnsupportedMediaType415,
+    UnsupportedMediaType415 = 415,
     /// 416 Range Not Satisfiable
-    RangeNotSatisfiable416,
+    RangeNotSatisfiable416 = 416,
     /// 417 Expectation Failed
-    ExpectationFailed417,
+    ExpectationFailed417 = 417,
     /// 421 Misdirected Request
-    MisdirectedRequest421,
+    MisdirectedRequest421 = 421,
     /// 422 Unprocessable Entity
-    UnprocessableEntity422,
+    UnprocessableEntity422 = 422,
     /// 423 Locked
-    Locked423,
+    Locked423 = 423,
     /// 424 Failed Dependency
-    FailedDependency424,
+    FailedDependency424 = 424,
     /// 425 Too Early
-    TooEarly425,
+    TooEarly425 = 425,
     /// 426 Upgrade Required
-    UpgradeRequired426,
+    UpgradeRequired426 = 426,
     /// 428 Precondition Required
-    PreconditionRequired428,
+    PreconditionRequired428 = 428,
     /// 429 Too Many Requests
-    TooManyRequests429,
+    TooManyRequests429 = 429,
     /// 431 Request Header Fields Too Large
-    RequestHeaderFieldsTooLarge431,
+    RequestHeaderFieldsTooLarge431 = 431,
     /// 451 Unavailable For Legal Reasons
-    UnavailableForLegalReasons451,
+    UnavailableForLegalReasons451 = 451,
 
     /// 500 Internal Server Error
-    InternalServerError500,
+    InternalServerError500 = 500,
     /// 501 Not Implemented
-    NotImplemented501,
+    NotImplemented501 = 501,
     /// 502 Bad Gateway
-    BadGateway502,
+    BadGateway502 = 502,
     /// 503 Service Unavailable
-    ServiceUnavailable503,
+    ServiceUnavailable503 = 503,
     /// 504 Gateway Timeout
-    GatewayTimeout504,
+    GatewayTimeout504 = 504,
     /// 505 HTTP Version Not Supported
-    HttpVersionNotSupported505,
+    HttpVersionNotSupported505 = 505,
     /// 506 Variant Also Negotiates
-    VariantAlsoNegotiates506,
+    VariantAlsoNegotiates506 = 506,
     /// 507 Insufficient Storage
-    InsufficientStorage507,
+    InsufficientStorage507 = 507,
     /// 508 Loop Detected
-    LoopDetected508,
+    LoopDetected508 = 508,
     /// 510 Not Extended
-    NotExtended510,
+    NotExtended510 = 510,
     /// 511 Network Authentication Required
-    NetworkAuthenticationRequired511,
+    NetworkAuthenticationRequired511 = 511,
 }
 
 impl StatusCode {
+    /// Returns the numerical value of the status code.
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::StatusCode;
+    ///
+    /// assert_eq!(StatusCode::NotFound404.as_u16(), 404);
+    /// ```
+    pub fn as_u16(&self) -> u16 {
+        *self as u16
+    }
+
+    /// Returns the canonical reason phrase of the status code, such as "Not Found".
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::StatusCode;
+    ///
+    /// assert_eq!(StatusCode::NotFound404.reason_phrase(), "Not Found");
+    /// ```
+    pub fn reason_phrase(&self) -> &'static str {
+        // Every variant has a registered phrase
+        Self::reason_for(self.as_u16()).unwrap_or_default()
+    }
+
+    /// Returns the canonical reason phrase for a numerical status code.
+    ///
+    /// This is useful for codes received from a server, which may not have a
+    /// `StatusCode` variant.
+    ///
+    /// # Arguments
+    /// * `code` - The numerical status code
+    ///
+    /// # Returns
+    /// The reason phrase, or `None` if the code has no registered phrase
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::StatusCode;
+    ///
+    /// assert_eq!(StatusCode::reason_for(418), None);
+    /// assert_eq!(StatusCode::reason_for(503), Some("Service Unavailable"));
+    /// ```
+    pub fn reason_for(code: u16) -> Option<&'static str> {
+        let reason = match code {
+            100 => "Continue",
+            101 => "Switching Protocols",
+            102 => "Processing",
+            103 => "Early Hints",
+            200 => "OK",
+            201 => "Created",
+            202 => "Accepted",
+            203 => "Non-Authoritative Information",
+            204 => "No Content",
+            205 => "Reset Content",
+            206 => "Partial Content",
+            207 => "Multi-Status",
+            208 => "Already Reported",
+            226 => "IM Used",
+            300 => "Multiple Choices",
+            301 => "Moved Permanently",
+            302 => "Found",
+            303 => "See Other",
+            304 => "Not Modified",
+            305 => "Use Proxy",
+            307 => "Temporary Redirect",
+            308 => "Permanent Redirect",
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            402 => "Payment Required",
+            403 => "Forbidden",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            406 => "Not Acceptable",
+            407 => "Proxy Authentication Required",
+            408 => "Request Timeout",
+            409 => "Conflict",
+            410 => "Gone",
+            411 => "Length Required",
+            412 => "Precondition Failed",
+            413 => "Payload Too Large",
+            414 => "URI Too Long",
+            415 => "Unsupported Media Type",
+            416 => "Range Not Satisfiable",
+            417 => "Expectation Failed",
+            421 => "Misdirected Request",
+            422 => "Unprocessable Entity",
+            423 => "Locked",
+            424 => "Failed Dependency",
+            425 => "Too Early",
+            426 => "Upgrade Required",
+            428 => "Precondition Required",
+            429 => "Too Many Requests",
+            431 => "Request Header Fields Too Large",
+            451 => "Unavailable For Legal Reasons",
+            500 => "Internal Server Error",
+            501 => "Not Implemented",
+            502 => "Bad Gateway",
+            503 => "Service Unavailable",
+            504 => "Gateway Timeout",
+            505 => "HTTP Version Not Supported",
+            506 => "Variant Also Negotiates",
+            507 => "Insufficient Storage",
+            508 => "Loop Detected",
+            510 => "Not Extended",
+            511 => "Network Authentication Required",
+            _ => return None,
+        };
+        Some(reason)
+    }
+
     /// Determines if the status code represents a successful response (2xx range).
     ///
     /// # Returns
@@ -160,19 +271,19 @@ impl StatusCode {
     /// assert!(status.is_success());
     /// ```
     pub fn is_success(&self) -> bool {
-        match self {
-            StatusCode::Ok200 => true,
-            StatusCode::Created201 => true,
-            StatusCode::Accepted202 => true,
-            StatusCode::NonAuthoritativeInformation203 => true,
-            StatusCode::NoContent204 => true,
-            StatusCode::ResetContent205 => true,
-            StatusCode::PartialContent206 => true,
-            StatusCode::MultiStatus207 => true,
-            StatusCode::AlreadyReported208 => true,
-            StatusCode::ImUsed226 => true,
-            _ => false,
-        }
+        matches!(
+            self,
+            StatusCode::Ok200
+                | StatusCode::Created201
+                | StatusCode::Accepted202
+                | StatusCode::NonAuthoritativeInformation203
+                | StatusCode::NoContent204
+                | StatusCode::ResetContent205
+                | StatusCode::PartialContent206
+                | StatusCode::MultiStatus207
+                | StatusCode::AlreadyReported208
+                | StatusCode::ImUsed226
+        )
     }
 }
 
@@ -278,80 +389,24 @@ impl Display for StatusCode {
     /// assert_eq!(status.to_string(), "200 OK");
     /// ```
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            StatusCode::Continue100 => write!(f, "100 Continue"),
-            StatusCode::SwitchingProtocols101 => write!(f, "101 Switching Protocols"),
-            StatusCode::Processing102 => write!(f, "102 Processing"),
-            StatusCode::EarlyHints103 => write!(f, "103 Early Hints"),
-
-            StatusCode::Ok200 => write!(f, "200 OK"),
-            StatusCode::Created201 => write!(f, "201 Created"),
-            StatusCode::Accepted202 => write!(f, "202 Accepted"),
-            StatusCode::NonAuthoritativeInformation203 => {
-                write!(f, "203 Non-Authoritative Information")
-            }
-            StatusCode::NoContent204 => write!(f, "204 No Content"),
-            StatusCode::ResetContent205 => write!(f, "205 Reset Content"),
-            StatusCode::PartialContent206 => write!(f, "206 Partial Content"),
-            StatusCode::MultiStatus207 => write!(f, "207 Multi-Status"),
-            StatusCode::AlreadyReported208 => write!(f, "208 Already Reported"),
-            StatusCode::ImUsed226 => write!(f, "226 IM Used"),
+        write!(f, "{} {}", self.as_u16(), self.reason_phrase())
+    }
+}
 
-            StatusCode::MultipleChoices300 => write!(f, "300 Multiple Choices"),
-            StatusCode::MovedPermanently301 => write!(f, "301 Moved Permanently"),
-            StatusCode::Found302 => write!(f, "302 Found"),
-            StatusCode::SeeOther303 => write!(f, "303 See Other"),
-            StatusCode::NotModified304 => write!(f, "304 Not Modified"),
-            StatusCode::UseProxy305 => write!(f, "305 Use Proxy"),
-            StatusCode::TemporaryRedirect307 => write!(f, "307 Temporary Redirect"),
-            StatusCode::PermanentRedirect308 => write!(f, "308 Permanent Redirect"),
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            StatusCode::BadRequest400 => write!(f, "400 Bad Request"),
-            StatusCode::Unauthorized401 => write!(f, "401 Unauthorized"),
-            StatusCode::PaymentRequired402 => write!(f, "402 Payment Required"),
-            StatusCode::Forbidden403 => write!(f, "403 Forbidden"),
-            StatusCode::NotFound404 => write!(f, "404 Not Found"),
-            StatusCode::MethodNotAllowed405 => write!(f, "405 Method Not Allowed"),
-            StatusCode::NotAcceptable406 => write!(f, "406 Not Acceptable"),
-            StatusCode::ProxyAuthenticationRequired407 => {
-                write!(f, "407 Proxy Authentication Required")
-            }
-            StatusCode::RequestTimeout408 => write!(f, "408 Request Timeout"),
-            StatusCode::Conflict409 => write!(f, "409 Conflict"),
-            StatusCode::Gone410 => write!(f, "410 Gone"),
-            StatusCode::LengthRequired411 => write!(f, "411 Length Required"),
-            StatusCode::PrecondiditionFailed412 => write!(f, "412 Precondition Failed"),
-            StatusCode::PayloadTooLarge413 => write!(f, "413 Payload Too Large"),
-            StatusCode::UriTooLong414 => write!(f, "414 URI Too Long"),
-            StatusCode::UnsupportedMediaType415 => write!(f, "415 Unsupported Media Type"),
-            StatusCode::RangeNotSatisfiable416 => write!(f, "416 Range Not Satisfiable"),
-            StatusCode::ExpectationFailed417 => write!(f, "417 Expectation Failed"),
-            StatusCode::MisdirectedRequest421 => write!(f, "421 Misdirected Request"),
-            StatusCode::UnprocessableEntity422 => write!(f, "422 Unprocessable Entity"),
-            StatusCode::Locked423 => write!(f, "423 Locked"),
-            StatusCode::FailedDependency424 => write!(f, "424 Failed Dependency"),
-            StatusCode::TooEarly425 => write!(f, "425 Too Early"),
-            StatusCode::UpgradeRequired426 => write!(f, "426 Upgrade Required"),
-            StatusCode::PreconditionRequired428 => write!(f, "428 Precondition Required"),
-            StatusCode::TooManyRequests429 => write!(f, "429 Too Many Requests"),
-            StatusCode::RequestHeaderFieldsTooLarge431 => {
-                write!(f, "431 Request Header Fields Too Large")
-            }
-            StatusCode::UnavailableForLegalReasons451 => {
-                write!(f, "451 Unavailable For Legal Reasons")
-            }
-            StatusCode::InternalServerError500 => write!(f, "500 Internal Server Error"),
-            StatusCode::NotImplemented501 => write!(f, "501 Not Implemented"),
-            StatusCode::BadGateway502 => write!(f, "502 Bad Gateway"),
-            StatusCode::ServiceUnavailable503 => write!(f, "503 Service Unavailable"),
-            StatusCode::GatewayTimeout504 => write!(f, "504 Gateway Timeout"),
-            StatusCode::HttpVersionNotSupported505 => write!(f, "505 HTTP Version Not Supported"),
-            StatusCode::VariantAlsoNegotiates506 => write!(f, "506 Variant Also Negotiates"),
-            StatusCode::InsufficientStorage507 => write!(f, "507 Insufficient Storage"),
-            StatusCode::LoopDetected508 => write!(f, "508 Loop Detected"),
-            StatusCode::NotExtended510 => write!(f, "510 Not Extended"),
-            StatusCode::NetworkAuthenticationRequired511 => {
-                write!(f, "511 Network Authentication Required")
+    #[test]
+    fn test_reason_table_matches_variants() {
+        for code in 100..600 {
+            match (StatusCode::try_from(code), StatusCode::reason_for(code)) {
+                (Ok(status), Some(reason)) => {
+                    assert_eq!(status.as_u16(), code);
+                    assert_eq!(status.to_string(), format!("{} {}", code, reason));
+                }
+                (Err(_), None) => {}
+                mismatch => panic!("{}: {:?}", code, mismatch),
             }
         }
     }
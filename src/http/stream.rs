@@ -0,0 +1,17 @@
+//! Transport abstraction for HTTP exchanges.
+//!
+//! The HTTP logic in this crate only needs something it can read from and write to,
+//! so any connected transport (a `TcpStream`, a tunnel, an in-memory pipe) can be
+//! used to carry a request.
+
+use std::any::Any;
+use std::io::{Read, Write};
+
+/// A bidirectional byte stream that an HTTP exchange can be performed on.
+///
+/// This is implemented automatically for every `'static` type that is
+/// `Read + Write + Send`, such as `std::net::TcpStream`. The client recognises a
+/// `TcpStream` behind a `ReadWrite` so that its timeouts can interrupt blocking reads.
+pub trait ReadWrite: Read + Write + Send + Any {}
+
+impl<T> ReadWrite for T where T: Read + Write + Send + Any {}
@@ -0,0 +1,129 @@
+//! The request target, which follows the method in the request line.
+//!
+//! RFC 7230 section 5.3 defines four forms of request target. Which one a request
+//! uses depends on its method and on whether it is sent to a proxy; see
+//! `HttpRequest::request_target`.
+
+use super::{HttpMethod, Uri};
+
+/// The target of a request, as written in its request line.
+///
+/// # Example
+/// ```
+/// use clienter::{HttpMethod, HttpRequest, RequestTarget};
+///
+/// let request = HttpRequest::new(HttpMethod::CONNECT, "http://example.com:443");
+/// assert_eq!(
+///     request.request_target(),
+///     RequestTarget::Authority("example.com:443".to_string())
+/// );
+/// assert_eq!(request.get_request_line(), "CONNECT example.com:443 HTTP/1.1");
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum RequestTarget {
+    /// The encoded path and query, such as `/search?q=rust`, used for requests sent
+    /// directly to the origin server
+    Origin(String),
+    /// The whole URI, such as `http://example.com/search?q=rust`, used for requests
+    /// sent through a forward proxy
+    Absolute(String),
+    /// The host and port, such as `example.com:443`, used by CONNECT requests
+    Authority(String),
+    /// `*`, used by an OPTIONS request about the server as a whole rather than one
+    /// of its resources
+    Asterisk,
+}
+
+impl RequestTarget {
+    /// Determines the target for a request to `uri`.
+    ///
+    /// # Arguments
+    /// * `method` - The method of the request
+    /// * `uri` - The URI the request is sent to
+    /// * `absolute_form` - Whether the request goes through a forward proxy
+    ///
+    /// # Returns
+    /// The authority-form for CONNECT, the asterisk-form for OPTIONS to the path `*`,
+    /// otherwise the absolute-form if `absolute_form` is set and the origin-form if not
+    pub(crate) fn for_uri(method: &HttpMethod, uri: &Uri, absolute_form: bool) -> Self {
+        if *method == HttpMethod::CONNECT {
+            return RequestTarget::Authority(uri.get_addr());
+        }
+        if *method == HttpMethod::OPTIONS && uri.path == "*" && uri.query.is_none() {
+            return RequestTarget::Asterisk;
+        }
+
+        let mut target = format!("/{}", uri.get_encoded_path());
+        if let Some(query) = &uri.query {
+            target.push('?');
+            target.push_str(query);
+        }
+        if absolute_form {
+            RequestTarget::Absolute(format!("{}://{}{}", uri.protocol, uri.get_host(), target))
+        } else {
+            RequestTarget::Origin(target)
+        }
+    }
+}
+
+impl std::fmt::Display for RequestTarget {
+    /// Formats the target as it appears in the request line.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RequestTarget::Origin(target)
+            | RequestTarget::Absolute(target)
+            | RequestTarget::Authority(target) => write!(f, "{}", target),
+            RequestTarget::Asterisk => write!(f, "*"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(method: HttpMethod, uri: &str, absolute_form: bool) -> RequestTarget {
+        RequestTarget::for_uri(&method, &uri.parse().unwrap(), absolute_form)
+    }
+
+    #[test]
+    fn test_origin_form() {
+        assert_eq!(
+            target(HttpMethod::GET, "http://example.com/a b?q=1", false),
+            RequestTarget::Origin("/a%20b?q=1".to_string())
+        );
+        assert_eq!(
+            target(HttpMethod::GET, "http://example.com", false).to_string(),
+            "/"
+        );
+    }
+
+    #[test]
+    fn test_absolute_form() {
+        assert_eq!(
+            target(HttpMethod::GET, "http://example.com:8080/x?y", true),
+            RequestTarget::Absolute("http://example.com:8080/x?y".to_string())
+        );
+        assert_eq!(
+            target(HttpMethod::GET, "https://example.com:443/", true).to_string(),
+            "https://example.com/"
+        );
+    }
+
+    #[test]
+    fn test_authority_and_asterisk_forms() {
+        assert_eq!(
+            target(HttpMethod::CONNECT, "https://example.com/ignored", true),
+            RequestTarget::Authority("example.com:443".to_string())
+        );
+        assert_eq!(
+            target(HttpMethod::OPTIONS, "http://example.com/*", true),
+            RequestTarget::Asterisk
+        );
+        // Only OPTIONS uses the asterisk-form
+        assert_eq!(
+            target(HttpMethod::GET, "http://example.com/*", false).to_string(),
+            "/*"
+        );
+    }
+}
@@ -20,9 +20,13 @@
 
 use std::{fmt::Debug, str::FromStr};
 
-use crate::utils;
+use super::Authority;
+use crate::utils::{self, EncodeSet};
 
-/// Represents a URI with protocol, hostname, optional port, and path components.
+/// Represents a URI with protocol, hostname, optional port, path and optional query components.
+///
+/// The path is stored without its leading `/`, so `http://example.com` and
+/// `http://example.com/` both have an empty path and compare equal.
 ///
 /// # Examples
 ///
@@ -36,9 +40,12 @@ use crate::utils;
 #[derive(Debug, PartialEq, Clone)]
 pub struct Uri {
     pub protocol: super::protocol::Protocol,
+    /// The host as written in the URI, with an IPv6 address kept in brackets
     pub hostname: String,
     pub port: Option<u16>,
     pub path: String,
+    /// The query string without the leading `?`, stored in its encoded form
+    pub query: Option<String>,
 }
 
 /// Possible errors that can occur when parsing a URI
@@ -46,11 +53,149 @@ pub struct Uri {
 pub enum UriError {
     Empty,
     InvalidProtocol,
+    /// The host is empty or contains characters that cannot appear in a host name,
+    /// which usually means the scheme and host were left out (e.g. `/just/a/path`)
     InvalidHostname,
     InvalidPort,
+    /// A character that is not allowed in a URI, only reported by `Uri::parse_strict`
+    InvalidCharacter(char),
+}
+
+impl std::fmt::Display for UriError {
+    /// Formats the error as a human readable message.
+    ///
+    /// # Example
+    /// ```
+    /// use clienter::{Uri, UriError};
+    ///
+    /// let err = "/just/a/path".parse::<Uri>().unwrap_err();
+    /// assert_eq!(err, UriError::InvalidHostname);
+    /// assert!(err.to_string().contains("http://example.com/path"));
+    /// ```
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UriError::Empty => write!(f, "empty URI"),
+            UriError::InvalidProtocol => write!(f, "unsupported URI scheme"),
+            UriError::InvalidHostname => write!(
+                f,
+                "missing or invalid host, is the scheme or host missing? \
+                 expected a URI such as 'http://example.com/path'"
+            ),
+            UriError::InvalidPort => write!(f, "invalid port"),
+            UriError::InvalidCharacter(c) => write!(f, "invalid character {:?} in URI", c),
+        }
+    }
 }
 
+impl std::error::Error for UriError {}
+
 impl Uri {
+    /// Parses a list of URIs, one per line.
+    ///
+    /// Each line is trimmed and parsed leniently, as by `str::parse`. Blank lines and
+    /// lines starting with `#` are skipped. A line that fails to parse yields an error
+    /// in its place without affecting the others.
+    ///
+    /// # Arguments
+    /// * `input` - The text to parse
+    ///
+    /// # Returns
+    /// The result of parsing each remaining line, in order
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clienter::{Uri, UriError};
+    ///
+    /// let uris = Uri::parse_many("# links\nhttp://example.com/a\n\n  http://:80/\nexample.org\n");
+    /// assert_eq!(uris.len(), 3);
+    /// assert_eq!(uris[0].as_ref().unwrap().path, "a");
+    /// assert_eq!(uris[1], Err(UriError::InvalidHostname));
+    /// assert_eq!(uris[2].as_ref().unwrap().hostname, "example.org");
+    /// ```
+    pub fn parse_many(input: &str) -> Vec<Result<Uri, UriError>> {
+        input
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(str::parse)
+            .collect()
+    }
+
+    /// Parses a URI, rejecting anything that is not strictly RFC 3986 compliant.
+    ///
+    /// The default parser (`str::parse`) is lenient: it assumes `http` when the scheme
+    /// is missing and accepts characters such as spaces, which are encoded when the
+    /// request is sent. This parser instead requires an explicit scheme, and only
+    /// accepts characters allowed in a URI, with every `%` starting a valid
+    /// percent-encoded byte.
+    ///
+    /// # Arguments
+    /// * `s` - The URI to parse
+    ///
+    /// # Returns
+    /// * `Ok(Uri)` - If the URI is well-formed
+    /// * `Err(UriError::InvalidProtocol)` - If the scheme is missing or unsupported
+    /// * `Err(UriError::InvalidCharacter)` - If the URI contains a character that is not allowed
+    /// * `Err(UriError)` - If the URI is otherwise invalid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clienter::{Uri, UriError};
+    ///
+    /// assert!(Uri::parse_strict("http://example.com/a%20b").is_ok());
+    /// assert_eq!(Uri::parse_strict("example.com"), Err(UriError::InvalidProtocol));
+    /// assert_eq!(Uri::parse_strict("http://example.com/a b"), Err(UriError::InvalidCharacter(' ')));
+    /// ```
+    pub fn parse_strict(s: &str) -> Result<Uri, UriError> {
+        if s.is_empty() {
+            return Err(UriError::Empty);
+        }
+        if !s.contains("://") {
+            return Err(UriError::InvalidProtocol);
+        }
+
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            if c == '%' {
+                let hex = chars
+                    .clone()
+                    .take(2)
+                    .filter(char::is_ascii_hexdigit)
+                    .count();
+                if hex != 2 {
+                    return Err(UriError::InvalidCharacter('%'));
+                }
+            } else if !(c.is_ascii_alphanumeric() || "-._~:/?#[]@!$&'()*+,;=".contains(c)) {
+                return Err(UriError::InvalidCharacter(c));
+            }
+        }
+
+        s.parse()
+    }
+
+    /// Returns the port to connect to.
+    ///
+    /// This is the single source of truth for the port of a URI: the explicit port if
+    /// one was given, otherwise the default port of the protocol.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clienter::Uri;
+    ///
+    /// let uri: Uri = "https://example.com".parse().unwrap();
+    /// assert_eq!(uri.get_port(), 443);
+    ///
+    /// let uri: Uri = "http://example.com:8080".parse().unwrap();
+    /// assert_eq!(uri.get_port(), 8080);
+    /// ```
+    pub fn get_port(&self) -> u16 {
+        self.port
+            .unwrap_or_else(|| self.protocol.get_default_port())
+    }
+
     /// Returns the address string in the format "hostname:port".
     /// If port is not specified, uses the default port for the protocol.
     ///
@@ -66,14 +211,240 @@ impl Uri {
     /// assert_eq!(uri.get_addr(), "example.com:443");
     /// ```
     pub fn get_addr(&self) -> String {
-        match self.port {
-            Some(port) => format!("{}:{}", self.hostname, port),
-            None => format!("{}:{}", self.hostname, self.protocol.get_default_port()),
+        self.authority().to_string()
+    }
+
+    /// Returns the host and port of the URI, using the default port for the protocol
+    /// when none is given.
+    ///
+    /// Brackets around an IPv6 address are removed from the host, and added back when
+    /// the authority is formatted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clienter::{Authority, Uri};
+    ///
+    /// let uri: Uri = "http://localhost:8080/api".parse().unwrap();
+    /// assert_eq!(
+    ///     uri.authority(),
+    ///     Authority { host: "localhost".to_string(), port: 8080 }
+    /// );
+    /// ```
+    pub fn authority(&self) -> Authority {
+        let host = self
+            .hostname
+            .strip_prefix('[')
+            .and_then(|host| host.strip_suffix(']'))
+            .unwrap_or(&self.hostname);
+        Authority {
+            host: host.to_string(),
+            port: self.get_port(),
+        }
+    }
+
+    /// Returns the value for the `Host` header of a request to this URI.
+    ///
+    /// The port is included only when it differs from the default port of the protocol.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clienter::Uri;
+    ///
+    /// let uri: Uri = "http://example.com:80/path".parse().unwrap();
+    /// assert_eq!(uri.get_host(), "example.com");
+    ///
+    /// let uri: Uri = "http://localhost:8080".parse().unwrap();
+    /// assert_eq!(uri.get_host(), "localhost:8080");
+    /// ```
+    pub fn get_host(&self) -> String {
+        let authority = self.authority();
+        if authority.port == self.protocol.get_default_port() {
+            authority.host_literal()
+        } else {
+            authority.to_string()
         }
     }
 
+    /// Returns the name of the server to present during a TLS handshake (SNI).
+    ///
+    /// This is always the bare hostname and never includes a port.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clienter::Uri;
+    ///
+    /// let uri: Uri = "https://example.com:8443/".parse().unwrap();
+    /// assert_eq!(uri.get_server_name(), "example.com");
+    /// ```
+    pub fn get_server_name(&self) -> &str {
+        &self.hostname
+    }
+
+    /// Returns a copy of this URI with a different path.
+    ///
+    /// A leading `/` is optional.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clienter::Uri;
+    ///
+    /// let uri: Uri = "http://example.com/users?page=2".parse().unwrap();
+    /// let uri = uri.with_path("/teams");
+    /// assert_eq!(uri.to_string(), "http://example.com/teams?page=2");
+    /// ```
+    pub fn with_path(mut self, path: &str) -> Self {
+        self.path = path.trim_start_matches('/').to_string();
+        self
+    }
+
+    /// Resolves a reference, such as the `Location` of a redirect, against this URI.
+    ///
+    /// The reference may be:
+    /// * an absolute URI (`https://other.example/x`), which replaces this one
+    /// * scheme-relative (`//other.example/x`), which keeps only the scheme
+    /// * an absolute path (`/login`), which keeps the scheme, host and port and
+    ///   replaces the path and query
+    /// * a query (`?page=2`), which keeps the path and replaces the query
+    /// * a relative path (`edit`, `../up`), which is resolved against the directory
+    ///   of the current path
+    ///
+    /// `.` and `..` segments are removed from the result and any fragment is dropped,
+    /// as described in RFC 3986 section 5.2.
+    ///
+    /// # Arguments
+    /// * `reference` - The reference to resolve
+    ///
+    /// # Returns
+    /// * `Ok(Uri)` - The resolved URI
+    /// * `Err(UriError)` - If an absolute or scheme-relative reference is invalid
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clienter::Uri;
+    ///
+    /// let uri: Uri = "http://example.com:8080/docs/guide?x=1".parse().unwrap();
+    /// assert_eq!(uri.join("/login").unwrap().to_string(), "http://example.com:8080/login");
+    /// assert_eq!(uri.join("intro").unwrap().to_string(), "http://example.com:8080/docs/intro");
+    /// assert_eq!(uri.join("../b?y=2").unwrap().to_string(), "http://example.com:8080/b?y=2");
+    /// assert_eq!(uri.join("//cdn.example/a").unwrap().to_string(), "http://cdn.example/a");
+    /// assert_eq!(uri.join("https://other.example/").unwrap().to_string(), "https://other.example/");
+    /// ```
+    pub fn join(&self, reference: &str) -> Result<Uri, UriError> {
+        let reference = reference.trim();
+        let reference = match reference.split_once('#') {
+            Some((reference, _fragment)) => reference,
+            None => reference,
+        };
+
+        if let Some((scheme, _)) = reference.split_once("://") {
+            if !scheme.is_empty() && !scheme.contains(['/', '?']) {
+                return reference.parse();
+            }
+        }
+        if reference.starts_with("//") {
+            return format!("{}:{}", self.protocol, reference).parse();
+        }
+
+        let (path, query) = match reference.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (reference, None),
+        };
+        let path = if path.is_empty() {
+            self.path.clone()
+        } else if let Some(path) = path.strip_prefix('/') {
+            path.to_string()
+        } else {
+            match self.path.rsplit_once('/') {
+                Some((directory, _)) => format!("{}/{}", directory, path),
+                None => path.to_string(),
+            }
+        };
+        let query = match query {
+            Some(query) => (!query.is_empty()).then(|| query.to_string()),
+            None if reference.is_empty() => self.query.clone(),
+            None => None,
+        };
+
+        Ok(Uri {
+            protocol: self.protocol,
+            hostname: self.hostname.clone(),
+            port: self.port,
+            path: remove_dot_segments(&path),
+            query,
+        })
+    }
+
+    /// Returns whether two URIs have the same origin: the same scheme, host and port.
+    ///
+    /// Credentials such as the `Authorization` header should only be sent again when a
+    /// redirect stays on the same origin.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clienter::Uri;
+    ///
+    /// let uri: Uri = "http://example.com/a".parse().unwrap();
+    /// assert!(uri.same_origin(&uri.join("/b").unwrap()));
+    /// assert!(uri.same_origin(&"http://EXAMPLE.com:80/c".parse().unwrap()));
+    /// assert!(!uri.same_origin(&"https://example.com/a".parse().unwrap()));
+    /// ```
+    pub fn same_origin(&self, other: &Uri) -> bool {
+        self.protocol == other.protocol
+            && self.hostname.eq_ignore_ascii_case(&other.hostname)
+            && self.get_port() == other.get_port()
+    }
+
+    /// Returns a copy of this URI with a different port.
+    ///
+    /// `None` uses the default port of the protocol.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clienter::Uri;
+    ///
+    /// let uri: Uri = "http://example.com/".parse().unwrap();
+    /// assert_eq!(uri.clone().with_port(Some(8080)).get_addr(), "example.com:8080");
+    /// assert_eq!(uri.with_port(None).get_addr(), "example.com:80");
+    /// ```
+    pub fn with_port(mut self, port: Option<u16>) -> Self {
+        self.port = port;
+        self
+    }
+
+    /// Returns a copy of this URI with a different query string.
+    ///
+    /// The query is used as is, so it should already be encoded; a leading `?` is
+    /// optional. An empty query removes it. Use `set_query_pairs` to build a query
+    /// from unencoded key-value pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clienter::Uri;
+    ///
+    /// let uri: Uri = "http://example.com/search".parse().unwrap();
+    /// let uri = uri.with_query("q=rust&page=2");
+    /// assert_eq!(uri.to_string(), "http://example.com/search?q=rust&page=2");
+    /// assert_eq!(uri.with_query("").query, None);
+    /// ```
+    pub fn with_query(mut self, query: &str) -> Self {
+        let query = query.strip_prefix('?').unwrap_or(query);
+        self.query = (!query.is_empty()).then(|| query.to_string());
+        self
+    }
+
     /// Returns the path with proper URL encoding.
-    /// Encodes spaces as "%20" and percent signs as "%25".
+    ///
+    /// Characters that cannot appear in a path, such as spaces, are percent-encoded.
+    /// A `%` that already starts a percent-encoded byte (as in `%2F`) is kept, so paths
+    /// that are already encoded are sent unchanged; any other `%` is encoded as `%25`.
     ///
     /// # Examples
     ///
@@ -85,12 +456,130 @@ impl Uri {
     ///
     /// let uri: Uri = "http://example.com/50%discount".parse().unwrap();
     /// assert_eq!(uri.get_encoded_path(), "50%25discount");
+    ///
+    /// let uri: Uri = "http://example.com/files/a%2Fb".parse().unwrap();
+    /// assert_eq!(uri.get_encoded_path(), "files/a%2Fb");
     /// ```
     pub fn get_encoded_path(&self) -> String {
-        self.path.replace("%", "%25").replace(" ", "%20")
+        let bytes = self.path.as_bytes();
+        let mut encoded = String::with_capacity(self.path.len());
+        // Encode the runs between existing escapes, which are copied unchanged
+        let (mut start, mut i) = (0, 0);
+        while i < bytes.len() {
+            let is_escape = bytes[i] == b'%'
+                && bytes.get(i + 1).is_some_and(u8::is_ascii_hexdigit)
+                && bytes.get(i + 2).is_some_and(u8::is_ascii_hexdigit);
+            if is_escape {
+                encoded.push_str(&utils::percent_encode(
+                    &self.path[start..i],
+                    EncodeSet::PATH,
+                ));
+                encoded.push_str(&self.path[i..i + 3]);
+                i += 3;
+                start = i;
+            } else {
+                i += 1;
+            }
+        }
+        encoded.push_str(&utils::percent_encode(&self.path[start..], EncodeSet::PATH));
+        encoded
+    }
+
+    /// Replaces the query with key-value pairs encoded as `application/x-www-form-urlencoded`.
+    ///
+    /// Each key and value is percent-encoded (with spaces written as `+`) so reserved
+    /// characters such as `&` and `=` can't break the structure of the query.
+    /// An empty slice removes the query.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clienter::Uri;
+    ///
+    /// let mut uri: Uri = "http://example.com/search".parse().unwrap();
+    /// uri.set_query_pairs(&[("q", "fish & chips"), ("page", "2")]);
+    /// assert_eq!(uri.query.as_deref(), Some("q=fish+%26+chips&page=2"));
+    /// ```
+    pub fn set_query_pairs(&mut self, pairs: &[(&str, &str)]) {
+        self.query = None;
+        self.append_query_pairs(pairs);
+    }
+
+    /// Appends key-value pairs to the query, after any parameters it already has.
+    ///
+    /// Pairs are encoded as for `set_query_pairs`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clienter::Uri;
+    ///
+    /// let mut uri: Uri = "http://example.com/search?q=rust".parse().unwrap();
+    /// uri.append_query_pairs(&[("api_key", "a/b")]);
+    /// assert_eq!(uri.query.as_deref(), Some("q=rust&api_key=a%2Fb"));
+    /// ```
+    pub fn append_query_pairs(&mut self, pairs: &[(&str, &str)]) {
+        let encoded = pairs.iter().map(|(key, value)| {
+            format!(
+                "{}={}",
+                utils::form_urlencode(key),
+                utils::form_urlencode(value)
+            )
+        });
+        let query = self
+            .query
+            .take()
+            .into_iter()
+            .chain(encoded)
+            .collect::<Vec<_>>()
+            .join("&");
+        self.query = (!query.is_empty()).then_some(query);
+    }
+
+    /// Determines whether the query has a parameter with the given unencoded name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clienter::Uri;
+    ///
+    /// let uri: Uri = "http://example.com/?page=2&sort+by=name".parse().unwrap();
+    /// assert!(uri.has_query_param("sort by"));
+    /// assert!(!uri.has_query_param("q"));
+    /// ```
+    pub fn has_query_param(&self, key: &str) -> bool {
+        let key = utils::form_urlencode(key);
+        self.query.as_deref().is_some_and(|query| {
+            query
+                .split('&')
+                .any(|pair| pair.split('=').next() == Some(key.as_str()))
+        })
     }
 }
 
+/// Removes `.` and `..` segments from a path without its leading `/`.
+///
+/// A path ending in such a segment keeps a trailing `/`, so `a/b/..` becomes `a/`.
+fn remove_dot_segments(path: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    let mut parts = path.split('/').peekable();
+    while let Some(part) = parts.next() {
+        let is_last = parts.peek().is_none();
+        match part {
+            "." | ".." => {
+                if part == ".." {
+                    segments.pop();
+                }
+                if is_last {
+                    segments.push("");
+                }
+            }
+            _ => segments.push(part),
+        }
+    }
+    segments.join("/")
+}
+
 impl FromStr for Uri {
     type Err = UriError;
 
@@ -108,13 +597,33 @@ impl FromStr for Uri {
             .parse::<super::protocol::Protocol>()
             .map_err(|_| UriError::InvalidProtocol)?;
 
+        let (s, query) = match utils::tuple_split(s, "?") {
+            Some((s, query)) => (s, Some(query)),
+            None => (s, None),
+        };
+
         let (hostname, path) = if s.contains('/') {
             utils::tuple_split(s, "/").ok_or(UriError::InvalidHostname)?
         } else {
             (s, "")
         };
 
-        let (hostname, port) = if hostname.contains(':') {
+        let (hostname, port) = if let Some(rest) = hostname.strip_prefix('[') {
+            // The colons of an IPv6 address don't separate a port, only one after `]` does
+            let (address, rest) = rest.split_once(']').ok_or(UriError::InvalidHostname)?;
+            address
+                .parse::<std::net::Ipv6Addr>()
+                .map_err(|_| UriError::InvalidHostname)?;
+            let port = match rest {
+                "" => None,
+                _ => Some(
+                    rest.strip_prefix(':')
+                        .and_then(|port| port.parse::<u16>().ok())
+                        .ok_or(UriError::InvalidPort)?,
+                ),
+            };
+            (format!("[{}]", address), port)
+        } else if hostname.contains(':') {
             utils::tuple_split_parse::<String, u16>(hostname, ":")
                 .map(|(hostname, port)| (hostname, Some(port)))
                 .ok_or(UriError::InvalidPort)?
@@ -122,7 +631,7 @@ impl FromStr for Uri {
             (String::from(hostname), None)
         };
 
-        if hostname.is_empty() {
+        if hostname.is_empty() || hostname.chars().any(|c| c.is_whitespace() || c == '\\') {
             return Err(UriError::InvalidHostname);
         }
 
@@ -131,19 +640,52 @@ impl FromStr for Uri {
             hostname,
             port,
             path: String::from(path),
+            query: query.map(String::from),
         })
     }
 }
 
+impl std::fmt::Display for Uri {
+    /// Formats the URI so that parsing the result yields an equal `Uri`.
+    ///
+    /// An empty path is written as `/`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use clienter::Uri;
+    ///
+    /// let uri: Uri = "http://example.com".parse().unwrap();
+    /// assert_eq!(uri.to_string(), "http://example.com/");
+    ///
+    /// let uri: Uri = "https://localhost:8080/api?x=1".parse().unwrap();
+    /// assert_eq!(uri.to_string(), "https://localhost:8080/api?x=1");
+    /// ```
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}://{}", self.protocol, self.hostname)?;
+        if let Some(port) = self.port {
+            write!(f, ":{}", port)?;
+        }
+        write!(f, "/{}", self.path)?;
+        if let Some(query) = &self.query {
+            write!(f, "?{}", query)?;
+        }
+        Ok(())
+    }
+}
+
 impl From<String> for Uri {
+    /// Parses a URI, panicking with the reason if it is invalid.
     fn from(s: String) -> Self {
-        s.parse().unwrap()
+        Uri::from(s.as_str())
     }
 }
 
 impl From<&str> for Uri {
+    /// Parses a URI, panicking with the reason if it is invalid.
     fn from(s: &str) -> Self {
-        s.parse().unwrap()
+        s.parse()
+            .unwrap_or_else(|err| panic!("invalid URI '{}': {}", s, err))
     }
 }
 
@@ -176,6 +718,270 @@ mod tests {
         // Test empty path
         let uri = "http://localhost:8080".parse::<Uri>().unwrap();
         assert_eq!(uri.path, "");
+        assert_eq!(uri.query, None);
+    }
+
+    #[test]
+    fn test_uri_empty_path_normalized() {
+        let without_slash = "http://example.com".parse::<Uri>().unwrap();
+        let with_slash = "http://example.com/".parse::<Uri>().unwrap();
+        assert_eq!(without_slash, with_slash);
+        assert_eq!(without_slash.path, "");
+        assert_eq!(without_slash.to_string(), "http://example.com/");
+    }
+
+    #[test]
+    fn test_uri_display_round_trip() {
+        for input in [
+            "http://example.com",
+            "http://example.com/",
+            "https://localhost:8443/a/b",
+            "http://localhost/search?q=rust",
+            "http://localhost?q=rust",
+        ] {
+            let uri = input.parse::<Uri>().unwrap();
+            assert_eq!(uri.to_string().parse::<Uri>().unwrap(), uri);
+        }
+    }
+
+    #[test]
+    fn test_uri_encoded_path() {
+        let uri = "http://localhost/a b/100%/%2F/café".parse::<Uri>().unwrap();
+        assert_eq!(uri.get_encoded_path(), "a%20b/100%25/%2F/caf%C3%A9");
+
+        let uri = "http://localhost/%zz/%4".parse::<Uri>().unwrap();
+        assert_eq!(uri.get_encoded_path(), "%25zz/%254");
+    }
+
+    #[test]
+    fn test_uri_parse_many() {
+        let uris = Uri::parse_many("\r\n# comment\r\n\thttp://a.example/x \r\nftp://b.example\n  #indented\nc.example:99999\nd.example");
+        assert_eq!(uris.len(), 4);
+        assert_eq!(uris[0].as_ref().unwrap().hostname, "a.example");
+        assert_eq!(uris[1], Err(UriError::InvalidProtocol));
+        assert_eq!(uris[2], Err(UriError::InvalidPort));
+        assert_eq!(uris[3].as_ref().unwrap().hostname, "d.example");
+        assert!(Uri::parse_many("").is_empty());
+    }
+
+    #[test]
+    fn test_uri_join_absolute_path() {
+        let uri = "http://host/a".parse::<Uri>().unwrap();
+        let joined = uri.join("/b").unwrap();
+        assert_eq!(joined, "http://host/b".parse::<Uri>().unwrap());
+        assert!(uri.same_origin(&joined));
+
+        let uri = "https://host:8443/deep/path?old=1".parse::<Uri>().unwrap();
+        let joined = uri.join("/login?next=%2Fdeep#form").unwrap();
+        assert_eq!(joined.to_string(), "https://host:8443/login?next=%2Fdeep");
+        assert_eq!(uri.join("/").unwrap().to_string(), "https://host:8443/");
+    }
+
+    #[test]
+    fn test_uri_join_relative() {
+        let uri = "http://host/a/b/c?q=1".parse::<Uri>().unwrap();
+        for (reference, expected) in [
+            ("", "http://host/a/b/c?q=1"),
+            ("#top", "http://host/a/b/c?q=1"),
+            ("?r=2", "http://host/a/b/c?r=2"),
+            ("d", "http://host/a/b/d"),
+            ("./d/", "http://host/a/b/d/"),
+            ("..", "http://host/a/"),
+            ("../../../../g", "http://host/g"),
+            ("/./x/../y", "http://host/y"),
+        ] {
+            assert_eq!(
+                uri.join(reference).unwrap().to_string(),
+                expected,
+                "{}",
+                reference
+            );
+        }
+
+        let root = "http://host".parse::<Uri>().unwrap();
+        assert_eq!(root.join("x").unwrap().to_string(), "http://host/x");
+    }
+
+    #[test]
+    fn test_uri_join_other_origin() {
+        let uri = "https://host/a".parse::<Uri>().unwrap();
+        let joined = uri.join("//cdn.example:8080/x").unwrap();
+        assert_eq!(joined.to_string(), "https://cdn.example:8080/x");
+        assert!(!uri.same_origin(&joined));
+
+        let joined = uri.join("http://host/a").unwrap();
+        assert!(!uri.same_origin(&joined));
+        assert_eq!(uri.join("ftp://host/"), Err(UriError::InvalidProtocol));
+    }
+
+    #[test]
+    fn test_uri_query() {
+        let uri = "http://localhost/search?q=rust&page=2"
+            .parse::<Uri>()
+            .unwrap();
+        assert_eq!(uri.path, "search");
+        assert_eq!(uri.query.as_deref(), Some("q=rust&page=2"));
+
+        let uri = "http://localhost:8080?q=rust".parse::<Uri>().unwrap();
+        assert_eq!(uri.hostname, "localhost");
+        assert_eq!(uri.port, Some(8080));
+        assert_eq!(uri.path, "");
+        assert_eq!(uri.query.as_deref(), Some("q=rust"));
+    }
+
+    #[test]
+    fn test_set_query_pairs() {
+        let mut uri = "http://localhost/search?old=1".parse::<Uri>().unwrap();
+        uri.set_query_pairs(&[("name", "a b&c=d"), ("emoji", "é")]);
+        assert_eq!(uri.query.as_deref(), Some("name=a+b%26c%3Dd&emoji=%C3%A9"));
+
+        uri.set_query_pairs(&[]);
+        assert_eq!(uri.query, None);
+    }
+
+    #[test]
+    fn test_port_resolution() {
+        // (uri, connect address, Host header, server name)
+        let cases = [
+            (
+                "http://example.com/",
+                "example.com:80",
+                "example.com",
+                "example.com",
+            ),
+            (
+                "http://example.com:80/",
+                "example.com:80",
+                "example.com",
+                "example.com",
+            ),
+            (
+                "http://example.com:443/",
+                "example.com:443",
+                "example.com:443",
+                "example.com",
+            ),
+            (
+                "http://example.com:8080/",
+                "example.com:8080",
+                "example.com:8080",
+                "example.com",
+            ),
+            (
+                "https://example.com/",
+                "example.com:443",
+                "example.com",
+                "example.com",
+            ),
+            (
+                "https://example.com:443/",
+                "example.com:443",
+                "example.com",
+                "example.com",
+            ),
+            (
+                "https://example.com:80/",
+                "example.com:80",
+                "example.com:80",
+                "example.com",
+            ),
+            (
+                "https://10.0.0.1:8443/",
+                "10.0.0.1:8443",
+                "10.0.0.1:8443",
+                "10.0.0.1",
+            ),
+        ];
+
+        for (uri, addr, host, server_name) in cases {
+            let uri = uri.parse::<Uri>().unwrap();
+            assert_eq!(uri.get_addr(), addr);
+            assert_eq!(uri.get_host(), host);
+            assert_eq!(uri.get_server_name(), server_name);
+        }
+    }
+
+    #[test]
+    fn test_authority_brackets_ipv6_hosts() {
+        let uri = "http://[::1]/".parse::<Uri>().unwrap();
+        assert_eq!(uri.hostname, "[::1]");
+        assert_eq!(uri.port, None);
+        assert_eq!(uri.authority().host, "::1");
+        assert_eq!(uri.get_addr(), "[::1]:80");
+        assert_eq!(uri.get_host(), "[::1]");
+        assert_eq!(uri.to_string(), "http://[::1]/");
+
+        let uri = "http://[::1]:8080/api".parse::<Uri>().unwrap();
+        assert_eq!(
+            uri.authority(),
+            Authority {
+                host: "::1".to_string(),
+                port: 8080
+            }
+        );
+        assert_eq!(uri.get_addr(), "[::1]:8080");
+        assert_eq!(uri.get_host(), "[::1]:8080");
+        assert_eq!(uri.path, "api");
+        assert_eq!(uri.to_string(), "http://[::1]:8080/api");
+
+        assert_eq!(
+            "http://[::1/".parse::<Uri>(),
+            Err(UriError::InvalidHostname)
+        );
+        assert_eq!(
+            "http://[nope]/".parse::<Uri>(),
+            Err(UriError::InvalidHostname)
+        );
+        assert_eq!("http://[::1]x/".parse::<Uri>(), Err(UriError::InvalidPort));
+        assert_eq!("http://[::1]:x/".parse::<Uri>(), Err(UriError::InvalidPort));
+    }
+
+    #[test]
+    fn test_with_mutators() {
+        let base = "https://api.example.com:8443/v1/users?page=1"
+            .parse::<Uri>()
+            .unwrap();
+
+        let uri = base
+            .clone()
+            .with_path("v2/teams")
+            .with_query("?")
+            .with_port(None);
+        assert_eq!(uri.to_string(), "https://api.example.com/v2/teams");
+        assert_eq!(uri.hostname, base.hostname);
+        assert_eq!(
+            base.with_path("").to_string(),
+            "https://api.example.com:8443/?page=1"
+        );
+    }
+
+    #[test]
+    fn test_parse_strict() {
+        assert_eq!(
+            Uri::parse_strict("https://example.com:8443/a/b%2Fc?q=1&r=~x"),
+            "https://example.com:8443/a/b%2Fc?q=1&r=~x".parse::<Uri>()
+        );
+        assert_eq!(Uri::parse_strict(""), Err(UriError::Empty));
+        assert_eq!(
+            Uri::parse_strict("example.com/"),
+            Err(UriError::InvalidProtocol)
+        );
+        assert_eq!(
+            Uri::parse_strict("http://example.com/a\tb"),
+            Err(UriError::InvalidCharacter('\t'))
+        );
+        assert_eq!(
+            Uri::parse_strict("http://example.com/?q=é"),
+            Err(UriError::InvalidCharacter('é'))
+        );
+        assert_eq!(
+            Uri::parse_strict("http://example.com/50%"),
+            Err(UriError::InvalidCharacter('%'))
+        );
+        assert_eq!(
+            Uri::parse_strict("http://example.com/%zz"),
+            Err(UriError::InvalidCharacter('%'))
+        );
     }
 
     #[test]
@@ -186,9 +992,27 @@ mod tests {
             Err(UriError::InvalidProtocol)
         );
         assert_eq!("http://:80".parse::<Uri>(), Err(UriError::InvalidHostname));
+        assert_eq!(
+            "/just/a/path".parse::<Uri>(),
+            Err(UriError::InvalidHostname)
+        );
+        assert_eq!(
+            "http:///path".parse::<Uri>(),
+            Err(UriError::InvalidHostname)
+        );
+        assert_eq!(
+            "http://exa mple.com/".parse::<Uri>(),
+            Err(UriError::InvalidHostname)
+        );
         assert_eq!(
             "http://localhost:invalid".parse::<Uri>(),
             Err(UriError::InvalidPort)
         );
     }
+
+    #[test]
+    #[should_panic(expected = "invalid URI '/just/a/path': ")]
+    fn test_from_string_panics_with_the_reason() {
+        let _ = Uri::from(String::from("/just/a/path"));
+    }
 }
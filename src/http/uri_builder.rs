@@ -0,0 +1,208 @@
+//! Building URIs from unencoded parts.
+//!
+//! Parsing a string into a `Uri` takes the path and query as written, so values that
+//! contain `/`, `?`, `&` or spaces must be encoded by hand first. `UriBuilder` instead
+//! takes each path segment and query parameter as plain text and percent-encodes it
+//! as it is added.
+
+use super::protocol::Protocol;
+use super::uri::{Uri, UriError};
+use crate::utils;
+
+/// Builder for a `Uri` that percent-encodes path segments and query parameters.
+///
+/// # Example
+/// ```
+/// use clienter::{Protocol, UriBuilder};
+///
+/// let uri = UriBuilder::new()
+///     .scheme(Protocol::HTTPS)
+///     .host("api.example.com")
+///     .port(8443)
+///     .push_path_segment("files")
+///     .push_path_segment("reports/2024 Q1.pdf")
+///     .query_param("owner", "a&b")
+///     .build()
+///     .unwrap();
+/// assert_eq!(
+///     uri.to_string(),
+///     "https://api.example.com:8443/files/reports%2F2024%20Q1.pdf?owner=a%26b"
+/// );
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct UriBuilder {
+    protocol: Protocol,
+    hostname: String,
+    port: Option<u16>,
+    /// Path segments, already percent-encoded
+    segments: Vec<String>,
+    /// `key=value` query pairs, already percent-encoded
+    query: Vec<String>,
+}
+
+impl Default for UriBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UriBuilder {
+    /// Creates an empty builder for an `http` URI.
+    ///
+    /// A host must be set before calling `build`.
+    pub fn new() -> Self {
+        UriBuilder {
+            protocol: Protocol::HTTP,
+            hostname: String::new(),
+            port: None,
+            segments: Vec::new(),
+            query: Vec::new(),
+        }
+    }
+
+    /// Sets the scheme of the URI.
+    ///
+    /// # Arguments
+    /// * `protocol` - The protocol to use
+    pub fn scheme(mut self, protocol: Protocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    /// Sets the host name of the URI.
+    ///
+    /// # Arguments
+    /// * `host` - The host name or IP address, without a port
+    pub fn host(mut self, host: &str) -> Self {
+        self.hostname = host.to_string();
+        self
+    }
+
+    /// Sets an explicit port, instead of the default port of the scheme.
+    ///
+    /// # Arguments
+    /// * `port` - The port number
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    /// Appends a segment to the path.
+    ///
+    /// The segment is percent-encoded, so any `/` it contains becomes part of the
+    /// segment rather than separating it into two.
+    ///
+    /// # Arguments
+    /// * `segment` - The unencoded path segment
+    pub fn push_path_segment(mut self, segment: &str) -> Self {
+        self.segments.push(utils::encode_path_segment(segment));
+        self
+    }
+
+    /// Appends a parameter to the query string.
+    ///
+    /// The key and value are encoded as in `application/x-www-form-urlencoded` data.
+    /// Parameters are kept in the order they are added, and repeated keys are allowed.
+    ///
+    /// # Arguments
+    /// * `key` - The unencoded parameter name
+    /// * `value` - The unencoded parameter value
+    pub fn query_param(mut self, key: &str, value: &str) -> Self {
+        self.query.push(format!(
+            "{}={}",
+            utils::form_urlencode(key),
+            utils::form_urlencode(value)
+        ));
+        self
+    }
+
+    /// Builds the URI.
+    ///
+    /// # Returns
+    /// * `Ok(Uri)` - The built URI
+    /// * `Err(UriError::InvalidHostname)` - If no host was set, or it contains
+    ///   characters that cannot appear in a host name
+    pub fn build(self) -> Result<Uri, UriError> {
+        if self.hostname.is_empty()
+            || self
+                .hostname
+                .chars()
+                .any(|c| c.is_whitespace() || "\\/?#@".contains(c))
+        {
+            return Err(UriError::InvalidHostname);
+        }
+
+        Ok(Uri {
+            protocol: self.protocol,
+            hostname: self.hostname,
+            port: self.port,
+            path: self.segments.join("/"),
+            query: (!self.query.is_empty()).then(|| self.query.join("&")),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_defaults() {
+        let uri = UriBuilder::new().host("localhost").build().unwrap();
+        assert_eq!(uri, "http://localhost".parse::<Uri>().unwrap());
+    }
+
+    #[test]
+    fn test_path_segments_are_encoded() {
+        let uri = UriBuilder::new()
+            .host("localhost")
+            .push_path_segment("a/b")
+            .push_path_segment("50% off?")
+            .push_path_segment("user@host:1")
+            .build()
+            .unwrap();
+        assert_eq!(uri.path, "a%2Fb/50%25%20off%3F/user@host:1");
+        assert_eq!(uri.get_encoded_path(), uri.path);
+    }
+
+    #[test]
+    fn test_query_params_are_encoded() {
+        let uri = UriBuilder::new()
+            .host("localhost")
+            .query_param("q", "rust & c++")
+            .query_param("tag", "a=b")
+            .query_param("tag", "")
+            .build()
+            .unwrap();
+        assert_eq!(
+            uri.query.as_deref(),
+            Some("q=rust+%26+c%2B%2B&tag=a%3Db&tag=")
+        );
+    }
+
+    #[test]
+    fn test_round_trips_through_display() {
+        let uri = UriBuilder::new()
+            .scheme(Protocol::HTTPS)
+            .host("example.com")
+            .port(8443)
+            .push_path_segment("x y")
+            .query_param("k", "v")
+            .build()
+            .unwrap();
+        assert_eq!(uri.to_string().parse::<Uri>().unwrap(), uri);
+    }
+
+    #[test]
+    fn test_invalid_host() {
+        assert_eq!(UriBuilder::new().build(), Err(UriError::InvalidHostname));
+        assert_eq!(
+            UriBuilder::new().host("a b").build(),
+            Err(UriError::InvalidHostname)
+        );
+        assert_eq!(
+            UriBuilder::new().host("example.com/path").build(),
+            Err(UriError::InvalidHostname)
+        );
+    }
+}
@@ -0,0 +1,33 @@
+//! HTTP versions that can be sent in the request line.
+
+/// The HTTP version announced in the request line.
+///
+/// The version is independent of the URI scheme: `https` requests are sent as
+/// HTTP/1.1 over the connection just like `http` ones.
+///
+/// # Examples
+///
+/// ```
+/// use clienter::HttpVersion;
+///
+/// assert_eq!(HttpVersion::default(), HttpVersion::Http11);
+/// assert_eq!(HttpVersion::Http10.to_string(), "HTTP/1.0");
+/// ```
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum HttpVersion {
+    /// HTTP/1.0, for servers that do not understand HTTP/1.1
+    Http10,
+    /// HTTP/1.1
+    #[default]
+    Http11,
+}
+
+impl std::fmt::Display for HttpVersion {
+    /// Formats the version as it appears in the request line, such as "HTTP/1.1".
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HttpVersion::Http10 => f.write_str("HTTP/1.0"),
+            HttpVersion::Http11 => f.write_str("HTTP/1.1"),
+        }
+    }
+}
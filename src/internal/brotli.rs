@@ -0,0 +1,37 @@
+//! Decoding of the Brotli content coding (RFC 7932).
+
+use brotli_decompressor::BrotliDecompress;
+
+/// Decodes a Brotli compressed body.
+///
+/// # Arguments
+///
+/// * `data` - The Brotli encoded body
+///
+/// # Returns
+///
+/// The decoded bytes, or `None` if the compressed data is invalid or truncated
+pub fn unbrotli(data: &[u8]) -> Option<Vec<u8>> {
+    let mut output = Vec::new();
+    BrotliDecompress(&mut &data[..], &mut output).ok()?;
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// "hello" stored in a single uncompressed meta-block, followed by an empty last meta-block.
+    const HELLO: &[u8] = b"\x40\x00\x10hello\x03";
+
+    #[test]
+    fn test_unbrotli_uncompressed_meta_block() {
+        assert_eq!(unbrotli(HELLO).unwrap(), b"hello");
+    }
+
+    #[test]
+    fn test_unbrotli_rejects_truncated_data() {
+        assert_eq!(unbrotli(&HELLO[..HELLO.len() - 1]), None);
+        assert_eq!(unbrotli(b"\xff\xff\xff\xff"), None);
+    }
+}
@@ -0,0 +1,72 @@
+//! Chunked transfer encoding as defined in RFC 7230 section 4.1.
+
+use std::io::{Read, Write};
+
+/// The maximum number of bytes sent in a single chunk.
+const CHUNK_SIZE: usize = 8192;
+
+/// Copies everything from `reader` to `writer` framed as HTTP chunks.
+///
+/// Each read from the reader is written as one chunk, and the body is terminated
+/// with the zero-length last chunk and an empty trailer section.
+///
+/// # Arguments
+///
+/// * `reader` - The source of the body bytes
+/// * `writer` - The stream to write the framed body to
+///
+/// # Returns
+///
+/// * `Ok(u64)` - The number of body bytes written, excluding framing
+/// * `Err(std::io::Error)` - If reading or writing fails
+pub fn write_chunked(reader: &mut dyn Read, writer: &mut dyn Write) -> Result<u64, std::io::Error> {
+    let mut buffer = vec![0; CHUNK_SIZE];
+    let mut total = 0;
+
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err),
+        };
+
+        write!(writer, "{:X}\r\n", read)?;
+        writer.write_all(&buffer[..read])?;
+        writer.write_all(b"\r\n")?;
+        total += read as u64;
+    }
+
+    writer.write_all(b"0\r\n\r\n")?;
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_chunked() {
+        let mut output = Vec::new();
+        let written = write_chunked(&mut &b"hello world"[..], &mut output).unwrap();
+        assert_eq!(written, 11);
+        assert_eq!(output, b"B\r\nhello world\r\n0\r\n\r\n");
+    }
+
+    #[test]
+    fn test_write_chunked_empty() {
+        let mut output = Vec::new();
+        let written = write_chunked(&mut &b""[..], &mut output).unwrap();
+        assert_eq!(written, 0);
+        assert_eq!(output, b"0\r\n\r\n");
+    }
+
+    #[test]
+    fn test_write_chunked_large() {
+        let data = vec![b'a'; CHUNK_SIZE + 10];
+        let mut output = Vec::new();
+        write_chunked(&mut &data[..], &mut output).unwrap();
+        assert!(output.starts_with(b"2000\r\n"));
+        assert!(output.ends_with(b"\r\nA\r\naaaaaaaaaa\r\n0\r\n\r\n"));
+    }
+}
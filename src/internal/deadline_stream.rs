@@ -0,0 +1,219 @@
+//! A stream wrapper that enforces an absolute deadline.
+//!
+//! Socket timeouts only bound a single read or write, so a server that trickles data
+//! can keep a connection busy indefinitely. This wrapper shrinks the socket timeout
+//! before every operation so the exchange as a whole cannot outlive the deadline.
+//!
+//! Streams that aren't a `TcpStream`, such as ones returned by a custom `Connector`,
+//! have no timeout to shrink. For those the deadline and cancellation are checked
+//! before every operation, so a single blocking read is only bounded by whatever
+//! timeout the stream has itself.
+
+use std::{
+    any::Any,
+    io::{ErrorKind, Read, Write},
+    net::TcpStream,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use crate::{CancelHandle, ReadWrite};
+
+/// How long a single socket operation may block before a cancelled request is noticed.
+const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A deadline that can be set on a `DeadlineStream` after it has been handed over,
+/// to bound a few operations, such as the reads of a single response body.
+#[derive(Clone, Default)]
+pub struct DeadlineHandle(Arc<Mutex<Option<Instant>>>);
+
+impl DeadlineHandle {
+    /// Sets the instant after which operations fail, or `None` to remove it.
+    pub fn set(&self, deadline: Option<Instant>) {
+        *self.0.lock().unwrap() = deadline;
+    }
+
+    /// Returns the deadline currently set.
+    fn get(&self) -> Option<Instant> {
+        *self.0.lock().unwrap()
+    }
+}
+
+/// A stream whose reads and writes fail with `ErrorKind::TimedOut` once a deadline
+/// passes, and with a cancellation error once its request is cancelled.
+pub struct DeadlineStream {
+    stream: Box<dyn ReadWrite>,
+    /// The socket underneath `stream`, whose timeouts bound each operation
+    socket: Option<TcpStream>,
+    deadline: Option<Instant>,
+    /// The read timeout set on `socket` before it was wrapped
+    read_timeout: Option<Duration>,
+    /// The write timeout set on `socket` before it was wrapped
+    write_timeout: Option<Duration>,
+    cancel: Option<CancelHandle>,
+    /// A further deadline set for a few operations, see `deadline_handle`
+    extra_deadline: DeadlineHandle,
+}
+
+impl DeadlineStream {
+    /// Wraps a stream with an optional deadline.
+    ///
+    /// When `stream` is a `TcpStream`, its socket timeouts are adjusted before every
+    /// operation, and a single operation never waits longer than the timeouts it
+    /// already had. Otherwise the deadline and cancellation are only checked between
+    /// operations.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - The connected stream
+    /// * `deadline` - The instant after which all operations fail, or `None` for no limit
+    /// * `cancel` - The handle whose cancellation makes all operations fail
+    pub fn new(
+        stream: Box<dyn ReadWrite>,
+        deadline: Option<Instant>,
+        cancel: Option<CancelHandle>,
+    ) -> Self {
+        // A clone shares the socket, and with it the timeouts set through it
+        let socket = (&*stream as &dyn Any)
+            .downcast_ref::<TcpStream>()
+            .and_then(|socket| socket.try_clone().ok());
+        let read_timeout = socket
+            .as_ref()
+            .and_then(|x| x.read_timeout().ok().flatten());
+        let write_timeout = socket
+            .as_ref()
+            .and_then(|x| x.write_timeout().ok().flatten());
+        DeadlineStream {
+            stream,
+            socket,
+            deadline,
+            read_timeout,
+            write_timeout,
+            cancel,
+            extra_deadline: DeadlineHandle::default(),
+        }
+    }
+
+    /// Replaces the read and write timeouts the socket had, which a single operation
+    /// may not exceed, with `timeout`.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - The longest a single operation may wait, or `None` for no limit
+    pub fn with_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.read_timeout = timeout;
+        self.write_timeout = timeout;
+        self
+    }
+
+    /// Returns a handle that sets a deadline in addition to the one the stream was
+    /// created with, which applies until it is removed.
+    pub fn deadline_handle(&self) -> DeadlineHandle {
+        self.extra_deadline.clone()
+    }
+
+    /// Returns the earlier of the request deadline and the one set through the handle.
+    fn deadline(&self) -> Option<Instant> {
+        match (self.deadline, self.extra_deadline.get()) {
+            (Some(x), Some(y)) => Some(x.min(y)),
+            (x, y) => x.or(y),
+        }
+    }
+
+    /// Checks for cancellation, then applies the time remaining until the deadline or
+    /// the idle timeout, whichever is sooner, as the socket's read or write timeout.
+    ///
+    /// While a cancel handle is set, the timeout is capped at `CANCEL_POLL_INTERVAL`
+    /// so that the operation can be retried after checking for cancellation again.
+    ///
+    /// # Arguments
+    ///
+    /// * `waiting_since` - When the current operation started waiting for the socket
+    /// * `write` - Whether the operation is a write rather than a read
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the operation may go ahead
+    /// * `Err(std::io::Error)` - With `ErrorKind::TimedOut` if the deadline has passed
+    ///   or the idle timeout ran out, or the cancellation error
+    fn apply_deadline(&self, waiting_since: Instant, write: bool) -> Result<(), std::io::Error> {
+        let timed_out = || std::io::Error::new(ErrorKind::TimedOut, "Request deadline exceeded");
+        if let Some(cancel) = &self.cancel {
+            cancel.check()?;
+        }
+        let deadline = self.deadline();
+        let Some(socket) = &self.socket else {
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Err(timed_out());
+            }
+            return Ok(());
+        };
+
+        let idle_timeout = if write {
+            self.write_timeout
+        } else {
+            self.read_timeout
+        };
+        // Always set, so the idle timeout is restored once the handle's deadline is removed
+        let mut timeout = idle_timeout;
+        if let Some(deadline) = deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            timeout = Some(timeout.map_or(remaining, |timeout| timeout.min(remaining)));
+        }
+        if self.cancel.is_some() {
+            let mut poll = CANCEL_POLL_INTERVAL;
+            if let Some(idle_timeout) = idle_timeout {
+                poll = poll.min(idle_timeout.saturating_sub(waiting_since.elapsed()));
+            }
+            timeout = Some(timeout.map_or(poll, |timeout| timeout.min(poll)));
+        }
+        if timeout.is_some_and(|timeout| timeout.is_zero()) {
+            return Err(timed_out());
+        }
+        if write {
+            socket.set_write_timeout(timeout)
+        } else {
+            socket.set_read_timeout(timeout)
+        }
+    }
+
+    /// Runs a stream operation, retrying it after each poll interval until it
+    /// completes, times out or the request is cancelled.
+    fn retry<T>(
+        &mut self,
+        write: bool,
+        mut operation: impl FnMut(&mut dyn ReadWrite) -> std::io::Result<T>,
+    ) -> std::io::Result<T> {
+        let waiting_since = Instant::now();
+        loop {
+            self.apply_deadline(waiting_since, write)?;
+            match operation(&mut *self.stream) {
+                // Only a socket timeout set for polling is retried, not the stream's own
+                Err(err)
+                    if self.cancel.is_some()
+                        && self.socket.is_some()
+                        && matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+                {
+                    continue
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+impl Read for DeadlineStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.retry(false, |stream| stream.read(buf))
+    }
+}
+
+impl Write for DeadlineStream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.retry(true, |stream| stream.write(buf))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.stream.flush()
+    }
+}
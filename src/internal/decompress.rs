@@ -0,0 +1,202 @@
+//! Decoding of DEFLATE based content codings (RFC 1950, 1951 and 1952).
+
+use miniz_oxide::inflate::core::{decompress, inflate_flags, DecompressorOxide};
+use miniz_oxide::inflate::{decompress_to_vec, decompress_to_vec_zlib, TINFLStatus};
+
+/// Flag bits of the gzip header, see RFC 1952 section 2.3.1.
+const FHCRC: u8 = 0x02;
+const FEXTRA: u8 = 0x04;
+const FNAME: u8 = 0x08;
+const FCOMMENT: u8 = 0x10;
+
+/// The CRC-32 lookup table for the polynomial used by gzip, see RFC 1952 section 8.
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut index = 0;
+    while index < 256 {
+        let mut crc = index as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 {
+                0xedb8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[index] = crc;
+        index += 1;
+    }
+    table
+};
+
+/// Computes the CRC-32 of `data` as stored in the gzip trailer.
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0, |crc, &byte| {
+        CRC_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
+
+/// Decodes raw DEFLATE data that may be followed by other bytes.
+///
+/// # Returns
+///
+/// The decoded bytes and the number of input bytes the DEFLATE data took up, or
+/// `None` if the data is invalid or truncated
+fn inflate_prefix(data: &[u8]) -> Option<(Vec<u8>, usize)> {
+    let mut decompressor = DecompressorOxide::new();
+    let mut out = vec![0; data.len().saturating_mul(2).max(64)];
+    let (mut read, mut written) = (0, 0);
+
+    loop {
+        let (status, consumed, produced) = decompress(
+            &mut decompressor,
+            data.get(read..)?,
+            &mut out,
+            written,
+            inflate_flags::TINFL_FLAG_USING_NON_WRAPPING_OUTPUT_BUF,
+        );
+        read += consumed;
+        written += produced;
+
+        match status {
+            TINFLStatus::Done => {
+                out.truncate(written);
+                return Some((out, read));
+            }
+            TINFLStatus::HasMoreOutput => out.resize(out.len() * 2, 0),
+            _ => return None,
+        }
+    }
+}
+
+/// Decodes a body in the gzip file format.
+///
+/// The CRC-32 and length in the gzip trailer are checked against the decoded data,
+/// so a truncated or corrupted body is rejected rather than returned partially.
+///
+/// # Arguments
+///
+/// * `data` - The gzip encoded body
+///
+/// # Returns
+///
+/// The decoded bytes, or `None` if the header, the compressed data or the trailer is
+/// invalid
+pub fn gunzip(data: &[u8]) -> Option<Vec<u8>> {
+    if data.len() < 10 || data[0..3] != [0x1f, 0x8b, 8] {
+        return None;
+    }
+    let flags = data[3];
+    let mut offset = 10;
+
+    if flags & FEXTRA != 0 {
+        let length = u16::from_le_bytes([*data.get(offset)?, *data.get(offset + 1)?]);
+        offset += 2 + length as usize;
+    }
+    for flag in [FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            // Zero-terminated strings
+            offset += data.get(offset..)?.iter().position(|&byte| byte == 0)? + 1;
+        }
+    }
+    if flags & FHCRC != 0 {
+        offset += 2;
+    }
+
+    let (decoded, length) = inflate_prefix(data.get(offset..)?)?;
+    let trailer = data.get(offset + length..offset + length + 8)?;
+    let crc = u32::from_le_bytes(trailer[0..4].try_into().ok()?);
+    let size = u32::from_le_bytes(trailer[4..8].try_into().ok()?);
+    // The size is stored modulo 2^32
+    (crc == crc32(&decoded) && size == decoded.len() as u32).then_some(decoded)
+}
+
+/// Decodes a body with the `deflate` content coding.
+///
+/// The coding is defined as a zlib stream, but some servers send raw DEFLATE data
+/// instead, so that is accepted as well.
+///
+/// # Arguments
+///
+/// * `data` - The deflate encoded body
+///
+/// # Returns
+///
+/// The decoded bytes, or `None` if the data is invalid
+pub fn inflate(data: &[u8]) -> Option<Vec<u8>> {
+    decompress_to_vec_zlib(data)
+        .or_else(|_| decompress_to_vec(data))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use miniz_oxide::deflate::{compress_to_vec, compress_to_vec_zlib};
+
+    use super::*;
+
+    /// Builds a gzip member with the given header flags and extra header bytes.
+    fn gzip(flags: u8, header: &[u8], content: &[u8]) -> Vec<u8> {
+        let mut data = vec![0x1f, 0x8b, 8, flags, 0, 0, 0, 0, 0, 255];
+        data.extend(header);
+        data.extend(compress_to_vec(content, 6));
+        data.extend(crc32(content).to_le_bytes());
+        data.extend((content.len() as u32).to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn test_gunzip_with_file_name() {
+        let data = gzip(FNAME, b"hello.txt\0", b"hello gzip");
+        assert_eq!(gunzip(&data).unwrap(), b"hello gzip");
+    }
+
+    #[test]
+    fn test_gunzip_checks_trailer() {
+        let data = gzip(0, b"", b"checked content");
+        assert_eq!(gunzip(&data).unwrap(), b"checked content");
+
+        // Truncated anywhere, including inside the trailer
+        for length in [data.len() - 1, data.len() - 8, data.len() / 2] {
+            assert_eq!(gunzip(&data[..length]), None, "{}", length);
+        }
+
+        let mut corrupted = data.clone();
+        let crc = corrupted.len() - 8;
+        corrupted[crc] ^= 1;
+        assert_eq!(gunzip(&corrupted), None);
+
+        let mut wrong_size = data.clone();
+        let size = wrong_size.len() - 4;
+        wrong_size[size] ^= 1;
+        assert_eq!(gunzip(&wrong_size), None);
+    }
+
+    #[test]
+    fn test_gunzip_large_output() {
+        let content = vec![b'a'; 100_000];
+        assert_eq!(gunzip(&gzip(0, b"", &content)).unwrap(), content);
+    }
+
+    #[test]
+    fn test_gunzip_rejects_bad_magic() {
+        assert_eq!(gunzip(b"not gzip at all"), None);
+        assert_eq!(
+            gunzip(&[0x1f, 0x8b, 8, FNAME, 0, 0, 0, 0, 0, 255, b'x']),
+            None
+        );
+    }
+
+    #[test]
+    fn test_inflate_zlib_and_raw() {
+        assert_eq!(inflate(&compress_to_vec_zlib(b"zlib", 6)).unwrap(), b"zlib");
+        assert_eq!(inflate(&compress_to_vec(b"raw", 6)).unwrap(), b"raw");
+    }
+}
@@ -1,2 +1,18 @@
+mod chunked;
+pub use chunked::write_chunked;
+
+mod deadline_stream;
+pub use deadline_stream::{DeadlineHandle, DeadlineStream};
+
 mod stream_buffer;
-pub use stream_buffer::StreamBuffer;
+pub use stream_buffer::{BodyMode, StreamBuffer};
+
+#[cfg(feature = "gzip")]
+mod decompress;
+#[cfg(feature = "gzip")]
+pub use decompress::{gunzip, inflate};
+
+#[cfg(feature = "brotli")]
+mod brotli;
+#[cfg(feature = "brotli")]
+pub use brotli::unbrotli;
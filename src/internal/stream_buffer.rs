@@ -1,14 +1,23 @@
 //! Provides buffered reading functionality for TCP streams.
 //!
 //! This module implements line-by-line and complete content reading
-//! capabilities over TCP connections.
+//! capabilities over TCP connections or any other connected stream.
+//!
+//! Data is read from the stream in blocks, so reading a line may pull in bytes
+//! that follow it (such as the start of a response body). Those bytes are kept in
+//! the buffer and returned by subsequent reads, so nothing is lost.
+//!
+//! How the end of a body is found is set explicitly with `set_body_mode`, once the
+//! headers that determine it have been read.
 
 use std::{
-    io::{ErrorKind, Read},
-    net::TcpStream,
+    io::{ErrorKind, Read, Write},
+    time::Instant,
 };
 
-/// A buffered reader for TCP streams that provides convenient reading operations.
+use crate::ReadWrite;
+
+/// A buffered reader for connected streams that provides convenient reading operations.
 ///
 /// # Examples
 ///
@@ -19,44 +28,207 @@ use std::{
 /// let mut buffer = StreamBuffer::new(stream);
 ///
 /// // Read a line
-/// let line = buffer.read_line().unwrap();
+/// let line = buffer.read_line_bytes().unwrap();
 /// ```
 pub struct StreamBuffer {
-    stream: TcpStream,
+    stream: Box<dyn ReadWrite>,
+    /// Bytes read from the stream that have not been consumed yet
+    buffer: Vec<u8>,
+    /// Index of the first unconsumed byte in `buffer`
+    position: usize,
     bytes_read: usize,
     total_bytes: Option<usize>,
+    /// How `read_body` and `copy_body` find the end of the body
+    mode: BodyMode,
+    /// When data was first received from the stream
+    first_read_at: Option<Instant>,
+}
+
+/// How the end of a body read from a `StreamBuffer` is found.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum BodyMode {
+    /// The body is exactly this many bytes long, and the bytes after it are left
+    /// in the buffer
+    Length(usize),
+    /// The body uses chunked transfer encoding, and ends with the last chunk and
+    /// its trailer section
+    Chunked,
+    /// The body runs until the stream reaches EOF
+    UntilClose,
 }
 
+/// The number of bytes requested from the stream each time the buffer is refilled.
+const READ_SIZE: usize = 8192;
+
 impl StreamBuffer {
-    /// Creates a new StreamBuffer from a TcpStream.
+    /// Creates a new StreamBuffer from a connected stream.
     ///
     /// # Arguments
     ///
-    /// * `stream` - The TCP stream to wrap
-    pub fn new(stream: TcpStream) -> Self {
+    /// * `stream` - The stream to wrap
+    pub fn new(stream: Box<dyn ReadWrite>) -> Self {
         StreamBuffer {
             stream,
+            buffer: Vec::new(),
+            position: 0,
             bytes_read: 0,
             total_bytes: None,
+            mode: BodyMode::UntilClose,
+            first_read_at: None,
+        }
+    }
+
+    /// Creates a new StreamBuffer whose first bytes were already read from the stream.
+    ///
+    /// The `prefill` bytes are returned by reads before anything is read from the
+    /// stream, as if they had been buffered by an earlier read. This lets code that
+    /// had to look at the start of a stream (for example to detect its protocol) hand
+    /// it over without losing those bytes. They don't count as received for
+    /// `first_read_at`.
+    ///
+    /// # Arguments
+    ///
+    /// * `stream` - The stream to wrap
+    /// * `prefill` - The bytes already read from the stream
+    pub fn with_prefill(stream: Box<dyn ReadWrite>, prefill: Vec<u8>) -> Self {
+        StreamBuffer {
+            buffer: prefill,
+            ..StreamBuffer::new(stream)
         }
     }
 
-    /// Sets the total number of bytes expected to be read from the stream.
+    /// Returns the instant at which data was first received from the stream.
+    ///
+    /// # Returns
+    ///
+    /// `None` if nothing has been received yet
+    pub fn first_read_at(&self) -> Option<Instant> {
+        self.first_read_at
+    }
+
+    /// Takes the underlying stream back out of the buffer.
+    ///
+    /// # Returns
+    ///
+    /// The stream, along with the bytes already read from it that have not been consumed
+    pub fn into_parts(mut self) -> (Box<dyn ReadWrite>, Vec<u8>) {
+        let remainder = self.buffer.split_off(self.position.min(self.buffer.len()));
+        (self.stream, remainder)
+    }
+
+    /// Sets the number of bytes expected to be read from this point on.
     ///
-    /// This is useful when you know the content length in advance and want to
-    /// prevent reading beyond the expected data size.
+    /// Bytes already buffered beyond this limit are left untouched.
     ///
     /// # Arguments
     ///
     /// * `total_bytes` - The total number of bytes that should be read from the stream
-    pub fn set_total_bytes(&mut self, total_bytes: usize) {
+    fn set_total_bytes(&mut self, total_bytes: usize) {
+        self.bytes_read = 0;
         self.total_bytes = Some(total_bytes);
     }
 
+    /// Removes the limit set with `set_total_bytes`, so reads continue until EOF.
+    fn clear_total_bytes(&mut self) {
+        self.bytes_read = 0;
+        self.total_bytes = None;
+    }
+
+    /// Sets how the end of the body that starts at the current position is found.
+    ///
+    /// The mode is `BodyMode::UntilClose` until this is called.
+    ///
+    /// # Arguments
+    ///
+    /// * `mode` - The framing of the body. With `BodyMode::Length(0)`, reads return
+    ///   nothing without touching the stream, so a read of an empty body never waits
+    ///   for data the server won't send
+    pub fn set_body_mode(&mut self, mode: BodyMode) {
+        match mode {
+            BodyMode::Length(length) => self.set_total_bytes(length),
+            BodyMode::Chunked | BodyMode::UntilClose => self.clear_total_bytes(),
+        }
+        self.mode = mode;
+    }
+
+    /// Reads the rest of the body, framed as set with `set_body_mode`.
+    ///
+    /// # Arguments
+    ///
+    /// * `deadline` - The instant after which reading stops, or `None` to read without a limit
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<u8>)` - The body, with any chunk framing removed
+    /// * `Err(std::io::Error)` - As for `copy_all` or `copy_chunked`, depending on the mode
+    pub fn read_body(&mut self, deadline: Option<Instant>) -> Result<Vec<u8>, std::io::Error> {
+        let mut data = Vec::new();
+        self.copy_body(deadline, &mut data)?;
+        Ok(data)
+    }
+
+    /// Like `read_body`, but writes the bytes to `out` as they arrive instead of
+    /// collecting them.
+    ///
+    /// # Arguments
+    ///
+    /// * `deadline` - The instant after which reading stops, or `None` to read without a limit
+    /// * `out` - The writer the body is copied to
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(u64)` - The number of body bytes copied
+    /// * `Err(std::io::Error)` - As for `copy_all` or `copy_chunked`, depending on the mode
+    pub fn copy_body(
+        &mut self,
+        deadline: Option<Instant>,
+        out: &mut dyn Write,
+    ) -> Result<u64, std::io::Error> {
+        match self.mode {
+            BodyMode::Chunked => self.copy_chunked(deadline, out),
+            BodyMode::Length(_) | BodyMode::UntilClose => self.copy_all(deadline, out),
+        }
+    }
+
+    /// Returns the unconsumed buffered bytes, reading more from the stream if there are none.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(&[u8])` - The buffered bytes, empty only when the stream has reached EOF
+    /// * `Err(std::io::Error)` - If an I/O error occurs during reading
+    fn fill_buffer(&mut self) -> Result<&[u8], std::io::Error> {
+        if self.position >= self.buffer.len() {
+            self.buffer.resize(READ_SIZE, 0);
+            self.position = 0;
+            let read = loop {
+                match self.stream.read(&mut self.buffer) {
+                    Ok(read) => break read,
+                    Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                    Err(err) => {
+                        self.buffer.clear();
+                        return Err(err);
+                    }
+                }
+            };
+            self.buffer.truncate(read);
+            if read > 0 && self.first_read_at.is_none() {
+                self.first_read_at = Some(Instant::now());
+            }
+        }
+
+        Ok(&self.buffer[self.position..])
+    }
+
+    /// Marks `count` buffered bytes as consumed.
+    fn consume(&mut self, count: usize) {
+        self.position += count;
+        self.bytes_read += count;
+    }
+
     /// Reads a single byte from the stream.
     ///
     /// This is an internal helper method that maintains the bytes_read count
-    /// while reading individual bytes from the underlying TCP stream.
+    /// while reading individual bytes from the buffer.
     ///
     /// # Returns
     ///
@@ -73,78 +245,360 @@ impl StreamBuffer {
             }
         }
 
-        let mut buf = [0x00; 1];
-        self.stream.read_exact(&mut buf)?;
-        self.bytes_read += 1;
-        Ok(buf[0])
+        let byte = match self.fill_buffer()?.first() {
+            Some(&byte) => byte,
+            None => {
+                return Err(std::io::Error::new(
+                    ErrorKind::UnexpectedEof,
+                    "End of file reached",
+                ))
+            }
+        };
+        self.consume(1);
+        Ok(byte)
     }
 
-    /// Reads a single line from the stream until a newline character is encountered.
+    /// Reads the raw bytes of a single line from the stream, up to a newline character.
     ///
-    /// The returned string has whitespace trimmed from both ends and does not include
-    /// the newline character.
+    /// The returned bytes do not include the newline character but are otherwise
+    /// untouched, so a trailing `\r` is preserved and bytes outside the ASCII range
+    /// are not altered.
     ///
     /// # Returns
     ///
-    /// * `Ok(String)` - The line that was read
+    /// * `Ok(Vec<u8>)` - The bytes of the line that was read
     /// * `Err(std::io::Error)` - If an I/O error occurs during reading
-    pub fn read_line(&mut self) -> Result<String, std::io::Error> {
-        let mut buffer = String::new();
+    pub fn read_line_bytes(&mut self) -> Result<Vec<u8>, std::io::Error> {
+        let mut buffer = Vec::new();
 
         loop {
-            let c = match self.get_byte() {
-                Ok(byte) => byte as char,
+            let byte = match self.get_byte() {
+                Ok(byte) => byte,
                 Err(err) if err.kind() == ErrorKind::UnexpectedEof => break,
                 Err(err) => return Err(err),
             };
 
-            if c == '\n' {
+            if byte == b'\n' {
                 break;
             }
 
-            buffer.push(c);
+            buffer.push(byte);
         }
 
-        Ok(buffer.trim().to_string())
+        Ok(buffer)
     }
 
-    /// Reads all remaining bytes from the stream into a vector.
+    /// Copies the remaining bytes from the stream to `out` as they arrive.
+    ///
+    /// This method will read until EOF is reached, or until the length set with
+    /// `BodyMode::Length` has been read, giving up once `deadline` has passed.
+    /// The deadline is checked between reads, so a single read that blocks is still
+    /// only bounded by the timeout of the underlying stream.
+    ///
+    /// # Arguments
     ///
-    /// This method will read until EOF is reached.
+    /// * `deadline` - The instant after which reading stops, or `None` to read without a limit
+    /// * `out` - The writer the bytes are copied to
     ///
     /// # Returns
     ///
-    /// * `Ok(Vec<u8>)` - The bytes that were read
-    /// * `Err(std::io::Error)` - If an I/O error occurs during reading
-    pub fn read_all(&mut self) -> Result<Vec<u8>, std::io::Error> {
-        // If we know the length of the data, we only need to read that much and can close out the connection early
-        if let Some(total_bytes) = self.total_bytes {
-            let mut buffer = vec![0; total_bytes];
-            self.stream.read_exact(&mut buffer)?;
-            return Ok(buffer);
+    /// * `Ok(u64)` - The number of bytes copied
+    /// * `Err(std::io::Error)` - If reading or writing fails, or `ErrorKind::TimedOut` if the deadline passed
+    fn copy_all(
+        &mut self,
+        deadline: Option<Instant>,
+        out: &mut dyn Write,
+    ) -> Result<u64, std::io::Error> {
+        let mut copied = 0;
+
+        loop {
+            // If we know the length of the data, we only need to read that much and can close out the connection early
+            let remaining = match self.total_bytes {
+                Some(total_bytes) if self.bytes_read >= total_bytes => break,
+                Some(total_bytes) => total_bytes - self.bytes_read,
+                None => usize::MAX,
+            };
+
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Err(std::io::Error::new(
+                    ErrorKind::TimedOut,
+                    "Deadline reached while reading",
+                ));
+            }
+
+            let buffered = self.fill_buffer()?;
+            if buffered.is_empty() {
+                if self.total_bytes.is_some() {
+                    return Err(std::io::Error::new(
+                        ErrorKind::UnexpectedEof,
+                        "End of file reached",
+                    ));
+                }
+                break;
+            }
+
+            let count = remaining.min(buffered.len());
+            out.write_all(&buffered[..count])?;
+            self.consume(count);
+            copied += count as u64;
         }
 
-        // We don't know how many bytes are left, we need to keep reading
-        let mut buffer = Vec::new();
-        self.stream.read_to_end(&mut buffer)?;
-        Ok(buffer)
+        Ok(copied)
     }
 
-    /// Reads all remaining data from the stream as a UTF-8 string.
+    /// Copies a body framed with chunked transfer encoding to `out`, decoding it as
+    /// the chunks arrive.
+    ///
+    /// Chunk extensions and trailer fields are read and discarded. Chunk size lines and
+    /// the line breaks after chunks may end with either CRLF or a bare LF.
     ///
-    /// This method will read until EOF is reached and attempt to decode
-    /// the bytes as UTF-8.
+    /// # Arguments
+    ///
+    /// * `deadline` - The instant after which reading stops, or `None` to read without a limit
+    /// * `out` - The writer the decoded bytes are copied to
     ///
     /// # Returns
     ///
-    /// * `Ok(String)` - The decoded string
-    /// * `Err(std::io::Error)` - If an I/O error occurs during reading
-    ///                           or if the data is not valid UTF-8
-    pub fn read_all_string(&mut self) -> Result<String, std::io::Error> {
-        let bytes = self.read_all()?;
-        let s = std::str::from_utf8(&bytes)
-            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?
-            .to_owned();
-        Ok(s)
+    /// * `Ok(u64)` - The number of decoded bytes copied
+    /// * `Err(std::io::Error)` - If reading or writing fails, the framing is invalid
+    ///   (`ErrorKind::InvalidData`), or `ErrorKind::TimedOut` if the deadline passed
+    fn copy_chunked(
+        &mut self,
+        deadline: Option<Instant>,
+        out: &mut dyn Write,
+    ) -> Result<u64, std::io::Error> {
+        let invalid = || std::io::Error::new(ErrorKind::InvalidData, "Invalid chunk framing");
+        let mut copied = 0;
+
+        loop {
+            self.total_bytes = None;
+            let line = self.read_line_bytes()?;
+            let line = std::str::from_utf8(&line).map_err(|_| invalid())?;
+            let size = line.split(';').next().unwrap_or_default().trim();
+            let size = usize::from_str_radix(size, 16).map_err(|_| invalid())?;
+
+            if size == 0 {
+                break;
+            }
+
+            self.set_total_bytes(size);
+            copied += self.copy_all(deadline, out)?;
+            self.total_bytes = None;
+
+            // Every chunk is followed by a line break
+            if !self.read_line_bytes()?.trim_ascii().is_empty() {
+                return Err(invalid());
+            }
+        }
+
+        // Skip the trailer section, which ends with an empty line or the end of the stream
+        while !self.read_line_bytes()?.trim_ascii().is_empty() {}
+
+        Ok(copied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn test_read_line_bytes_preserves_non_ascii() {
+        let data = b"Content-Disposition: filename=\"caf\xc3\xa9\"\r\nnext".to_vec();
+        let mut buffer = StreamBuffer::new(Box::new(Cursor::new(data)));
+
+        let line = buffer.read_line_bytes().unwrap();
+        assert_eq!(line, b"Content-Disposition: filename=\"caf\xc3\xa9\"\r");
+
+        let line = buffer.read_line_bytes().unwrap();
+        assert_eq!(line, b"next");
+    }
+
+    #[test]
+    fn test_into_parts_returns_unconsumed_bytes() {
+        let data = b"line\r\nleftover".to_vec();
+        let mut buffer = StreamBuffer::new(Box::new(Cursor::new(data)));
+
+        assert_eq!(buffer.read_line_bytes().unwrap(), b"line\r");
+        let (mut stream, remainder) = buffer.into_parts();
+        assert_eq!(remainder, b"leftover");
+
+        let mut rest = Vec::new();
+        stream.read_to_end(&mut rest).unwrap();
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn test_with_prefill_reads_prefill_first() {
+        let stream = Cursor::new(b"ine two\r\nrest".to_vec());
+        let mut buffer = StreamBuffer::with_prefill(Box::new(stream), b"line one\r\nl".to_vec());
+
+        assert_eq!(buffer.read_line_bytes().unwrap(), b"line one\r");
+        assert_eq!(buffer.first_read_at(), None);
+        assert_eq!(buffer.read_line_bytes().unwrap(), b"line two\r");
+        assert!(buffer.first_read_at().is_some());
+        buffer.set_body_mode(BodyMode::Length(4));
+        assert_eq!(buffer.read_body(None).unwrap(), b"rest");
+    }
+
+    #[test]
+    fn test_read_all_keeps_bytes_buffered_by_read_line() {
+        let data = b"header\r\n\r\nbody\x00\xffbytes".to_vec();
+        let mut buffer = StreamBuffer::new(Box::new(Cursor::new(data)));
+
+        assert_eq!(buffer.read_line_bytes().unwrap(), b"header\r");
+        assert_eq!(buffer.read_line_bytes().unwrap(), b"\r");
+        assert_eq!(buffer.read_body(None).unwrap(), b"body\x00\xffbytes");
+    }
+
+    #[test]
+    fn test_read_all_with_total_bytes_spanning_buffer() {
+        let mut data = b"line\n".to_vec();
+        data.extend(std::iter::repeat_n(b'x', READ_SIZE * 2));
+        data.extend(b"trailing");
+        let mut buffer = StreamBuffer::new(Box::new(Cursor::new(data)));
+
+        assert_eq!(buffer.read_line_bytes().unwrap(), b"line");
+        buffer.set_body_mode(BodyMode::Length(READ_SIZE * 2));
+        let body = buffer.read_body(None).unwrap();
+        assert_eq!(body.len(), READ_SIZE * 2);
+        assert!(body.iter().all(|&byte| byte == b'x'));
+    }
+
+    #[test]
+    fn test_read_all_with_total_bytes_inside_buffer() {
+        let data = b"line\nabcdefgh".to_vec();
+        let mut buffer = StreamBuffer::new(Box::new(Cursor::new(data)));
+
+        assert_eq!(buffer.read_line_bytes().unwrap(), b"line");
+        buffer.set_body_mode(BodyMode::Length(3));
+        assert_eq!(buffer.read_body(None).unwrap(), b"abc");
+    }
+
+    #[test]
+    fn test_read_chunked() {
+        let data =
+            b"5;name=value\r\nhello\r\n7\r\n, world\r\n0\r\nTrailer: yes\r\n\r\nnext".to_vec();
+        let mut buffer = StreamBuffer::new(Box::new(Cursor::new(data)));
+
+        buffer.set_body_mode(BodyMode::Chunked);
+        assert_eq!(buffer.read_body(None).unwrap(), b"hello, world");
+        assert_eq!(buffer.read_line_bytes().unwrap(), b"next");
+    }
+
+    #[test]
+    fn test_read_chunked_invalid_framing() {
+        for data in [
+            &b"zz\r\nhello\r\n0\r\n\r\n"[..],
+            b"2\r\nhello\r\n0\r\n\r\n",
+            b"5\r\nhel",
+        ] {
+            let mut buffer = StreamBuffer::new(Box::new(Cursor::new(data.to_vec())));
+            buffer.set_body_mode(BodyMode::Chunked);
+            assert!(buffer.read_body(None).is_err());
+        }
+    }
+
+    /// A stream that fails every read, to check that nothing is read from it.
+    struct Unreadable;
+
+    impl Read for Unreadable {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(ErrorKind::WouldBlock.into())
+        }
+    }
+
+    impl Write for Unreadable {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_read_all_with_zero_total_bytes() {
+        let stream = Box::new(Unreadable);
+        let mut buffer =
+            StreamBuffer::with_prefill(stream, b"HTTP/1.1 204 No Content\r\n\r\n".to_vec());
+
+        assert_eq!(
+            buffer.read_line_bytes().unwrap(),
+            b"HTTP/1.1 204 No Content\r"
+        );
+        assert_eq!(buffer.read_line_bytes().unwrap(), b"\r");
+        buffer.set_body_mode(BodyMode::Length(0));
+        assert_eq!(buffer.read_body(None).unwrap(), b"");
+        assert_eq!(buffer.read_body(Some(Instant::now())).unwrap(), b"");
+        assert!(buffer.into_parts().1.is_empty());
+    }
+
+    #[test]
+    fn test_read_all_past_deadline() {
+        let data = b"line\nbody".to_vec();
+        let mut buffer = StreamBuffer::new(Box::new(Cursor::new(data)));
+
+        assert_eq!(buffer.read_line_bytes().unwrap(), b"line");
+        let err = buffer.read_body(Some(Instant::now())).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn test_read_all_with_total_bytes_past_eof() {
+        let data = b"line\nabc".to_vec();
+        let mut buffer = StreamBuffer::new(Box::new(Cursor::new(data)));
+
+        assert_eq!(buffer.read_line_bytes().unwrap(), b"line");
+        buffer.set_body_mode(BodyMode::Length(10));
+        let err = buffer.read_body(None).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_body_mode_length() {
+        let data = b"line\nbodynext".to_vec();
+        let mut buffer = StreamBuffer::new(Box::new(Cursor::new(data)));
+
+        assert_eq!(buffer.read_line_bytes().unwrap(), b"line");
+        buffer.set_body_mode(BodyMode::Length(4));
+        assert_eq!(buffer.mode, BodyMode::Length(4));
+        assert_eq!(buffer.read_body(None).unwrap(), b"body");
+        assert_eq!(buffer.into_parts().1, b"next");
+    }
+
+    #[test]
+    fn test_body_mode_chunked() {
+        let data = b"line\n4\r\nbody\r\n0\r\n\r\nnext".to_vec();
+        let mut buffer = StreamBuffer::new(Box::new(Cursor::new(data)));
+
+        assert_eq!(buffer.read_line_bytes().unwrap(), b"line");
+        buffer.set_body_mode(BodyMode::Chunked);
+        let mut out = Vec::new();
+        assert_eq!(buffer.copy_body(None, &mut out).unwrap(), 4);
+        assert_eq!(out, b"body");
+        assert_eq!(buffer.into_parts().1, b"next");
+    }
+
+    #[test]
+    fn test_body_mode_until_close() {
+        let data = b"line\nbody\r\n0\r\n\r\n".to_vec();
+        let mut buffer = StreamBuffer::new(Box::new(Cursor::new(data)));
+
+        assert_eq!(buffer.mode, BodyMode::UntilClose);
+        assert_eq!(buffer.read_line_bytes().unwrap(), b"line");
+        buffer.set_body_mode(BodyMode::Length(2));
+        buffer.set_body_mode(BodyMode::UntilClose);
+        assert_eq!(buffer.read_body(None).unwrap(), b"body\r\n0\r\n\r\n");
+    }
+
+    #[test]
+    fn test_body_mode_empty_length_never_reads() {
+        let mut buffer = StreamBuffer::new(Box::new(Unreadable));
+        buffer.set_body_mode(BodyMode::Length(0));
+        assert_eq!(buffer.read_body(None).unwrap(), b"");
     }
 }
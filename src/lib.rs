@@ -1,6 +1,19 @@
+//! A simple, lightweight HTTP client.
+//!
+//! Every public type is defined once, in the private `http` module, and re-exported
+//! from the crate root. `clienter::HttpClient`, `clienter::Uri` and the other types
+//! below are therefore the only paths to them; there is no second copy of any type
+//! that could fail to interoperate with these.
+
 mod internal;
 
 mod http;
-pub use http::*;
+pub use http::{
+    AddressFamily, AuthChallenge, Authority, Body, BodyReader, CancelHandle, ClientEvent,
+    Connector, Cookie, Encoding, EventHook, HeaderMergeReport, HttpClient, HttpError, HttpHeaders,
+    HttpMethod, HttpRequest, HttpResponse, HttpVersion, PoolStats, Protocol, ReadWrite,
+    RequestTarget, ResponseError, SameSite, StatusCode, TcpConnector, Uri, UriBuilder, UriError,
+    MAX_HEADER_LINES,
+};
 
 pub mod utils;
@@ -2,7 +2,8 @@
 //!
 //! This module provides various functions for splitting strings and parsing their parts
 //! into different types. It includes functions for splitting into tuples, arrays, and
-//! parsing split results into specific types.
+//! parsing split results into specific types, along with percent-encoding for the
+//! different parts of a URI.
 
 /// Splits a string into two parts at the first occurrence of a pattern.
 ///
@@ -53,9 +54,9 @@ pub fn split<'a, const N: usize>(s: &'a str, pat: &str) -> Option<[&'a str; N]>
     let mut arr = [""; N];
 
     let mut remainder = s;
-    for i in 0..(N - 1) {
+    for part in arr.iter_mut().take(N - 1) {
         let (left, right) = tuple_split(remainder, pat)?;
-        arr[i] = left;
+        *part = left;
         remainder = right;
     }
 
@@ -119,6 +120,175 @@ pub fn triple_split<'a>(s: &'a str, pat: &str) -> Option<(&'a str, &'a str, &'a
     Some((left, middle, right))
 }
 
+/// The bytes that `percent_encode` leaves as they are in some part of a URI.
+///
+/// ASCII letters and digits are never encoded. Each set adds the other characters
+/// that may appear literally in its component, following RFC 3986 section 3 and the
+/// WHATWG URL standard's percent-encode sets.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct EncodeSet {
+    /// Characters kept besides ASCII letters and digits
+    keep: &'static [u8],
+    /// Whether a space is written as `+` rather than `%20`
+    space_as_plus: bool,
+}
+
+impl EncodeSet {
+    /// A whole path: `/` separates segments and is kept along with the characters
+    /// allowed in a segment.
+    pub const PATH: EncodeSet = EncodeSet {
+        keep: b"-._~!$&'()*+,;=:@/",
+        space_as_plus: false,
+    };
+    /// A single path segment: like `PATH`, but `/` is encoded so the segment can't
+    /// change the structure of the path.
+    pub const PATH_SEGMENT: EncodeSet = EncodeSet {
+        keep: b"-._~!$&'()*+,;=:@",
+        space_as_plus: false,
+    };
+    /// A key or value in a query: `&`, `=`, `+` and `#` are encoded so they can't
+    /// break the structure of the query.
+    pub const QUERY: EncodeSet = EncodeSet {
+        keep: b"-._~!$'()*,;:@/?",
+        space_as_plus: false,
+    };
+    /// A key or value in `application/x-www-form-urlencoded` data: only `*-._` are
+    /// kept and a space becomes `+`.
+    pub const FORM: EncodeSet = EncodeSet {
+        keep: b"*-._",
+        space_as_plus: true,
+    };
+    /// A fragment, which may contain any URI character but `#`.
+    pub const FRAGMENT: EncodeSet = EncodeSet {
+        keep: b"-._~!$&'()*+,;=:@/?",
+        space_as_plus: false,
+    };
+
+    /// Determines whether a byte is left as it is.
+    fn keeps(&self, byte: u8) -> bool {
+        byte.is_ascii_alphanumeric() || self.keep.contains(&byte)
+    }
+}
+
+/// Percent-encodes a string for use in the part of a URI described by `set`.
+///
+/// Every byte of the UTF-8 encoding that the set doesn't keep is written as `%XX`,
+/// including `%` itself.
+///
+/// # Arguments
+/// * `s` - The string to encode
+/// * `set` - The characters to leave as they are
+///
+/// # Returns
+/// The encoded string
+///
+/// # Examples
+/// ```
+/// # use clienter::utils::{percent_encode, EncodeSet};
+/// assert_eq!(percent_encode("a b/c", EncodeSet::PATH), "a%20b/c");
+/// assert_eq!(percent_encode("a b/c", EncodeSet::PATH_SEGMENT), "a%20b%2Fc");
+/// assert_eq!(percent_encode("a=1&b", EncodeSet::QUERY), "a%3D1%26b");
+/// assert_eq!(percent_encode("a b&c", EncodeSet::FORM), "a+b%26c");
+/// ```
+pub fn percent_encode(s: &str, set: EncodeSet) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        if set.keeps(byte) {
+            encoded.push(byte as char);
+        } else if byte == b' ' && set.space_as_plus {
+            encoded.push('+');
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Decodes the `%XX` escapes in a string.
+///
+/// A `%` that isn't followed by two hexadecimal digits is kept as it is. The result is
+/// returned as bytes, since the escapes may encode text in any charset.
+///
+/// # Arguments
+/// * `s` - The string to decode
+///
+/// # Returns
+/// The decoded bytes
+///
+/// # Examples
+/// ```
+/// # use clienter::utils::percent_decode;
+/// assert_eq!(percent_decode("caf%C3%A9%20au%20lait"), "café au lait".as_bytes());
+/// assert_eq!(percent_decode("100%"), b"100%");
+/// ```
+pub fn percent_decode(s: &str) -> Vec<u8> {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|hex| bytes[i] == b'%' && hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    decoded
+}
+
+/// Encodes a string for use as a key or value in `application/x-www-form-urlencoded` data.
+///
+/// Alphanumeric characters and `*-._` are kept as-is, spaces become `+` and every other
+/// byte of the UTF-8 encoding is percent-encoded. This is `percent_encode` with
+/// `EncodeSet::FORM`.
+///
+/// # Arguments
+/// * `s` - The string to encode
+///
+/// # Returns
+/// The encoded string
+///
+/// # Examples
+/// ```
+/// # use clienter::utils::form_urlencode;
+/// assert_eq!(form_urlencode("a b&c"), "a+b%26c");
+/// ```
+pub fn form_urlencode(s: &str) -> String {
+    percent_encode(s, EncodeSet::FORM)
+}
+
+/// Percent-encodes a string for use as a single segment of a URI path.
+///
+/// Unreserved characters, sub-delimiters, `:` and `@` are kept as-is (RFC 3986 section
+/// 3.3). Every other byte of the UTF-8 encoding is percent-encoded, including `/`,
+/// `?`, `#` and `%`, so the segment can't change the structure of the path. This is
+/// `percent_encode` with `EncodeSet::PATH_SEGMENT`.
+///
+/// # Arguments
+/// * `s` - The segment to encode
+///
+/// # Returns
+/// The encoded segment
+///
+/// # Examples
+/// ```
+/// # use clienter::utils::encode_path_segment;
+/// assert_eq!(encode_path_segment("a/b c?"), "a%2Fb%20c%3F");
+/// assert_eq!(encode_path_segment("user@host:1"), "user@host:1");
+/// ```
+pub fn encode_path_segment(s: &str) -> String {
+    percent_encode(s, EncodeSet::PATH_SEGMENT)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -188,6 +358,41 @@ mod tests {
         assert_eq!(right, "");
     }
 
+    #[test]
+    fn test_percent_encode_sets() {
+        let input = "a b/c?d=e&f+g#h%i~";
+        assert_eq!(
+            percent_encode(input, EncodeSet::PATH),
+            "a%20b/c%3Fd=e&f+g%23h%25i~"
+        );
+        assert_eq!(
+            percent_encode(input, EncodeSet::QUERY),
+            "a%20b/c?d%3De%26f%2Bg%23h%25i~"
+        );
+        assert_eq!(
+            percent_encode(input, EncodeSet::FORM),
+            "a+b%2Fc%3Fd%3De%26f%2Bg%23h%25i%7E"
+        );
+        assert_eq!(
+            percent_encode(input, EncodeSet::FRAGMENT),
+            "a%20b/c?d=e&f+g%23h%25i~"
+        );
+        assert_eq!(percent_encode("é", EncodeSet::PATH), "%C3%A9");
+    }
+
+    #[test]
+    fn test_percent_decode_round_trip() {
+        let input = "a b/c?d=e&f+g#h%i~é";
+        for set in [EncodeSet::PATH, EncodeSet::QUERY, EncodeSet::FRAGMENT] {
+            assert_eq!(
+                percent_decode(&percent_encode(input, set)),
+                input.as_bytes()
+            );
+        }
+        assert_eq!(percent_decode("%zz%+F%4"), b"%zz%+F%4");
+        assert_eq!(percent_decode("%4a%4A"), b"JJ");
+    }
+
     #[test]
     fn test_tuple_split_empty_input() {
         let s = "";
@@ -0,0 +1,188 @@
+mod common;
+
+use std::collections::HashMap;
+use std::io::Cursor;
+
+use clienter::{HttpClient, HttpError, HttpMethod};
+use common::{written, MockStream, EMPTY_RESPONSE};
+
+#[test]
+fn test_body_sent_with_content_length() {
+    let client = HttpClient::new();
+    let mut request = client.request(HttpMethod::POST, "http://example.com/items");
+    request.set_body("hello");
+
+    let written = written(&client, &request);
+    assert!(written.contains("Content-Length: 5\r\n"));
+    assert!(written.ends_with("\r\n\r\nhello"));
+    assert!(!written.contains("Content-Type"));
+}
+
+#[test]
+fn test_sniff_content_type() {
+    let client = HttpClient::new();
+    let cases: [(&[u8], &str); 4] = [
+        (b"{\"a\": 1}", "application/json"),
+        (b"[1, 2]", "application/json"),
+        (b"plain words", "text/plain"),
+        (b"\xff\xfe\x00", "application/octet-stream"),
+    ];
+
+    for (body, expected) in cases {
+        let mut request = client
+            .request(HttpMethod::POST, "http://example.com/")
+            .sniff_content_type(true);
+        request.set_body(body);
+        let written = written(&client, &request);
+        assert!(written.contains(&format!("Content-Type: {}\r\n", expected)));
+    }
+}
+
+#[test]
+fn test_sniff_keeps_explicit_content_type() {
+    let client = HttpClient::new();
+    let mut request = client
+        .request(HttpMethod::POST, "http://example.com/")
+        .sniff_content_type(true);
+    request
+        .headers
+        .insert("Content-Type".to_string(), "application/xml".to_string());
+    request.set_body("{}");
+
+    let written = written(&client, &request);
+    assert!(written.contains("Content-Type: application/xml\r\n"));
+    assert!(!written.contains("application/json"));
+}
+
+#[test]
+fn test_chunked_body() {
+    let client = HttpClient::new();
+    let request = client
+        .request(HttpMethod::POST, "http://example.com/upload")
+        .chunked_body(Cursor::new(b"streamed data".to_vec()));
+
+    let written = written(&client, &request);
+    assert!(written.contains("Transfer-Encoding: chunked\r\n"));
+    assert!(!written.contains("Content-Length"));
+    assert!(written.ends_with("\r\n\r\nD\r\nstreamed data\r\n0\r\n\r\n"));
+}
+
+#[test]
+fn test_chunked_body_consumed_once() {
+    let client = HttpClient::new();
+    let request = client
+        .request(HttpMethod::POST, "http://example.com/upload")
+        .chunked_body(Cursor::new(b"once".to_vec()));
+    written(&client, &request);
+
+    let (stream, _) = MockStream::new(EMPTY_RESPONSE);
+    let result = client.send_on(stream, &request.clone());
+    assert_eq!(result.err(), Some(HttpError::BodyConsumed));
+}
+
+#[test]
+fn test_text_body() {
+    let client = HttpClient::new();
+    let request = client
+        .request(HttpMethod::POST, "http://example.com/notes")
+        .text(String::from("héllo"));
+
+    let written = written(&client, &request);
+    assert!(written.contains("\r\nContent-Type: text/plain; charset=utf-8\r\n"));
+    assert!(written.contains("\r\nContent-Length: 6\r\n"));
+    assert!(written.ends_with("\r\n\r\nhéllo"), "{}", written);
+}
+
+#[test]
+fn test_text_keeps_explicit_content_type() {
+    let client = HttpClient::new();
+    let mut request = client.request(HttpMethod::POST, "http://example.com/");
+    request
+        .headers
+        .insert("content-type".to_string(), "application/json".to_string());
+    let request = request.text(r#"{"a":1}"#);
+
+    let written = written(&client, &request);
+    assert!(written.contains("content-type: application/json\r\n"));
+    assert!(!written.contains("text/plain"));
+}
+
+#[test]
+fn test_try_clone_for_retries() {
+    let client = HttpClient::new();
+    let mut request = client.request(HttpMethod::PUT, "http://example.com/item");
+    request.set_body("payload");
+    let retry = request.try_clone().unwrap();
+    written(&client, &request);
+    assert!(written(&client, &retry).ends_with("\r\n\r\npayload"));
+
+    let request = request.chunked_body(Cursor::new(b"once".to_vec()));
+    assert!(request.try_clone().is_none());
+}
+
+#[test]
+fn test_trace_with_body_is_rejected() {
+    let client = HttpClient::new();
+    let mut request = client.request(HttpMethod::TRACE, "http://example.com/");
+    request.set_body("not allowed");
+
+    let (stream, written) = MockStream::new(EMPTY_RESPONSE);
+    let err = client.send_on(stream, &request).err().unwrap();
+    assert_eq!(err, HttpError::BodyNotAllowed(HttpMethod::TRACE));
+    assert_eq!(err.to_string(), "TRACE requests cannot have a body");
+    assert!(written.lock().unwrap().is_empty());
+
+    // Rejected before any connection is attempted
+    let err = client.send(&request).err().unwrap();
+    assert_eq!(err, HttpError::BodyNotAllowed(HttpMethod::TRACE));
+}
+
+#[test]
+fn test_connect_body_is_dropped() {
+    let client = HttpClient::new();
+    let mut request = client.request(HttpMethod::CONNECT, "http://example.com:443/");
+    request.set_body("dropped");
+
+    let written = written(&client, &request);
+    assert!(!written.contains("Content-Length"));
+    assert!(written.ends_with("\r\n\r\n"));
+    assert!(!written.contains("dropped"));
+}
+
+#[test]
+fn test_form_from_slice_and_hash_map() {
+    let client = HttpClient::new();
+    let pairs = [("b", "two words"), ("a", "x&y=z"), ("c", "")];
+    let from_slice = client
+        .request(HttpMethod::POST, "http://example.com/form")
+        .form(pairs);
+    let from_map = client
+        .request(HttpMethod::POST, "http://example.com/form")
+        .form(HashMap::from(
+            pairs.map(|(k, v)| (k.to_string(), v.to_string())),
+        ));
+    assert_eq!(from_slice, from_map);
+
+    let written = written(&client, &from_map);
+    assert!(written.contains("Content-Type: application/x-www-form-urlencoded\r\n"));
+    assert!(written.ends_with("\r\n\r\na=x%26y%3Dz&b=two+words&c="));
+}
+
+#[test]
+fn test_retain_body_keeps_raw_bytes() {
+    let raw = b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello";
+    let mut client = HttpClient::new();
+    let request = client.request(HttpMethod::GET, "http://example.com/");
+
+    let (stream, _) = MockStream::new(raw);
+    let mut response = client.send_on(stream, &request).unwrap();
+    assert_eq!(response.body_as_string().unwrap(), "hello");
+    assert!(response.raw_body().is_empty());
+
+    client.retain_body = true;
+    let (stream, _) = MockStream::new(raw);
+    let mut response = client.send_on(stream, &request).unwrap();
+    assert!(response.raw_body().is_empty());
+    assert_eq!(response.body_as_string().unwrap(), "hello");
+    assert_eq!(response.raw_body(), b"hello");
+}
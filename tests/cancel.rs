@@ -0,0 +1,89 @@
+mod common;
+
+use std::io::Write;
+use std::net::TcpListener;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clienter::{CancelHandle, HttpClient, HttpError, HttpMethod, ResponseError};
+
+/// Starts a server that writes `head` and then sends nothing for two seconds.
+fn serve_stalled(head: &'static [u8]) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        common::read_request_head(&mut stream);
+        stream.write_all(head).unwrap();
+        thread::sleep(Duration::from_secs(2));
+    });
+    format!("http://{}/", addr)
+}
+
+/// Cancels `handle` from another thread after `delay`.
+fn cancel_after(handle: &CancelHandle, delay: Duration) {
+    let handle = handle.clone();
+    thread::spawn(move || {
+        thread::sleep(delay);
+        handle.cancel();
+    });
+}
+
+#[test]
+fn test_cancelled_before_sending() {
+    let client = HttpClient::new();
+    let cancel = CancelHandle::new();
+    cancel.cancel();
+    let request = client
+        .request(HttpMethod::GET, "http://127.0.0.1:9/")
+        .cancel_on(cancel);
+
+    assert_eq!(client.send(&request).err(), Some(HttpError::Cancelled));
+}
+
+#[test]
+fn test_cancel_while_waiting_for_headers() {
+    let uri = serve_stalled(b"HTTP/1.1 200 OK\r\n");
+    let client = HttpClient::new();
+    let cancel = CancelHandle::new();
+    let request = client
+        .request(HttpMethod::GET, uri)
+        .cancel_on(cancel.clone());
+
+    let start = Instant::now();
+    cancel_after(&cancel, Duration::from_millis(200));
+    assert_eq!(client.send(&request).err(), Some(HttpError::Cancelled));
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn test_cancel_while_reading_body() {
+    let uri = serve_stalled(b"HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\npartial");
+    let client = HttpClient::new();
+    let cancel = CancelHandle::new();
+    let request = client
+        .request(HttpMethod::GET, uri)
+        .cancel_on(cancel.clone());
+    let mut response = client.send(&request).unwrap();
+
+    let start = Instant::now();
+    cancel_after(&cancel, Duration::from_millis(200));
+    assert_eq!(response.body(), Err(ResponseError::Cancelled));
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn test_read_timeout_still_applies_with_a_handle() {
+    let uri = serve_stalled(b"HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\n");
+    let mut client = HttpClient::new();
+    client.read_timeout = Some(Duration::from_millis(300));
+    let request = client
+        .request(HttpMethod::GET, uri)
+        .cancel_on(CancelHandle::new());
+    let mut response = client.send(&request).unwrap();
+
+    let start = Instant::now();
+    assert_eq!(response.body(), Err(ResponseError::Timeout));
+    assert!(start.elapsed() >= Duration::from_millis(250));
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
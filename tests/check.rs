@@ -0,0 +1,33 @@
+mod common;
+
+use std::net::TcpListener;
+
+use clienter::{HttpClient, HttpError, StatusCode};
+use common::serve_once;
+
+#[test]
+fn test_check_returns_status_without_reading_body() {
+    // The advertised body is never sent, so reading it would block until the server closes
+    let (addr, server) =
+        serve_once(b"HTTP/1.1 503 Service Unavailable\r\nContent-Length: 100\r\n\r\n");
+    let client = HttpClient::new();
+
+    let status = client.check(format!("http://{}/health", addr).as_str());
+    assert_eq!(status, Ok(StatusCode::ServiceUnavailable503));
+
+    let request = server.join().unwrap();
+    assert!(request.starts_with(b"HEAD /health HTTP/1.1\r\n"));
+}
+
+#[test]
+fn test_check_unreachable_server() {
+    // Bind and immediately drop a listener to get a port nothing is listening on
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let client = HttpClient::new();
+
+    let result = client.check(format!("http://{}/", addr).as_str());
+    assert!(matches!(result, Err(HttpError::ConnectionFailed { .. })));
+}
@@ -0,0 +1,91 @@
+#![allow(dead_code)]
+
+use std::io::{Cursor, Read, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+
+use clienter::{HttpClient, HttpMethod, HttpRequest, HttpResponse};
+
+/// A response without a body, for tests that only look at what the client sent.
+pub const EMPTY_RESPONSE: &[u8] = b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n";
+
+/// An in-memory stream that replays a canned response and records everything written to it.
+pub struct MockStream {
+    input: Cursor<Vec<u8>>,
+    output: Arc<Mutex<Vec<u8>>>,
+}
+
+impl MockStream {
+    /// Creates a stream that will yield `response` when read, along with a handle to the bytes written.
+    pub fn new(response: &[u8]) -> (Self, Arc<Mutex<Vec<u8>>>) {
+        let output = Arc::new(Mutex::new(Vec::new()));
+        let stream = MockStream {
+            input: Cursor::new(response.to_vec()),
+            output: output.clone(),
+        };
+        (stream, output)
+    }
+}
+
+impl Read for MockStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.input.read(buf)
+    }
+}
+
+impl Write for MockStream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.output.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Sends `request` over a `MockStream` answering `EMPTY_RESPONSE`, and returns
+/// everything the client wrote.
+pub fn written(client: &HttpClient, request: &HttpRequest) -> String {
+    let (stream, written) = MockStream::new(EMPTY_RESPONSE);
+    client.send_on(stream, request).unwrap();
+    let written = written.lock().unwrap().clone();
+    String::from_utf8_lossy(&written).to_string()
+}
+
+/// Sends a `method` request to `http://example.com/` over a `MockStream` answering
+/// `raw`, and returns the response.
+pub fn respond(method: HttpMethod, raw: &[u8]) -> HttpResponse {
+    let (stream, _) = MockStream::new(raw);
+    let client = HttpClient::new();
+    let request = client.request(method, "http://example.com/");
+    client.send_on(stream, &request).unwrap()
+}
+
+/// Reads a request head (up to and including the blank line) from `stream`.
+pub fn read_request_head(stream: &mut impl Read) -> Vec<u8> {
+    let mut request = Vec::new();
+    let mut byte = [0u8; 1];
+    while !request.ends_with(b"\r\n\r\n") {
+        match stream.read(&mut byte) {
+            Ok(1) => request.push(byte[0]),
+            _ => break,
+        }
+    }
+    request
+}
+
+/// Starts a loopback server that accepts a single connection, reads the request head,
+/// writes `response` and closes. The join handle yields the request head that was received.
+pub fn serve_once(response: &'static [u8]) -> (SocketAddr, JoinHandle<Vec<u8>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let handle = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let request = read_request_head(&mut stream);
+        stream.write_all(response).unwrap();
+        request
+    });
+    (addr, handle)
+}
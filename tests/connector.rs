@@ -0,0 +1,201 @@
+mod common;
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clienter::{
+    Connector, HttpClient, HttpError, HttpMethod, ReadWrite, ResponseError, TcpConnector,
+};
+use common::{serve_once, MockStream};
+
+/// Records where it was asked to connect and replays a canned response.
+struct Recording {
+    calls: Mutex<Vec<(String, Option<Duration>)>>,
+    written: Mutex<Option<Arc<Mutex<Vec<u8>>>>>,
+}
+
+impl Connector for Recording {
+    fn connect(
+        &self,
+        addr: &str,
+        timeout: Option<Duration>,
+    ) -> std::io::Result<Box<dyn ReadWrite>> {
+        self.calls.lock().unwrap().push((addr.to_string(), timeout));
+        let (stream, written) = MockStream::new(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok");
+        *self.written.lock().unwrap() = Some(written);
+        Ok(Box::new(stream))
+    }
+}
+
+struct Refusing;
+
+impl Connector for Refusing {
+    fn connect(&self, _: &str, _: Option<Duration>) -> std::io::Result<Box<dyn ReadWrite>> {
+        Err(ErrorKind::ConnectionRefused.into())
+    }
+}
+
+#[test]
+fn test_custom_connector_carries_request() {
+    let connector = Arc::new(Recording {
+        calls: Mutex::new(Vec::new()),
+        written: Mutex::new(None),
+    });
+    let mut client = HttpClient::new();
+    client.timeout = Some(Duration::from_secs(3));
+    client.connector = Some(connector.clone());
+
+    let request = client.request(HttpMethod::GET, "http://unresolvable.invalid:8080/path");
+    let mut response = client.send(&request).unwrap();
+    assert_eq!(response.body_as_string().unwrap(), "ok");
+    assert_eq!(client.pool_stats().created, 1);
+
+    assert_eq!(
+        *connector.calls.lock().unwrap(),
+        [(
+            "unresolvable.invalid:8080".to_string(),
+            Some(Duration::from_secs(3))
+        )]
+    );
+    let written = connector.written.lock().unwrap().clone().unwrap();
+    assert!(written
+        .lock()
+        .unwrap()
+        .starts_with(b"GET /path HTTP/1.1\r\n"));
+}
+
+#[test]
+fn test_custom_connector_failure() {
+    let mut client = HttpClient::new();
+    client.connector = Some(Arc::new(Refusing));
+
+    let request = client.request(HttpMethod::GET, "http://example.com/");
+    let err = client.send(&request).err().unwrap();
+    assert_eq!(
+        err,
+        HttpError::ConnectorFailed(ErrorKind::ConnectionRefused)
+    );
+    assert_eq!(err.to_string(), "connector failed: connection refused");
+}
+
+#[test]
+fn test_tcp_connector() {
+    let (addr, server) = serve_once(b"HTTP/1.1 204 No Content\r\n\r\n");
+    let mut client = HttpClient::new();
+    client.connector = Some(Arc::new(TcpConnector));
+
+    let request = client.request(HttpMethod::GET, format!("http://{}/", addr).as_str());
+    let response = client.send(&request).unwrap();
+    assert_eq!(response.status_code().to_string(), "204 No Content");
+    server.join().unwrap();
+}
+
+#[test]
+fn test_https_without_tls_is_rejected() {
+    let client = HttpClient::new();
+    let request = client.request(HttpMethod::GET, "https://unresolvable.invalid/");
+    let err = client.send(&request).err().unwrap();
+    assert_eq!(err, HttpError::TlsUnsupported);
+    assert_eq!(client.pool_stats().created, 0);
+}
+
+#[test]
+fn test_https_through_custom_connector() {
+    let connector = Arc::new(Recording {
+        calls: Mutex::new(Vec::new()),
+        written: Mutex::new(None),
+    });
+    let mut client = HttpClient::new();
+    client.connector = Some(connector.clone());
+
+    let request = client.request(HttpMethod::GET, "https://unresolvable.invalid/");
+    let mut response = client.send(&request).unwrap();
+    assert_eq!(response.body_as_string().unwrap(), "ok");
+    assert_eq!(
+        connector.calls.lock().unwrap()[0].0,
+        "unresolvable.invalid:443"
+    );
+}
+
+/// Starts a server that sends part of a response head and then nothing for four seconds.
+fn serve_stalled() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        common::read_request_head(&mut stream);
+        stream.write_all(b"HTTP/1.1 200 OK\r\nX-Slow: ").unwrap();
+        thread::sleep(Duration::from_secs(4));
+        let _ = stream.write_all(b"done\r\nContent-Length: 0\r\n\r\n");
+    });
+    format!("http://{}/", addr)
+}
+
+#[test]
+fn test_total_timeout_interrupts_stalled_connector_stream() {
+    let uri = serve_stalled();
+    let mut client = HttpClient::new();
+    client.connector = Some(Arc::new(TcpConnector));
+    client.total_timeout = Some(Duration::from_millis(300));
+
+    let start = Instant::now();
+    let request = client.request(HttpMethod::GET, uri.as_str());
+    assert_eq!(client.send(&request).err(), Some(HttpError::Timeout));
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
+/// A stream the client cannot see the socket of.
+struct Opaque(TcpStream);
+
+impl Read for Opaque {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl Write for Opaque {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
+
+/// Connects over TCP, hiding the socket behind `Opaque`.
+struct OpaqueConnector;
+
+impl Connector for OpaqueConnector {
+    fn connect(&self, addr: &str, _: Option<Duration>) -> std::io::Result<Box<dyn ReadWrite>> {
+        Ok(Box::new(Opaque(TcpStream::connect(addr)?)))
+    }
+}
+
+#[test]
+fn test_total_timeout_checked_between_reads_of_other_streams() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        common::read_request_head(&mut stream);
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 1000\r\n\r\n")
+            .unwrap();
+        while stream.write_all(b"x").is_ok() {
+            thread::sleep(Duration::from_millis(50));
+        }
+    });
+    let mut client = HttpClient::new();
+    client.connector = Some(Arc::new(OpaqueConnector));
+    client.total_timeout = Some(Duration::from_millis(300));
+
+    let start = Instant::now();
+    let request = client.request(HttpMethod::GET, format!("http://{}/", addr).as_str());
+    let mut response = client.send(&request).unwrap();
+    assert_eq!(response.body().err(), Some(ResponseError::Timeout));
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
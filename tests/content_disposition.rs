@@ -0,0 +1,72 @@
+use std::io::Cursor;
+
+use clienter::HttpResponse;
+
+fn filename(value: &str) -> Option<String> {
+    let raw = format!(
+        "HTTP/1.1 200 OK\r\nContent-Disposition: {}\r\nContent-Length: 0\r\n\r\n",
+        value
+    );
+    let response = HttpResponse::build(Cursor::new(raw.into_bytes()))
+        .ok()
+        .unwrap();
+    response.content_disposition_filename()
+}
+
+#[test]
+fn test_plain_and_quoted_filenames() {
+    assert_eq!(
+        filename("attachment; filename=report.pdf").as_deref(),
+        Some("report.pdf")
+    );
+    assert_eq!(
+        filename(r#"attachment; FILENAME="annual report; 2024 \"final\".pdf""#).as_deref(),
+        Some(r#"annual report; 2024 "final".pdf"#)
+    );
+    assert_eq!(
+        filename("inline; size=10; creation-date; filename=x.txt").as_deref(),
+        Some("x.txt")
+    );
+}
+
+#[test]
+fn test_extended_filename_is_preferred() {
+    assert_eq!(
+        filename("attachment; filename*=UTF-8''%E2%82%AC%20rates.csv; filename=rates.csv")
+            .as_deref(),
+        Some("€ rates.csv")
+    );
+    assert_eq!(
+        filename("attachment; filename*=iso-8859-1'en'%A3%20rates.csv").as_deref(),
+        Some("£ rates.csv")
+    );
+    // An unsupported charset falls back to the plain parameter
+    assert_eq!(
+        filename("attachment; filename*=KOI8-R''%F0; filename=fallback.txt").as_deref(),
+        Some("fallback.txt")
+    );
+}
+
+#[test]
+fn test_directories_are_stripped() {
+    assert_eq!(
+        filename(r#"attachment; filename="../../etc/passwd""#).as_deref(),
+        Some("passwd")
+    );
+    assert_eq!(
+        filename(r#"attachment; filename="C:\\Temp\\evil.exe""#).as_deref(),
+        Some("evil.exe")
+    );
+    assert_eq!(filename(r#"attachment; filename="..""#), None);
+}
+
+#[test]
+fn test_missing_or_malformed_headers() {
+    let raw = b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n".to_vec();
+    let response = HttpResponse::build(Cursor::new(raw)).ok().unwrap();
+    assert_eq!(response.content_disposition_filename(), None);
+
+    assert_eq!(filename("attachment"), None);
+    assert_eq!(filename(r#"attachment; filename="unterminated"#), None);
+    assert_eq!(filename("attachment; filename*=UTF-8''%FF"), None);
+}
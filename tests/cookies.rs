@@ -0,0 +1,27 @@
+mod common;
+
+use clienter::{HttpClient, HttpMethod};
+use common::MockStream;
+
+#[test]
+fn test_repeated_set_cookie_headers() {
+    let client = HttpClient::new();
+    let (stream, _) = MockStream::new(
+        b"HTTP/1.1 200 OK\r\n\
+          Set-Cookie: session=abc; HttpOnly\r\n\
+          Set-Cookie: theme=dark; Path=/\r\n\
+          Content-Length: 0\r\n\r\n",
+    );
+
+    let request = client.request(HttpMethod::GET, "http://example.com/login");
+    let response = client.send_on(stream, &request).unwrap();
+
+    assert_eq!(response.headers.get_all("Set-Cookie").len(), 2);
+
+    let cookies = response.cookies();
+    assert_eq!(cookies.len(), 2);
+    assert_eq!(cookies[0].name, "session");
+    assert!(cookies[0].http_only);
+    assert_eq!(cookies[1].name, "theme");
+    assert_eq!(cookies[1].path.as_deref(), Some("/"));
+}
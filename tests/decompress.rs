@@ -0,0 +1,99 @@
+mod common;
+
+use clienter::{HttpMethod, HttpResponse, ResponseError};
+
+/// "hello gzip" compressed with gzip.
+const GZIP_BODY: &[u8] = b"\x1f\x8b\x08\x00\x00\x00\x00\x00\x02\x03\xcb\x48\xcd\xc9\xc9\x57\x48\xaf\xca\x2c\x00\x00\x19\x6a\xd2\xdf\x0a\x00\x00\x00";
+
+/// "hello deflate" compressed as a zlib stream.
+#[cfg(feature = "gzip")]
+const DEFLATE_BODY: &[u8] =
+    b"\x78\x9c\xcb\x48\xcd\xc9\xc9\x57\x48\x49\x4d\xcb\x49\x2c\x49\x05\x00\x23\x0c\x05\x0a";
+
+/// "hello brotli hello brotli hello brotli hello brotli" compressed with Brotli.
+const BROTLI_BODY: &[u8] = b"\x1b\x32\x00\xf8\x1d\xa9\x53\x9f\xbb\x70\x2d\x56\x86\x26\x27\x41\xd8\xe8\x92\x2d\xed\x2d\x8c\x8a\x06\x86\xcc\xf8\x05";
+
+fn respond_encoded(encoding: Option<&str>, body: &[u8]) -> HttpResponse {
+    let mut raw = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n", body.len());
+    if let Some(encoding) = encoding {
+        raw.push_str(&format!("Content-Encoding: {}\r\n", encoding));
+    }
+    raw.push_str("\r\n");
+    let mut raw = raw.into_bytes();
+    raw.extend_from_slice(body);
+
+    common::respond(HttpMethod::GET, &raw)
+}
+
+#[test]
+fn test_decompressed_body_identity() {
+    let mut response = respond_encoded(None, b"plain");
+    assert_eq!(response.decompressed_body().unwrap(), b"plain");
+
+    let mut response = respond_encoded(Some("identity"), b"plain");
+    assert_eq!(response.decompressed_body().unwrap(), b"plain");
+}
+
+#[test]
+fn test_decompressed_body_unknown_encoding_leaves_body_unread() {
+    let mut response = respond_encoded(Some("zstd"), b"raw");
+    assert_eq!(
+        response.decompressed_body(),
+        Err(ResponseError::UnsupportedEncoding("zstd".to_string()))
+    );
+    assert_eq!(response.body().unwrap(), b"raw");
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_decompressed_body_gzip_and_deflate() {
+    let mut response = respond_encoded(Some("gzip"), GZIP_BODY);
+    assert_eq!(response.decompressed_body().unwrap(), b"hello gzip");
+
+    let mut response = respond_encoded(Some("deflate"), DEFLATE_BODY);
+    assert_eq!(response.decompressed_body().unwrap(), b"hello deflate");
+
+    let mut response = respond_encoded(Some("gzip"), b"not gzip");
+    assert_eq!(
+        response.decompressed_body(),
+        Err(ResponseError::InvalidBody)
+    );
+}
+
+#[cfg(not(feature = "gzip"))]
+#[test]
+fn test_decompressed_body_gzip_requires_feature() {
+    let mut response = respond_encoded(Some("gzip"), GZIP_BODY);
+    assert_eq!(
+        response.decompressed_body(),
+        Err(ResponseError::UnsupportedEncoding("gzip".to_string()))
+    );
+    assert_eq!(response.body().unwrap(), GZIP_BODY);
+}
+
+#[cfg(feature = "brotli")]
+#[test]
+fn test_decompressed_body_brotli() {
+    let mut response = respond_encoded(Some("br"), BROTLI_BODY);
+    assert_eq!(
+        response.decompressed_body().unwrap(),
+        b"hello brotli hello brotli hello brotli hello brotli"
+    );
+
+    let mut response = respond_encoded(Some("br"), &BROTLI_BODY[..10]);
+    assert_eq!(
+        response.decompressed_body(),
+        Err(ResponseError::InvalidBody)
+    );
+}
+
+#[cfg(not(feature = "brotli"))]
+#[test]
+fn test_decompressed_body_brotli_requires_feature() {
+    let mut response = respond_encoded(Some("br"), BROTLI_BODY);
+    assert_eq!(
+        response.decompressed_body(),
+        Err(ResponseError::UnsupportedEncoding("br".to_string()))
+    );
+    assert_eq!(response.body().unwrap(), BROTLI_BODY);
+}
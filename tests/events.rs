@@ -0,0 +1,123 @@
+mod common;
+
+use std::io::{Cursor, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use clienter::{ClientEvent, HttpClient, HttpHeaders, HttpMethod, HttpResponse};
+use common::{serve_once, MockStream, EMPTY_RESPONSE};
+
+/// Returns a client whose events are recorded in the returned list.
+fn recording_client() -> (HttpClient, Arc<Mutex<Vec<ClientEvent>>>) {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    let mut client = HttpClient::new();
+    client.on_event = Some(Arc::new(move |event| recorded.lock().unwrap().push(event)));
+    (client, events)
+}
+
+#[test]
+fn test_send_emits_lifecycle_events_in_order() {
+    let (addr, server) = serve_once(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nhi");
+    let (client, events) = recording_client();
+
+    let request = client.request(HttpMethod::GET, format!("http://{}/", addr).as_str());
+    let mut response = client.send(&request).unwrap();
+    assert_eq!(response.body_as_string().unwrap(), "hi");
+    server.join().unwrap();
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 5);
+    assert_eq!(
+        events[0],
+        ClientEvent::DnsResolved {
+            addr,
+            elapsed: events[0].elapsed()
+        }
+    );
+    assert!(matches!(events[1], ClientEvent::Connected { .. }));
+    assert!(matches!(events[2], ClientEvent::RequestSent { .. }));
+    assert!(matches!(events[3], ClientEvent::FirstByteReceived { .. }));
+    assert!(matches!(events[4], ClientEvent::ResponseComplete { .. }));
+    assert!(events
+        .windows(2)
+        .all(|pair| pair[0].elapsed() <= pair[1].elapsed()));
+}
+
+#[test]
+fn test_send_on_skips_connection_events() {
+    let (stream, _) = MockStream::new(EMPTY_RESPONSE);
+    let (client, events) = recording_client();
+
+    let request = client.request(HttpMethod::GET, "http://example.com/");
+    let mut response = client.send_on(stream, &request).unwrap();
+    response.body().unwrap();
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 3);
+    assert!(matches!(events[0], ClientEvent::RequestSent { .. }));
+    assert!(matches!(events[1], ClientEvent::FirstByteReceived { .. }));
+    assert!(matches!(events[2], ClientEvent::ResponseComplete { .. }));
+}
+
+#[test]
+fn test_time_to_first_byte() {
+    let (addr, server) = {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            common::read_request_head(&mut stream);
+            std::thread::sleep(Duration::from_millis(100));
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nhi")
+                .unwrap();
+        });
+        (addr, server)
+    };
+    let client = HttpClient::new();
+    let request = client.request(HttpMethod::GET, format!("http://{}/", addr).as_str());
+    let mut response = client.send(&request).unwrap();
+    response.body().unwrap();
+    server.join().unwrap();
+
+    let ttfb = response.time_to_first_byte().unwrap();
+    assert!(ttfb >= Duration::from_millis(100), "{:?}", ttfb);
+
+    let response = HttpResponse::build(Cursor::new(b"HTTP/1.1 200 OK\r\n\r\n".to_vec()))
+        .ok()
+        .unwrap();
+    assert_eq!(response.time_to_first_byte(), None);
+}
+
+#[test]
+fn test_header_merge_is_reported_when_enabled() {
+    let (mut client, events) = recording_client();
+    client.headers = HttpHeaders::minimal();
+    let mut request = client.request(HttpMethod::GET, "http://example.com/");
+    request
+        .headers
+        .insert("User-Agent".to_string(), "custom".to_string());
+
+    let (stream, _) = MockStream::new(b"HTTP/1.1 204 No Content\r\n\r\n");
+    client.send_on(stream, &request).unwrap();
+    assert!(!events
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|event| matches!(event, ClientEvent::HeadersMerged { .. })));
+
+    client.report_header_merge = true;
+    let (stream, _) = MockStream::new(b"HTTP/1.1 204 No Content\r\n\r\n");
+    client.send_on(stream, &request).unwrap();
+    let events = events.lock().unwrap();
+    let report = events
+        .iter()
+        .find_map(|event| match event {
+            ClientEvent::HeadersMerged { report, .. } => Some(report),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(report.overridden, ["User-Agent"]);
+    assert!(report.from_defaults.is_empty());
+}
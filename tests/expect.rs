@@ -0,0 +1,222 @@
+mod common;
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::time::{Duration, Instant};
+
+use clienter::{HttpClient, HttpMethod, HttpRequest, StatusCode};
+use common::{read_request_head, MockStream};
+
+fn upload(client: &HttpClient, uri: &str) -> HttpRequest {
+    let mut request = client.request(HttpMethod::PUT, uri);
+    request
+        .headers
+        .insert("Expect".to_string(), "100-continue".to_string());
+    request.set_body("payload");
+    request
+}
+
+#[test]
+fn test_body_sent_after_continue() {
+    let client = HttpClient::new();
+    let request = upload(&client, "http://example.com/upload");
+    let (stream, written) = MockStream::new(
+        b"HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 201 Created\r\nContent-Length: 2\r\n\r\nok",
+    );
+
+    let mut response = client.send_on(stream, &request).unwrap();
+    assert_eq!(response.status, StatusCode::Created201);
+    assert_eq!(response.body_as_string().unwrap(), "ok");
+
+    let written = String::from_utf8(written.lock().unwrap().clone()).unwrap();
+    assert!(written.contains("Expect: 100-continue\r\n"));
+    assert!(written.ends_with("\r\n\r\npayload"));
+}
+
+#[test]
+fn test_final_response_skips_body() {
+    let client = HttpClient::new();
+    let request = upload(&client, "http://example.com/upload");
+    let (stream, written) =
+        MockStream::new(b"HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\n\r\n");
+
+    let response = client.send_on(stream, &request).unwrap();
+    assert_eq!(response.status, StatusCode::Unauthorized401);
+
+    let written = String::from_utf8(written.lock().unwrap().clone()).unwrap();
+    assert!(written.ends_with("\r\n\r\n"));
+    assert!(!written.contains("payload"));
+}
+
+#[test]
+fn test_expectation_failed_retries_on_same_connection() {
+    let client = HttpClient::new();
+    let request = upload(&client, "http://example.com/upload");
+    let (stream, written) = MockStream::new(
+        b"HTTP/1.1 417 Expectation Failed\r\nContent-Length: 4\r\n\r\nnopeHTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n",
+    );
+
+    let response = client.send_on(stream, &request).unwrap();
+    assert_eq!(response.status, StatusCode::Ok200);
+
+    let written = String::from_utf8(written.lock().unwrap().clone()).unwrap();
+    let (first, retry) = written.split_once("\r\n\r\n").unwrap();
+    assert!(first.contains("Expect: 100-continue"));
+    assert!(!retry.contains("Expect"));
+    assert!(retry.starts_with("PUT /upload HTTP/1.1\r\n"));
+    assert!(retry.ends_with("\r\n\r\npayload"));
+}
+
+#[test]
+fn test_expectation_failed_with_close_reconnects() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let first = read_request_head(&mut stream);
+        stream
+            .write_all(b"HTTP/1.1 417 Expectation Failed\r\nConnection: close\r\nContent-Length: 0\r\n\r\n")
+            .unwrap();
+        drop(stream);
+
+        let (mut stream, _) = listener.accept().unwrap();
+        let retry = read_request_head(&mut stream);
+        let mut body = [0u8; 7];
+        stream.read_exact(&mut body).unwrap();
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+            .unwrap();
+        (first, retry, body)
+    });
+
+    let client = HttpClient::new();
+    let request = upload(&client, format!("http://{}/upload", addr).as_str());
+    let response = client.send(&request).unwrap();
+    assert_eq!(response.status, StatusCode::Ok200);
+
+    let (first, retry, body) = server.join().unwrap();
+    assert!(String::from_utf8(first)
+        .unwrap()
+        .contains("Expect: 100-continue"));
+    assert!(!String::from_utf8(retry).unwrap().contains("Expect"));
+    assert_eq!(&body, b"payload");
+}
+
+#[test]
+fn test_expectation_failed_with_close_among_options_is_not_retried_on_it() {
+    let client = HttpClient::new();
+    let request = upload(&client, "http://example.com/upload");
+    let (stream, written) = MockStream::new(
+        b"HTTP/1.1 417 Expectation Failed\r\nConnection: close, foo\r\nContent-Length: 0\r\n\r\n",
+    );
+
+    let response = client.send_on(stream, &request).unwrap();
+    assert_eq!(response.status, StatusCode::ExpectationFailed417);
+
+    let written = String::from_utf8(written.lock().unwrap().clone()).unwrap();
+    assert_eq!(written.matches("PUT /upload").count(), 1);
+    assert!(!written.contains("payload"));
+}
+
+#[test]
+fn test_expectation_failed_is_retried_at_most_once() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        read_request_head(&mut stream);
+        stream
+            .write_all(b"HTTP/1.1 417 Expectation Failed\r\nContent-Length: 0\r\n\r\n")
+            .unwrap();
+        read_request_head(&mut stream);
+        let mut body = [0u8; 7];
+        stream.read_exact(&mut body).unwrap();
+        stream
+            .write_all(b"HTTP/1.1 417 Expectation Failed\r\nConnection: close\r\nContent-Length: 0\r\n\r\n")
+            .unwrap();
+        drop(stream);
+
+        // Count any further connection attempt
+        listener.set_nonblocking(true).unwrap();
+        std::thread::sleep(Duration::from_millis(300));
+        listener.accept().is_ok()
+    });
+
+    let client = HttpClient::new();
+    let request = upload(&client, format!("http://{}/upload", addr).as_str());
+    let response = client.send(&request).unwrap();
+    assert_eq!(response.status, StatusCode::ExpectationFailed417);
+    assert!(!server.join().unwrap());
+}
+
+/// Starts a server that ignores `Expect`, reading the whole request before answering
+/// with `response`. The join handle yields the body that was received.
+fn serve_ignoring_expect(response: &'static [u8]) -> (String, std::thread::JoinHandle<Vec<u8>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        read_request_head(&mut stream);
+        let mut body = vec![0u8; 7];
+        stream.read_exact(&mut body).unwrap();
+        stream.write_all(response).unwrap();
+        body
+    });
+    (format!("http://{}/upload", addr), server)
+}
+
+#[test]
+fn test_body_sent_when_continue_never_comes() {
+    let (uri, server) = serve_ignoring_expect(b"HTTP/1.1 201 Created\r\nContent-Length: 0\r\n\r\n");
+    let mut client = HttpClient::new();
+    assert_eq!(client.continue_timeout, Some(Duration::from_secs(1)));
+    client.continue_timeout = Some(Duration::from_millis(200));
+
+    let start = Instant::now();
+    let request = upload(&client, uri.as_str());
+    let response = client.send(&request).unwrap();
+    assert_eq!(response.status, StatusCode::Created201);
+    assert!(start.elapsed() >= Duration::from_millis(200));
+    assert!(start.elapsed() < Duration::from_secs(2));
+    assert_eq!(server.join().unwrap(), b"payload");
+}
+
+#[test]
+fn test_late_continue_is_skipped() {
+    let (uri, server) = serve_ignoring_expect(
+        b"HTTP/1.1 100 Continue\r\n\r\nHTTP/1.1 201 Created\r\nContent-Length: 2\r\n\r\nok",
+    );
+    let mut client = HttpClient::new();
+    client.continue_timeout = Some(Duration::from_millis(200));
+
+    let request = upload(&client, uri.as_str());
+    let mut response = client.send(&request).unwrap();
+    assert_eq!(response.status, StatusCode::Created201);
+    assert_eq!(response.body_as_string().unwrap(), "ok");
+    assert_eq!(server.join().unwrap(), b"payload");
+}
+
+#[test]
+fn test_send_on_sends_body_when_continue_never_comes() {
+    let (uri, server) = serve_ignoring_expect(b"HTTP/1.1 201 Created\r\nContent-Length: 0\r\n\r\n");
+    let mut client = HttpClient::new();
+    client.continue_timeout = Some(Duration::from_millis(200));
+    let stream = TcpStream::connect(
+        uri.trim_start_matches("http://")
+            .trim_end_matches("/upload"),
+    )
+    .unwrap();
+    stream
+        .set_read_timeout(Some(Duration::from_secs(5)))
+        .unwrap();
+    let socket = stream.try_clone().unwrap();
+
+    let start = Instant::now();
+    let request = upload(&client, uri.as_str());
+    let response = client.send_on(stream, &request).unwrap();
+    assert_eq!(response.status, StatusCode::Created201);
+    assert!(start.elapsed() < Duration::from_secs(2));
+    assert_eq!(server.join().unwrap(), b"payload");
+    // The stream's own timeout applies again once the wait is over
+    assert_eq!(socket.read_timeout().unwrap(), Some(Duration::from_secs(5)));
+}
@@ -0,0 +1,401 @@
+mod common;
+
+use std::io::Write;
+use std::net::TcpListener;
+use std::ops::ControlFlow;
+
+use clienter::{HttpClient, HttpError, HttpMethod, HttpResponse, ResponseError};
+use common::{read_request_head, respond, serve_once, MockStream};
+
+#[test]
+fn test_chunked_body_is_decoded() {
+    let mut response = respond(
+        HttpMethod::GET,
+        b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nContent-Length: 99\r\n\r\n\
+          4\r\nWiki\r\n5\r\npedia\r\n0\r\n\r\n",
+    );
+    assert_eq!(response.body_as_string().unwrap(), "Wikipedia");
+}
+
+#[test]
+fn test_head_response_has_no_body() {
+    // The server advertises the length of the body a GET would return, but sends none
+    let (addr, server) = serve_once(b"HTTP/1.1 200 OK\r\nContent-Length: 1000\r\n\r\n");
+    let client = HttpClient::new();
+    let request = client.request(HttpMethod::HEAD, format!("http://{}/", addr).as_str());
+
+    let mut response = client.send(&request).unwrap();
+    assert_eq!(response.body().unwrap(), b"");
+    server.join().unwrap();
+}
+
+#[test]
+fn test_statuses_without_body() {
+    for raw in [
+        &b"HTTP/1.1 204 No Content\r\nConnection: keep-alive\r\n\r\n"[..],
+        b"HTTP/1.1 304 Not Modified\r\nContent-Length: 10\r\n\r\n",
+    ] {
+        let mut response = respond(HttpMethod::GET, raw);
+        assert_eq!(response.body().unwrap(), b"");
+    }
+}
+
+#[test]
+fn test_keep_alive_without_length_is_an_error() {
+    let mut response = respond(
+        HttpMethod::GET,
+        b"HTTP/1.1 200 OK\r\nConnection: keep-alive\r\n\r\nbody",
+    );
+    assert_eq!(response.body(), Err(ResponseError::UnframedBody));
+}
+
+#[test]
+fn test_keep_alive_among_connection_options_is_found() {
+    for raw in [
+        &b"HTTP/1.1 200 OK\r\nConnection: Keep-Alive, Upgrade\r\n\r\nbody"[..],
+        b"HTTP/1.1 200 OK\r\nConnection: Upgrade\r\nConnection: keep-alive\r\n\r\nbody",
+    ] {
+        let mut response = respond(HttpMethod::GET, raw);
+        assert_eq!(response.body(), Err(ResponseError::UnframedBody));
+    }
+}
+
+#[test]
+fn test_close_delimited_body_reads_to_eof() {
+    let mut response = respond(
+        HttpMethod::GET,
+        b"HTTP/1.1 200 OK\r\nConnection: close\r\n\r\nuntil the end",
+    );
+    assert_eq!(response.body_as_string().unwrap(), "until the end");
+
+    let mut response = respond(HttpMethod::GET, b"HTTP/1.1 200 OK\r\n\r\nno headers");
+    assert_eq!(response.body_as_string().unwrap(), "no headers");
+}
+
+#[test]
+fn test_lf_only_line_endings() {
+    let mut response = respond(
+        HttpMethod::GET,
+        b"HTTP/1.1 200 OK\nContent-Type: text/plain\nContent-Length: 5\n\nhello",
+    );
+    assert_eq!(response.headers.get("Content-Type").unwrap(), "text/plain");
+    assert_eq!(response.body_as_string().unwrap(), "hello");
+
+    let mut response = respond(
+        HttpMethod::GET,
+        b"HTTP/1.1 200 OK\nTransfer-Encoding: chunked\n\n5\nhello\n1;ext=1\n!\n0\nTrailer: x\n\n",
+    );
+    assert_eq!(response.body_as_string().unwrap(), "hello!");
+}
+
+#[test]
+fn test_mixed_line_endings() {
+    let mut response = respond(
+        HttpMethod::GET,
+        b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\n\r\n5\r\nhello\n0\n\r\n",
+    );
+    assert_eq!(response.body_as_string().unwrap(), "hello");
+}
+
+#[test]
+fn test_copy_to_streams_framed_bodies() {
+    let mut response = respond(
+        HttpMethod::GET,
+        b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nWiki\r\n5\r\npedia\r\n0\r\n\r\n",
+    );
+    let mut out = Vec::new();
+    assert_eq!(response.copy_to(&mut out).unwrap(), 9);
+    assert_eq!(out, b"Wikipedia");
+
+    let mut response = respond(
+        HttpMethod::GET,
+        b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello and more",
+    );
+    let mut out = Vec::new();
+    assert_eq!(response.copy_to(&mut out).unwrap(), 5);
+    assert_eq!(out, b"hello");
+}
+
+#[test]
+fn test_copy_to_unframed_body_is_an_error() {
+    let mut response = respond(
+        HttpMethod::GET,
+        b"HTTP/1.1 200 OK\r\nConnection: keep-alive\r\n\r\nbody",
+    );
+    let err = response.copy_to(&mut Vec::new()).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_for_each_chunk_sees_whole_body() {
+    let mut response = respond(
+        HttpMethod::GET,
+        b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nWiki\r\n5\r\npedia\r\n0\r\n\r\n",
+    );
+    let mut out = Vec::new();
+    response
+        .for_each_chunk(|chunk| {
+            assert!(!chunk.is_empty());
+            out.extend_from_slice(chunk);
+            ControlFlow::Continue(())
+        })
+        .unwrap();
+    assert_eq!(out, b"Wikipedia");
+}
+
+#[test]
+fn test_for_each_chunk_stops_on_break() {
+    let mut response = respond(
+        HttpMethod::GET,
+        b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nWiki\r\n5\r\npedia\r\n0\r\n\r\n",
+    );
+    let mut calls = 0;
+    response
+        .for_each_chunk(|_| {
+            calls += 1;
+            ControlFlow::Break(())
+        })
+        .unwrap();
+    assert_eq!(calls, 1);
+
+    let mut response = respond(
+        HttpMethod::GET,
+        b"HTTP/1.1 200 OK\r\nConnection: keep-alive\r\n\r\nbody",
+    );
+    assert!(matches!(
+        response.for_each_chunk(|_| ControlFlow::Continue(())),
+        Err(ResponseError::UnframedBody)
+    ));
+}
+
+#[test]
+fn test_conflicting_content_lengths_are_rejected() {
+    let client = HttpClient::new();
+    let request = client.request(HttpMethod::GET, "http://example.com/");
+    for raw in [
+        &b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\nContent-Length: 50\r\n\r\nhello"[..],
+        b"HTTP/1.1 200 OK\r\nContent-Length: 5, 50\r\n\r\nhello",
+    ] {
+        let (stream, _) = MockStream::new(raw);
+        let err = client.send_on(stream, &request).err().unwrap();
+        assert!(matches!(
+            err,
+            HttpError::InvalidResponse(ResponseError::InvalidHeader(_))
+        ));
+    }
+}
+
+#[test]
+fn test_invalid_content_lengths_are_rejected() {
+    let client = HttpClient::new();
+    let request = client.request(HttpMethod::GET, "http://example.com/");
+    for raw in [
+        &b"HTTP/1.1 200 OK\r\nContent-Length: abc\r\n\r\nhello"[..],
+        b"HTTP/1.1 200 OK\r\nContent-Length: 5, abc\r\n\r\nhello",
+        b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\nContent-Length: abc\r\n\r\nhello",
+        b"HTTP/1.1 200 OK\r\nContent-Length: +5\r\n\r\nhello",
+        b"HTTP/1.1 200 OK\r\nContent-Length: \r\n\r\nhello",
+    ] {
+        let (stream, _) = MockStream::new(raw);
+        let err = client.send_on(stream, &request).err().unwrap();
+        assert!(
+            matches!(
+                err,
+                HttpError::InvalidResponse(ResponseError::InvalidHeader(_))
+            ),
+            "{}",
+            String::from_utf8_lossy(raw)
+        );
+    }
+}
+
+#[test]
+fn test_repeated_equal_content_lengths_are_accepted() {
+    let mut response = respond(
+        HttpMethod::GET,
+        b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\nContent-Length: 5, 5\r\n\r\nhello and more",
+    );
+    assert_eq!(response.body_as_string().unwrap(), "hello");
+}
+
+#[test]
+fn test_chunked_wins_over_content_length_across_headers() {
+    let mut response = respond(
+        HttpMethod::GET,
+        b"HTTP/1.1 200 OK\r\nContent-Length: 1\r\nContent-Length: 2\r\n\
+          Transfer-Encoding: gzip\r\nTransfer-Encoding: chunked\r\n\r\n\
+          3\r\nabc\r\n0\r\n\r\n",
+    );
+    assert_eq!(response.body().unwrap(), b"abc");
+}
+
+#[test]
+fn test_request_connection_close_reads_to_eof() {
+    let client = HttpClient::new();
+    let mut request = client.request(HttpMethod::GET, "http://example.com/");
+    request
+        .headers
+        .insert("Connection".to_string(), "close".to_string());
+
+    let (stream, written) =
+        MockStream::new(b"HTTP/1.1 200 OK\r\nConnection: keep-alive\r\n\r\nuntil the end");
+    let mut response = client.send_on(stream, &request).unwrap();
+    assert_eq!(response.body_as_string().unwrap(), "until the end");
+
+    let written = String::from_utf8(written.lock().unwrap().clone()).unwrap();
+    assert!(written.contains("Connection: close\r\n"));
+    assert!(!written.contains("keep-alive"));
+}
+
+#[test]
+fn test_bytes_after_body_are_preserved() {
+    let raw = b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nfirst\
+                HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n6\r\nsecond\r\n0\r\n\r\n\
+                HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nthird";
+    let mut response = respond(HttpMethod::GET, raw);
+    assert_eq!(response.body_as_string().unwrap(), "first");
+
+    let mut second = HttpResponse::build(response.into_stream()).ok().unwrap();
+    assert_eq!(second.body_as_string().unwrap(), "second");
+
+    let mut third = HttpResponse::build(second.into_stream()).ok().unwrap();
+    assert_eq!(third.body_as_string().unwrap(), "third");
+}
+
+#[test]
+fn test_empty_bodies_leave_connection_at_next_response() {
+    let raw = b"HTTP/1.1 204 No Content\r\n\r\n\
+                HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n\
+                HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\nnext";
+    let mut response = respond(HttpMethod::GET, raw);
+    assert_eq!(response.body().unwrap(), b"");
+
+    let mut second = HttpResponse::build(response.into_stream()).ok().unwrap();
+    assert_eq!(second.body().unwrap(), b"");
+    assert_eq!(second.body().unwrap(), b"");
+
+    let mut third = HttpResponse::build(second.into_stream()).ok().unwrap();
+    assert_eq!(third.body_as_string().unwrap(), "next");
+}
+
+#[test]
+fn test_head_and_body_in_a_single_write() {
+    // serve_once writes the whole response at once, so the first read takes in the
+    // body along with the head
+    let (addr, server) = serve_once(
+        b"HTTP/1.1 200 OK\r\nContent-Length: 11\r\nConnection: close\r\n\r\nhello world",
+    );
+    let client = HttpClient::new();
+    let request = client.request(HttpMethod::GET, format!("http://{}/", addr).as_str());
+
+    let mut response = client.send(&request).unwrap();
+    assert_eq!(response.body_as_string().unwrap(), "hello world");
+    server.join().unwrap();
+}
+
+#[test]
+fn test_single_write_body_larger_than_read_buffer() {
+    let body = "0123456789".repeat(2000);
+    let mut raw = format!(
+        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    )
+    .into_bytes();
+    raw.extend_from_slice(body.as_bytes());
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        read_request_head(&mut stream);
+        stream.write_all(&raw).unwrap();
+    });
+    let client = HttpClient::new();
+    let request = client.request(HttpMethod::GET, format!("http://{}/", addr).as_str());
+
+    let mut response = client.send(&request).unwrap();
+    assert_eq!(response.body_as_string().unwrap(), body);
+    server.join().unwrap();
+}
+
+#[test]
+fn test_force_read_body_ignores_the_no_body_rule() {
+    let raw = b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\nbody";
+    assert_eq!(respond(HttpMethod::HEAD, raw).body().unwrap(), b"");
+    let mut response = respond(HttpMethod::HEAD, raw);
+    assert_eq!(response.force_read_body().unwrap(), b"body");
+
+    let mut response = respond(
+        HttpMethod::GET,
+        b"HTTP/1.1 304 Not Modified\r\nTransfer-Encoding: chunked\r\n\r\n3\r\nabc\r\n0\r\n\r\n",
+    );
+    assert_eq!(response.force_read_body().unwrap(), b"abc");
+
+    // Without a length, and despite keep-alive, the rest of the stream is read
+    let mut response = respond(
+        HttpMethod::GET,
+        b"HTTP/1.1 204 No Content\r\nConnection: keep-alive\r\n\r\nstray bytes",
+    );
+    assert_eq!(response.force_read_body().unwrap(), b"stray bytes");
+}
+
+#[test]
+fn test_force_read_body_reads_normal_bodies_as_usual() {
+    let mut response = respond(
+        HttpMethod::GET,
+        b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nfirstHTTP/1.1 200 OK\r\n",
+    );
+    assert_eq!(response.force_read_body().unwrap(), b"first");
+}
+
+#[test]
+fn test_has_body() {
+    let cases: [(HttpMethod, &[u8], bool); 8] = [
+        (
+            HttpMethod::GET,
+            b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello",
+            true,
+        ),
+        (
+            HttpMethod::GET,
+            b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n",
+            false,
+        ),
+        (
+            HttpMethod::HEAD,
+            b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\n",
+            false,
+        ),
+        (HttpMethod::GET, b"HTTP/1.1 204 No Content\r\n\r\n", false),
+        (
+            HttpMethod::GET,
+            b"HTTP/1.1 304 Not Modified\r\nContent-Length: 5\r\n\r\n",
+            false,
+        ),
+        (
+            HttpMethod::GET,
+            b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n0\r\n\r\n",
+            true,
+        ),
+        (
+            HttpMethod::GET,
+            b"HTTP/1.1 200 OK\r\nConnection: close\r\n\r\nuntil close",
+            true,
+        ),
+        (
+            HttpMethod::GET,
+            b"HTTP/1.1 200 OK\r\nConnection: keep-alive\r\n\r\n",
+            false,
+        ),
+    ];
+
+    for (method, raw, has_body) in cases {
+        let response = respond(method, raw);
+        assert_eq!(
+            response.has_body(),
+            has_body,
+            "{}",
+            String::from_utf8_lossy(raw)
+        );
+    }
+}
@@ -0,0 +1,232 @@
+mod common;
+
+use clienter::{Encoding, HttpClient, HttpHeaders, HttpMethod};
+use common::{serve_once, written, MockStream, EMPTY_RESPONSE};
+
+#[test]
+fn test_request_headers_override_defaults() {
+    let mut client = HttpClient::new();
+    client.headers.set_user_agent("Default/1.0".to_string());
+
+    let mut request = client.request(HttpMethod::GET, "http://example.com/");
+    request.headers.set_user_agent("Custom/2.0".to_string());
+
+    let head = written(&client, &request);
+    assert!(head.contains("User-Agent: Custom/2.0\r\n"));
+    assert!(!head.contains("Default/1.0"));
+}
+
+#[test]
+fn test_removed_request_header_suppresses_default() {
+    let client = HttpClient::new();
+    let mut request = client.request(HttpMethod::GET, "http://example.com/");
+    request.headers.remove("User-Agent");
+
+    let head = written(&client, &request);
+    assert!(
+        !head.to_ascii_lowercase().contains("user-agent"),
+        "{}",
+        head
+    );
+    assert!(head.contains("Accept: */*\r\n"));
+
+    // Adding the header back sends the new value instead
+    request
+        .headers
+        .insert("user-agent".to_string(), "Other/1.0".to_string());
+    let head = written(&client, &request);
+    assert!(head.contains("user-agent: Other/1.0\r\n"));
+    assert!(!head.contains("Clienter"));
+}
+
+#[test]
+fn test_merged_defaults_yield_to_request_headers() {
+    let mut client = HttpClient::new();
+    client.set_default_headers(HttpHeaders::minimal());
+    let mut extra = HttpHeaders::new();
+    extra.insert("X-Api-Version".to_string(), "3".to_string());
+    extra.insert("X-Tenant".to_string(), "default".to_string());
+    client.merge_default_headers(extra);
+
+    let mut request = client.request(HttpMethod::GET, "http://example.com/");
+    request
+        .headers
+        .insert("X-Tenant".to_string(), "acme".to_string());
+    assert_eq!(
+        written(&client, &request),
+        "GET / HTTP/1.1\r\nUser-Agent: Clienter/1.0 (Rust)\r\nX-Api-Version: 3\r\n\
+         X-Tenant: acme\r\nHost: example.com\r\n\r\n"
+    );
+}
+
+#[test]
+fn test_minimal_preset_on_the_wire() {
+    let mut client = HttpClient::new();
+    client.headers = HttpHeaders::minimal();
+
+    let request = client.request(HttpMethod::GET, "http://example.com/items");
+    assert_eq!(
+        written(&client, &request),
+        "GET /items HTTP/1.1\r\nUser-Agent: Clienter/1.0 (Rust)\r\nHost: example.com\r\n\r\n"
+    );
+}
+
+#[test]
+fn test_default_headers_kept_separate() {
+    let mut client = HttpClient::new();
+    client
+        .headers
+        .insert("X-Default".to_string(), "yes".to_string());
+
+    let mut request = client.request(HttpMethod::GET, "http://example.com/");
+    request
+        .headers
+        .insert("X-Request".to_string(), "yes".to_string());
+
+    assert!(client.default_headers().get("X-Request").is_none());
+    assert!(request.headers.get("X-Default").is_none());
+
+    let head = written(&client, &request);
+    assert!(head.contains("X-Default: yes\r\n"));
+    assert!(head.contains("X-Request: yes\r\n"));
+}
+
+#[test]
+fn test_host_derived_from_uri() {
+    let client = HttpClient::new();
+    let request = client.request(HttpMethod::GET, "http://example.com:8080/");
+    let head = written(&client, &request);
+    assert!(head.contains("Host: example.com:8080\r\n"));
+}
+
+#[test]
+fn test_host_override_keeps_connect_target() {
+    let (addr, server) = serve_once(EMPTY_RESPONSE);
+    let client = HttpClient::new();
+    let request = client
+        .request(HttpMethod::GET, format!("http://{}/", addr))
+        .host_override("api.example.com");
+
+    client.send(&request).unwrap();
+
+    let received = String::from_utf8(server.join().unwrap()).unwrap();
+    assert!(received.contains("Host: api.example.com\r\n"));
+    assert_eq!(received.matches("Host:").count(), 1);
+}
+
+#[test]
+fn test_set_accept_encodings_is_sent() {
+    let mut client = HttpClient::new();
+    client.set_accept_encodings(&[(Encoding::Brotli, 1.0), (Encoding::Gzip, 0.8)]);
+
+    let request = client.request(HttpMethod::GET, "http://example.com/");
+    assert!(written(&client, &request).contains("\r\nAccept-Encoding: br, gzip;q=0.8\r\n"));
+}
+
+#[test]
+fn test_content_encoding() {
+    let client = HttpClient::new();
+    let request = client.request(HttpMethod::GET, "http://example.com/");
+    let cases: [(&[u8], Option<Encoding>); 4] = [
+        (b"HTTP/1.1 200 OK\r\n\r\n", Some(Encoding::Identity)),
+        (
+            b"HTTP/1.1 200 OK\r\nContent-Encoding: GZIP\r\n\r\n",
+            Some(Encoding::Gzip),
+        ),
+        (b"HTTP/1.1 200 OK\r\nContent-Encoding: zstd\r\n\r\n", None),
+        (
+            b"HTTP/1.1 200 OK\r\nContent-Encoding: gzip, br\r\n\r\n",
+            None,
+        ),
+    ];
+
+    for (raw, expected) in cases {
+        let (stream, _) = MockStream::new(raw);
+        let response = client.send_on(stream, &request).unwrap();
+        assert_eq!(response.content_encoding(), expected);
+    }
+}
+
+#[test]
+fn test_allowed_methods() {
+    let client = HttpClient::new();
+    let request = client.request(HttpMethod::OPTIONS, "http://example.com/");
+
+    let (stream, _) = MockStream::new(
+        b"HTTP/1.1 204 No Content\r\nAllow: GET, head,PROPFIND\r\nAllow: DELETE\r\n\r\n",
+    );
+    let response = client.send_on(stream, &request).unwrap();
+    assert_eq!(
+        response.allowed_methods(),
+        vec![
+            HttpMethod::GET,
+            HttpMethod::HEAD,
+            HttpMethod::Other("PROPFIND".to_string()),
+            HttpMethod::DELETE,
+        ]
+    );
+
+    let (stream, _) = MockStream::new(
+        b"HTTP/1.1 204 No Content\r\nAccess-Control-Allow-Methods: POST, PUT\r\n\r\n",
+    );
+    let response = client.send_on(stream, &request).unwrap();
+    assert_eq!(
+        response.allowed_methods(),
+        vec![HttpMethod::POST, HttpMethod::PUT]
+    );
+
+    let (stream, _) = MockStream::new(EMPTY_RESPONSE);
+    let response = client.send_on(stream, &request).unwrap();
+    assert!(response.allowed_methods().is_empty());
+}
+
+#[test]
+fn test_header_block_ends_with_single_blank_line() {
+    let mut client = HttpClient::new();
+    client.headers = HttpHeaders::new();
+
+    let request = client.request(HttpMethod::GET, "http://example.com/");
+    assert_eq!(
+        written(&client, &request),
+        "GET / HTTP/1.1\r\nHost: example.com\r\n\r\n"
+    );
+
+    let mut request = client.request(HttpMethod::POST, "http://example.com/items");
+    request.set_body("abc");
+    assert_eq!(
+        written(&client, &request),
+        "POST /items HTTP/1.1\r\nHost: example.com\r\nContent-Length: 3\r\n\r\nabc"
+    );
+}
+
+#[test]
+fn test_auth_challenges() {
+    let client = HttpClient::new();
+    let request = client.request(HttpMethod::GET, "http://example.com/");
+    let (stream, _) = MockStream::new(
+        b"HTTP/1.1 401 Unauthorized\r\n\
+          WWW-Authenticate: Digest realm=\"api, v2\", qop=\"auth,auth-int\", nonce=\"n1\"\r\n\
+          WWW-Authenticate: Basic realm=\"api\"\r\n\
+          Content-Length: 0\r\n\r\n",
+    );
+    let response = client.send_on(stream, &request).unwrap();
+
+    assert_eq!(
+        response
+            .header("www-authenticate")
+            .unwrap()
+            .split(' ')
+            .next(),
+        Some("Digest")
+    );
+    assert_eq!(response.header("content-length"), Some("0"));
+    assert_eq!(response.header("Location"), None);
+
+    let challenges = response.auth_challenges();
+    assert_eq!(challenges.len(), 2);
+    assert_eq!(challenges[0].scheme, "Digest");
+    assert_eq!(challenges[0].params["realm"], "api, v2");
+    assert_eq!(challenges[0].params["qop"], "auth,auth-int");
+    assert_eq!(challenges[1].scheme, "Basic");
+    assert_eq!(challenges[1].params["realm"], "api");
+}
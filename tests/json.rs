@@ -0,0 +1,61 @@
+#![cfg(feature = "json")]
+
+mod common;
+
+use clienter::{HttpMethod, ResponseError, StatusCode};
+use common::respond;
+use serde::Deserialize;
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct ApiError {
+    code: String,
+    retry: bool,
+}
+
+#[test]
+fn test_error_body_json() {
+    let body = br#"{"code": "rate_limited", "retry": true}"#;
+    let raw = [
+        format!(
+            "HTTP/1.1 429 Too Many Requests\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+            body.len()
+        )
+        .as_bytes(),
+        body,
+    ]
+    .concat();
+    let mut response = respond(HttpMethod::GET, &raw);
+    let error: ApiError = response.error_body_json().unwrap();
+    assert_eq!(
+        error,
+        ApiError {
+            code: "rate_limited".to_string(),
+            retry: true
+        }
+    );
+}
+
+#[test]
+fn test_error_body_json_on_success_leaves_body() {
+    let mut response = respond(
+        HttpMethod::GET,
+        b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n{}",
+    );
+    assert_eq!(
+        response.error_body_json::<ApiError>().unwrap_err(),
+        ResponseError::UnexpectedStatus(StatusCode::Ok200)
+    );
+    assert_eq!(response.body().unwrap(), b"{}");
+}
+
+#[test]
+fn test_error_body_json_invalid() {
+    let mut response = respond(
+        HttpMethod::GET,
+        b"HTTP/1.1 500 Internal Server Error\r\nContent-Length: 4\r\n\r\noops",
+    );
+    assert!(matches!(
+        response.error_body_json::<ApiError>(),
+        Err(ResponseError::InvalidJson(_))
+    ));
+}
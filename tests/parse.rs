@@ -0,0 +1,57 @@
+use clienter::{HttpHeaders, HttpResponse, ResponseError, StatusCode};
+
+#[test]
+fn test_parse_pipelined_responses() {
+    let bytes = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n4\r\nWiki\r\n5\r\npedia\r\n0\r\n\r\n\
+                  HTTP/1.1 404 Not Found\r\nContent-Length: 4\r\n\r\nnopeHTTP/1.1 304 Not Modified\r\n\r\n";
+
+    let (mut first, rest) = HttpResponse::parse(bytes).unwrap();
+    assert_eq!(first.status, StatusCode::Ok200);
+    assert_eq!(first.body_as_string().unwrap(), "Wikipedia");
+
+    let (mut second, rest) = HttpResponse::parse(rest).unwrap();
+    assert_eq!(second.status, StatusCode::NotFound404);
+    assert_eq!(second.body().unwrap(), b"nope");
+
+    let (mut third, rest) = HttpResponse::parse(rest).unwrap();
+    assert_eq!(third.status, StatusCode::NotModified304);
+    assert_eq!(third.body().unwrap(), b"");
+    assert!(rest.is_empty());
+}
+
+#[test]
+fn test_parse_unframed_body_runs_to_end() {
+    let bytes = b"HTTP/1.1 200 OK\r\nConnection: keep-alive\r\n\r\nall of this";
+    let (mut response, rest) = HttpResponse::parse(bytes).unwrap();
+    assert_eq!(response.body().unwrap(), b"all of this");
+    assert!(rest.is_empty());
+}
+
+#[test]
+fn test_parse_incomplete_response() {
+    assert!(matches!(
+        HttpResponse::parse(b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nshort"),
+        Err(ResponseError::InvalidBody)
+    ));
+    assert!(HttpResponse::parse(b"").is_err());
+}
+
+#[test]
+fn test_from_parts() {
+    let mut headers = HttpHeaders::new();
+    headers.insert("Retry-After".to_string(), "5".to_string());
+    let mut response = HttpResponse::from_parts(
+        StatusCode::ServiceUnavailable503,
+        headers,
+        b"down for maintenance".to_vec(),
+    );
+
+    assert!(!response.status.is_success());
+    assert_eq!(response.header("Retry-After"), Some("5"));
+    assert_eq!(response.body_as_string().unwrap(), "down for maintenance");
+    assert_eq!(response.time_to_first_byte(), None);
+
+    let mut empty =
+        HttpResponse::from_parts(StatusCode::NoContent204, HttpHeaders::new(), Vec::new());
+    assert_eq!(empty.body().unwrap(), b"");
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use clienter::{HttpClient, HttpMethod, PoolStats};
+use common::serve_once;
+
+const RESPONSE: &[u8] = b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok";
+
+#[test]
+fn test_pool_stats_track_open_connections() {
+    let client = HttpClient::new();
+    let clone = client.clone();
+    assert_eq!(client.pool_stats(), PoolStats::default());
+
+    let (first_addr, first) = serve_once(RESPONSE);
+    let (second_addr, second) = serve_once(RESPONSE);
+    let first_request = client.request(HttpMethod::GET, format!("http://{}/", first_addr).as_str());
+    let second_request =
+        client.request(HttpMethod::GET, format!("http://{}/", second_addr).as_str());
+
+    let response = client.send(&first_request).unwrap();
+    let _other = clone.send(&second_request).unwrap();
+    first.join().unwrap();
+    second.join().unwrap();
+
+    let stats = client.pool_stats();
+    assert_eq!(stats.created, 2);
+    assert_eq!(stats.in_use, 2);
+    assert_eq!(stats.idle, 0);
+    assert_eq!(stats.reused, 0);
+
+    drop(response);
+    assert_eq!(clone.pool_stats().in_use, 1);
+}
+
+#[test]
+fn test_pool_stats_release_batched_connections() {
+    let client = HttpClient::new();
+    let (addr, server) = serve_once(RESPONSE);
+    let request = client.request(HttpMethod::GET, format!("http://{}/", addr).as_str());
+
+    let results = client.send_batch(vec![request], 1);
+    server.join().unwrap();
+
+    assert!(results[0].is_ok());
+    assert_eq!(client.pool_stats().created, 1);
+    assert_eq!(client.pool_stats().in_use, 0);
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use clienter::{HttpClient, HttpMethod};
+use common::written;
+
+fn request_line(client: &HttpClient, uri: &str) -> String {
+    let request = client.request(HttpMethod::GET, uri);
+    written(client, &request)
+        .lines()
+        .next()
+        .unwrap()
+        .to_string()
+}
+
+#[test]
+fn test_default_query_params_are_appended() {
+    let mut client = HttpClient::new();
+    client.default_query_param("api_key", "a&b");
+    client.default_query_param("v", "2");
+
+    assert_eq!(
+        request_line(&client, "http://example.com/items"),
+        "GET /items?api_key=a%26b&v=2 HTTP/1.1"
+    );
+    assert_eq!(
+        request_line(&client, "http://example.com/items?page=3&sort=name"),
+        "GET /items?page=3&sort=name&api_key=a%26b&v=2 HTTP/1.1"
+    );
+}
+
+#[test]
+fn test_request_query_params_take_precedence() {
+    let mut client = HttpClient::new();
+    client.default_query_param("api_key", "default");
+    client.default_query_param("lang", "en");
+
+    assert_eq!(
+        request_line(&client, "http://example.com/?api_key=mine"),
+        "GET /?api_key=mine&lang=en HTTP/1.1"
+    );
+}
+
+#[test]
+fn test_no_default_query_leaves_request_line() {
+    let client = HttpClient::new();
+    assert_eq!(
+        request_line(&client, "http://example.com/a?b"),
+        "GET /a?b HTTP/1.1"
+    );
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use std::io::Cursor;
+
+use clienter::{HttpClient, HttpMethod, HttpResponse, StatusCode};
+use common::MockStream;
+
+fn content_range(value: &str) -> Option<(u64, u64, Option<u64>)> {
+    let raw = format!(
+        "HTTP/1.1 206 Partial Content\r\nContent-Range: {}\r\nContent-Length: 0\r\n\r\n",
+        value
+    );
+    let response = HttpResponse::build(Cursor::new(raw.into_bytes()))
+        .ok()
+        .unwrap();
+    response.content_range()
+}
+
+#[test]
+fn test_range_request_reads_partial_content() {
+    let (stream, written) = MockStream::new(
+        b"HTTP/1.1 206 Partial Content\r\nContent-Range: bytes 4-8/20\r\nContent-Length: 5\r\n\r\npedia",
+    );
+    let client = HttpClient::new();
+    let request = client
+        .request(HttpMethod::GET, "http://example.com/file")
+        .range(4, Some(8));
+
+    let mut response = client.send_on(stream, &request).unwrap();
+    assert_eq!(response.status, StatusCode::PartialContent206);
+    assert!(response.status.is_success());
+    assert_eq!(response.content_range(), Some((4, 8, Some(20))));
+    assert_eq!(response.body_as_string().unwrap(), "pedia");
+
+    let written = String::from_utf8(written.lock().unwrap().clone()).unwrap();
+    assert!(written.contains("\r\nRange: bytes=4-8\r\n"), "{}", written);
+}
+
+#[test]
+fn test_open_ended_range() {
+    let client = HttpClient::new();
+    let request = client
+        .request(HttpMethod::GET, "http://example.com/file")
+        .range(500, None);
+    assert_eq!(request.headers.get_str("Range"), Some("bytes=500-"));
+}
+
+#[test]
+fn test_content_range_forms() {
+    assert_eq!(content_range("bytes 0-0/1"), Some((0, 0, Some(1))));
+    assert_eq!(content_range("bytes 500-999/*"), Some((500, 999, None)));
+    assert_eq!(content_range("BYTES 1-2/3"), Some((1, 2, Some(3))));
+    assert_eq!(content_range("bytes */1234"), None);
+    assert_eq!(content_range("bytes 9-1/10"), None);
+    assert_eq!(content_range("items 0-9/10"), None);
+    assert_eq!(content_range("bytes 0-9"), None);
+
+    let response = HttpResponse::build(Cursor::new(b"HTTP/1.1 200 OK\r\n\r\n".to_vec()))
+        .ok()
+        .unwrap();
+    assert_eq!(response.content_range(), None);
+}
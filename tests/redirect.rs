@@ -0,0 +1,120 @@
+mod common;
+
+use clienter::{HttpClient, HttpError, HttpMethod, HttpRequest, HttpResponse, StatusCode};
+use common::{serve_once, MockStream};
+
+/// Sends `request` on a mock stream that answers with `raw`.
+fn redirect(client: &HttpClient, request: &HttpRequest, raw: String) -> HttpResponse {
+    let (stream, _) = MockStream::new(raw.as_bytes());
+    client.send_on(stream, request).unwrap()
+}
+
+#[test]
+fn test_see_other_turns_post_into_get() {
+    let (addr, server) = serve_once(b"HTTP/1.1 200 OK\r\nContent-Length: 4\r\n\r\ndone");
+    let client = HttpClient::new();
+    let mut request = client
+        .request(HttpMethod::POST, format!("http://{}/form", addr).as_str())
+        .text("name=value");
+    request
+        .headers
+        .insert("Authorization".to_string(), "Bearer token".to_string());
+
+    let response = redirect(
+        &client,
+        &request,
+        "HTTP/1.1 303 See Other\r\nLocation: /done?id=1\r\nContent-Length: 0\r\n\r\n".to_string(),
+    );
+    let mut response = client.follow(&response, &request).ok().unwrap();
+    assert_eq!(response.body_as_string().unwrap(), "done");
+
+    let head = String::from_utf8(server.join().unwrap()).unwrap();
+    assert!(head.starts_with("GET /done?id=1 HTTP/1.1\r\n"));
+    assert!(!head.contains("Content-Type"));
+    assert!(!head.contains("Content-Length"));
+    // The redirect stayed on the same origin
+    assert!(head.contains("Authorization: Bearer token\r\n"));
+}
+
+#[test]
+fn test_temporary_redirect_keeps_method_and_body() {
+    let (addr, server) = serve_once(b"HTTP/1.1 201 Created\r\nContent-Length: 0\r\n\r\n");
+    let client = HttpClient::new();
+    let request = client
+        .request(
+            HttpMethod::POST,
+            format!("http://{}/v1/items", addr).as_str(),
+        )
+        .text("item");
+
+    let response = redirect(
+        &client,
+        &request,
+        "HTTP/1.1 307 Temporary Redirect\r\nLocation: ../v2/items\r\n\r\n".to_string(),
+    );
+    let response = client.follow(&response, &request).ok().unwrap();
+    assert_eq!(response.status, StatusCode::Created201);
+
+    let head = String::from_utf8(server.join().unwrap()).unwrap();
+    assert!(head.starts_with("POST /v2/items HTTP/1.1\r\n"));
+    assert!(head.contains("Content-Length: 4\r\n"));
+}
+
+#[test]
+fn test_credentials_are_dropped_across_origins() {
+    let (addr, server) = serve_once(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n");
+    let mut client = HttpClient::new();
+    client
+        .headers
+        .insert("Cookie".to_string(), "session=abc".to_string());
+    let mut request = client.request(HttpMethod::GET, "http://example.com/start");
+    request
+        .headers
+        .insert("Authorization".to_string(), "Bearer token".to_string());
+
+    let response = redirect(
+        &client,
+        &request,
+        format!(
+            "HTTP/1.1 302 Found\r\nLocation: http://{}/elsewhere\r\n\r\n",
+            addr
+        ),
+    );
+    let response = client.follow(&response, &request).ok().unwrap();
+    assert_eq!(
+        response.redirect_history,
+        ["http://example.com/start".parse().unwrap()]
+    );
+    assert!(response.changed_origin());
+
+    let head = String::from_utf8(server.join().unwrap()).unwrap();
+    assert!(head.starts_with("GET /elsewhere HTTP/1.1\r\n"));
+    assert!(!head.contains("Authorization"));
+    assert!(!head.contains("Cookie"));
+}
+
+#[test]
+fn test_non_redirects_are_rejected() {
+    let client = HttpClient::new();
+    let request = client.request(HttpMethod::GET, "http://example.com/");
+
+    let response = redirect(
+        &client,
+        &request,
+        "HTTP/1.1 200 OK\r\nLocation: /other\r\nContent-Length: 0\r\n\r\n".to_string(),
+    );
+    assert_eq!(
+        client.follow(&response, &request).err().unwrap(),
+        HttpError::NotRedirect(StatusCode::Ok200)
+    );
+
+    let response = redirect(
+        &client,
+        &request,
+        "HTTP/1.1 302 Found\r\nContent-Length: 0\r\n\r\n".to_string(),
+    );
+    assert_eq!(
+        client.follow(&response, &request).err().unwrap(),
+        HttpError::NotRedirect(StatusCode::Found302)
+    );
+}
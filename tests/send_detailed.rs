@@ -0,0 +1,80 @@
+mod common;
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+
+use clienter::{HttpClient, HttpMethod, StatusCode};
+use common::{read_request_head, serve_once};
+
+#[test]
+fn test_sent_request_matches_the_wire() {
+    let (addr, server) = serve_once(b"HTTP/1.1 204 No Content\r\n\r\n");
+    let mut client = HttpClient::new();
+    client.default_query_param("api_key", "k");
+    client
+        .headers
+        .insert("X-Client".to_string(), "clienter".to_string());
+    let request = client
+        .request(
+            HttpMethod::POST,
+            format!("http://{}/items?page=2", addr).as_str(),
+        )
+        .text("hello");
+
+    let (sent, response) = client.send_detailed(&request).ok().unwrap();
+    assert_eq!(response.status.as_u16(), 204);
+    assert_eq!(sent.uri.query.as_deref(), Some("page=2&api_key=k"));
+    assert_eq!(sent.headers.get_str("X-Client"), Some("clienter"));
+    assert_eq!(
+        sent.headers.get_str("Host"),
+        Some(addr.to_string().as_str())
+    );
+    assert_eq!(sent.headers.get_str("Content-Length"), Some("5"));
+    // The caller's request is left untouched
+    assert_eq!(request.uri.query.as_deref(), Some("page=2"));
+    assert!(request.headers.get("X-Client").is_none());
+
+    let head = String::from_utf8(server.join().unwrap()).unwrap();
+    assert!(head.starts_with(&format!("{}\r\n", sent.get_request_line())));
+    for (name, value) in &sent.headers {
+        assert!(head.contains(&format!("{}: {}\r\n", name, value)));
+    }
+}
+
+#[test]
+fn test_sent_request_lacks_expect_after_417_resend() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let first = String::from_utf8(read_request_head(&mut stream)).unwrap();
+        stream
+            .write_all(b"HTTP/1.1 417 Expectation Failed\r\nContent-Length: 0\r\n\r\n")
+            .unwrap();
+        let retry = String::from_utf8(read_request_head(&mut stream)).unwrap();
+        let mut body = [0u8; 7];
+        stream.read_exact(&mut body).unwrap();
+        stream
+            .write_all(b"HTTP/1.1 201 Created\r\nContent-Length: 0\r\n\r\n")
+            .unwrap();
+        (first, retry)
+    });
+
+    let client = HttpClient::new();
+    let mut request = client
+        .request(HttpMethod::PUT, format!("http://{}/upload", addr).as_str())
+        .text("payload");
+    request
+        .headers
+        .insert("Expect".to_string(), "100-continue".to_string());
+
+    let (sent, response) = client.send_detailed(&request).ok().unwrap();
+    assert_eq!(response.status, StatusCode::Created201);
+    assert!(sent.headers.get("Expect").is_none());
+    // The caller's request still asks for it
+    assert!(request.headers.get("Expect").is_some());
+
+    let (first, retry) = server.join().unwrap();
+    assert!(first.contains("Expect: 100-continue\r\n"));
+    assert!(!retry.contains("Expect"));
+}
@@ -0,0 +1,359 @@
+mod common;
+
+use std::io::{Cursor, Read, Write};
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex};
+
+use clienter::{
+    AddressFamily, HttpClient, HttpError, HttpHeaders, HttpMethod, HttpVersion, ResponseError,
+    StatusCode, MAX_HEADER_LINES,
+};
+use common::{serve_once, MockStream, EMPTY_RESPONSE};
+
+#[test]
+fn test_send_on_in_memory_stream() {
+    let client = HttpClient::new();
+    let (stream, written) = MockStream::new(b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nhello");
+
+    let request = client.request(HttpMethod::GET, "http://example.com/greeting");
+    let mut response = client.send_on(stream, &request).unwrap();
+
+    assert_eq!(response.status, StatusCode::Ok200);
+    assert_eq!(response.body_as_string().unwrap(), "hello");
+
+    let written = String::from_utf8(written.lock().unwrap().clone()).unwrap();
+    assert!(written.starts_with("GET /greeting HTTP/1.1\r\n"));
+}
+
+#[test]
+fn test_send_on_loopback_socket() {
+    let (addr, server) = serve_once(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n");
+    let client = HttpClient::new();
+
+    let stream = TcpStream::connect(addr).unwrap();
+    let request = client.request(HttpMethod::GET, "http://example.com/missing");
+    let response = client.send_on(stream, &request).unwrap();
+
+    assert_eq!(response.status, StatusCode::NotFound404);
+    let received = String::from_utf8(server.join().unwrap()).unwrap();
+    assert!(received.starts_with("GET /missing HTTP/1.1\r\n"));
+}
+
+#[test]
+fn test_send_on_decodes_non_ascii_headers() {
+    let client = HttpClient::new();
+    let (stream, _) = MockStream::new(
+        b"HTTP/1.1 200 OK\r\n\
+          X-Utf8: caf\xc3\xa9\r\n\
+          X-Latin1: caf\xe9\r\n\
+          Content-Length: 0\r\n\r\n",
+    );
+
+    let request = client.request(HttpMethod::GET, "http://example.com/");
+    let response = client.send_on(stream, &request).unwrap();
+
+    assert_eq!(response.headers.get("X-Utf8").unwrap(), "café");
+    assert_eq!(response.headers.get("X-Latin1").unwrap(), "café");
+}
+
+#[test]
+fn test_address_family_filters_resolved_addresses() {
+    let (addr, server) = serve_once(EMPTY_RESPONSE);
+    let uri = format!("http://{}/", addr);
+
+    let mut client = HttpClient::new();
+    client.address_family = AddressFamily::V6;
+    let request = client.request(HttpMethod::GET, uri.as_str());
+    assert_eq!(client.send(&request).err(), Some(HttpError::InvalidUri));
+
+    client.address_family = AddressFamily::V4;
+    let response = client.send(&request).unwrap();
+    assert_eq!(response.status, StatusCode::NoContent204);
+    server.join().unwrap();
+}
+
+#[test]
+fn test_request_line_includes_encoded_query() {
+    let client = HttpClient::new();
+    let (stream, written) = MockStream::new(b"HTTP/1.1 204 No Content\r\n\r\n");
+
+    let mut request = client.request(HttpMethod::GET, "http://example.com/search");
+    request.uri.set_query_pairs(&[("q", "a&b c")]);
+    client.send_on(stream, &request).unwrap();
+
+    let written = String::from_utf8(written.lock().unwrap().clone()).unwrap();
+    assert!(written.starts_with("GET /search?q=a%26b+c HTTP/1.1\r\n"));
+}
+
+#[test]
+fn test_connection_failed_reports_address() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    let client = HttpClient::new();
+    let request = client.request(HttpMethod::GET, format!("http://{}/", addr));
+    let err = client.send(&request).err().unwrap();
+
+    assert_eq!(
+        err,
+        HttpError::ConnectionFailed {
+            addr,
+            kind: std::io::ErrorKind::ConnectionRefused
+        }
+    );
+    assert!(err
+        .to_string()
+        .starts_with(&format!("connect to {} failed", addr)));
+}
+
+#[test]
+fn test_request_line_for_empty_and_root_paths() {
+    let client = HttpClient::new();
+    for uri in ["http://example.com", "http://example.com/"] {
+        let request = client.request(HttpMethod::GET, uri);
+        assert_eq!(request.get_request_line(), "GET / HTTP/1.1");
+    }
+}
+
+#[test]
+fn test_request_line_is_http11_for_both_schemes() {
+    let client = HttpClient::new();
+    for uri in ["http://example.com/path", "https://example.com/path"] {
+        let request = client.request(HttpMethod::GET, uri);
+        let (stream, output) = MockStream::new(b"HTTP/1.1 204 No Content\r\n\r\n");
+        client.send_on(stream, &request).unwrap();
+        assert!(output
+            .lock()
+            .unwrap()
+            .starts_with(b"GET /path HTTP/1.1\r\n"));
+        assert_eq!(request.uri.protocol.get_http_version(), "HTTP/1.1");
+    }
+}
+
+#[test]
+fn test_response_records_final_uri() {
+    let client = HttpClient::new();
+    let request = client.request(HttpMethod::GET, "https://example.com/a?b=c");
+    let (stream, _) = MockStream::new(b"HTTP/1.1 204 No Content\r\n\r\n");
+    let response = client.send_on(stream, &request).unwrap();
+
+    assert_eq!(response.final_uri, Some(request.uri.clone()));
+    assert!(!response.was_redirected());
+    assert!(!response.changed_origin());
+    assert!(!response.was_downgraded());
+}
+
+#[test]
+fn test_request_line_version() {
+    let client = HttpClient::new();
+    let request = client
+        .request(HttpMethod::GET, "https://example.com/")
+        .http_version(HttpVersion::Http10);
+    let (stream, output) = MockStream::new(b"HTTP/1.0 200 OK\r\nContent-Length: 0\r\n\r\n");
+    client.send_on(stream, &request).unwrap();
+    assert!(output.lock().unwrap().starts_with(b"GET / HTTP/1.0\r\n"));
+}
+
+#[test]
+fn test_headers_then_body_byte_exact() {
+    let mut raw = b"HTTP/1.1 200 OK\r\nContent-Length: 260\r\n\r\n".to_vec();
+    let body: Vec<u8> = (0..=255u8).chain(*b"\r\n\r\n").collect();
+    raw.extend(&body);
+
+    let client = HttpClient::new();
+    let (stream, _) = MockStream::new(&raw);
+    let request = client.request(HttpMethod::GET, "http://example.com/binary");
+    let mut response = client.send_on(stream, &request).unwrap();
+
+    assert_eq!(response.headers.get("Content-Length").unwrap(), "260");
+    assert_eq!(response.body().unwrap(), body);
+}
+
+#[test]
+fn test_invalid_response_carries_raw_lines() {
+    let client = HttpClient::new();
+    let request = client.request(HttpMethod::GET, "http://example.com/");
+
+    let (stream, _) = MockStream::new(b"HTTP/1.1 garbage\r\n\r\n");
+    let err = client.send_on(stream, &request).err().unwrap();
+    assert_eq!(
+        err,
+        HttpError::InvalidResponse(ResponseError::InvalidStatusLine(
+            "HTTP/1.1 garbage".to_string()
+        ))
+    );
+    assert_eq!(
+        err.to_string(),
+        "invalid response: InvalidStatusLine: 'HTTP/1.1 garbage'"
+    );
+
+    let (stream, _) = MockStream::new(b"HTTP/1.1 200 OK\r\nno colon here\r\n\r\n");
+    let err = client.send_on(stream, &request).err().unwrap();
+    assert_eq!(
+        err,
+        HttpError::InvalidResponse(ResponseError::InvalidHeader("no colon here".to_string()))
+    );
+}
+
+#[test]
+fn test_raw_override_is_written_verbatim() {
+    let (addr, server) = serve_once(b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n");
+    let client = HttpClient::new();
+
+    let raw = b"GET /smuggle HTTP/1.1\r\nContent-Length: 1\r\nContent-Length: 2\r\n\r\n";
+    let mut request = client
+        .request(
+            HttpMethod::POST,
+            format!("http://{}/ignored", addr).as_str(),
+        )
+        .raw_override(raw.to_vec());
+    request.set_body("ignored");
+    let response = client.send(&request).unwrap();
+
+    assert_eq!(response.status, StatusCode::BadRequest400);
+    assert_eq!(server.join().unwrap(), raw);
+}
+
+#[test]
+fn test_too_many_header_lines() {
+    let client = HttpClient::new();
+    let request = client.request(HttpMethod::GET, "http://example.com/");
+
+    let mut raw = b"HTTP/1.1 200 OK\r\n".to_vec();
+    for i in 0..MAX_HEADER_LINES {
+        raw.extend(format!("X-Header-{}: value\r\n", i).as_bytes());
+    }
+    let (stream, _) = MockStream::new(&[&raw[..], b"\r\n"].concat());
+    assert!(client.send_on(stream, &request).is_ok());
+
+    // Without the blank line that ends the headers
+    raw.extend(b"X-One-Too-Many: value\r\n".repeat(1000));
+    let (stream, _) = MockStream::new(&raw);
+    assert_eq!(
+        client.send_on(stream, &request).err(),
+        Some(HttpError::InvalidResponse(ResponseError::HeadersTooLarge))
+    );
+}
+
+#[test]
+fn test_max_headers_is_configurable() {
+    let mut client = HttpClient::new();
+    client.max_headers = 3;
+    let request = client.request(HttpMethod::GET, "http://example.com/");
+
+    let raw = b"HTTP/1.1 204 No Content\r\nA: 1\r\nB: 2\r\nC: 3\r\n\r\n";
+    let (stream, _) = MockStream::new(raw);
+    assert!(client.send_on(stream, &request).is_ok());
+
+    let raw = b"HTTP/1.1 204 No Content\r\nA: 1\r\nB: 2\r\nC: 3\r\nD: 4\r\n\r\n";
+    let (stream, _) = MockStream::new(raw);
+    assert_eq!(
+        client.send_on(stream, &request).err(),
+        Some(HttpError::InvalidResponse(ResponseError::HeadersTooLarge))
+    );
+
+    // Raising the limit above the default accepts more
+    client.max_headers = MAX_HEADER_LINES * 2;
+    let mut raw = b"HTTP/1.1 204 No Content\r\n".to_vec();
+    for i in 0..MAX_HEADER_LINES + 1 {
+        raw.extend(format!("X-Header-{}: value\r\n", i).as_bytes());
+    }
+    raw.extend(b"\r\n");
+    let (stream, _) = MockStream::new(&raw);
+    assert!(client.send_on(stream, &request).is_ok());
+}
+
+#[test]
+fn test_bare_lf_line_endings() {
+    let mut client = HttpClient::new();
+    client.headers = HttpHeaders::new();
+    client.bare_lf_line_endings = true;
+    let request = client
+        .request(HttpMethod::POST, "http://example.com/items")
+        .text("a\r\nb");
+
+    let (stream, written) = MockStream::new(b"HTTP/1.1 204 No Content\r\n\r\n");
+    client.send_on(stream, &request).unwrap();
+    let written = String::from_utf8(written.lock().unwrap().clone()).unwrap();
+    // Only the head changes; the body is sent as given
+    assert_eq!(
+        written,
+        "POST /items HTTP/1.1\nContent-Type: text/plain; charset=utf-8\nHost: example.com\n\
+         Content-Length: 4\n\na\r\nb"
+    );
+}
+
+/// A stream that records each call to `write` separately.
+struct WriteRecorder {
+    input: Cursor<Vec<u8>>,
+    writes: Arc<Mutex<Vec<Vec<u8>>>>,
+}
+
+impl WriteRecorder {
+    fn new(response: &[u8]) -> (Self, Arc<Mutex<Vec<Vec<u8>>>>) {
+        let writes = Arc::new(Mutex::new(Vec::new()));
+        let stream = WriteRecorder {
+            input: Cursor::new(response.to_vec()),
+            writes: writes.clone(),
+        };
+        (stream, writes)
+    }
+}
+
+impl Read for WriteRecorder {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.input.read(buf)
+    }
+}
+
+impl Write for WriteRecorder {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writes.lock().unwrap().push(buf.to_vec());
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_small_body_is_sent_with_the_head() {
+    let client = HttpClient::new();
+    let request = client
+        .request(HttpMethod::POST, "http://example.com/items")
+        .text("small");
+
+    let (stream, writes) = WriteRecorder::new(b"HTTP/1.1 204 No Content\r\n\r\n");
+    client.send_on(stream, &request).unwrap();
+    let writes = writes.lock().unwrap();
+    assert_eq!(writes.len(), 1);
+    assert!(writes[0].starts_with(b"POST /items HTTP/1.1\r\n"));
+    assert!(writes[0].ends_with(b"\r\n\r\nsmall"));
+}
+
+#[test]
+fn test_large_and_streamed_bodies_are_sent_separately() {
+    let client = HttpClient::new();
+    let body = "x".repeat(64 * 1024);
+    let request = client
+        .request(HttpMethod::POST, "http://example.com/items")
+        .text(body.clone());
+
+    let (stream, writes) = WriteRecorder::new(b"HTTP/1.1 204 No Content\r\n\r\n");
+    client.send_on(stream, &request).unwrap();
+    let writes = writes.lock().unwrap();
+    assert!(writes.len() > 1);
+    assert!(writes[0].ends_with(b"\r\n\r\n"));
+    assert_eq!(writes[1..].concat(), body.as_bytes());
+
+    let request = client
+        .request(HttpMethod::POST, "http://example.com/items")
+        .chunked_body(Cursor::new(b"streamed".to_vec()));
+    let (stream, writes) = WriteRecorder::new(b"HTTP/1.1 204 No Content\r\n\r\n");
+    client.send_on(stream, &request).unwrap();
+    let writes = writes.lock().unwrap();
+    assert!(writes.len() > 1);
+    assert!(writes[0].ends_with(b"\r\n\r\n"));
+}
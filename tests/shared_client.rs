@@ -0,0 +1,114 @@
+mod common;
+
+use std::sync::Arc;
+use std::thread;
+
+use clienter::{HttpClient, HttpMethod, StatusCode};
+use common::{serve_once, MockStream};
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn test_client_is_send_and_sync() {
+    assert_send_sync::<HttpClient>();
+}
+
+#[test]
+fn test_client_shared_between_threads() {
+    let client = Arc::new(HttpClient::new());
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let client = client.clone();
+            thread::spawn(move || {
+                let (stream, _) = MockStream::new(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n");
+                let request = client.request(HttpMethod::GET, "http://example.com/");
+                client.send_on(stream, &request).unwrap().status_code()
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), StatusCode::Ok200);
+    }
+}
+
+#[test]
+fn test_send_async_delivers_responses() {
+    let client = HttpClient::new();
+    let servers: Vec<_> = (0..3)
+        .map(|_| serve_once(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok"))
+        .collect();
+
+    let receivers: Vec<_> = servers
+        .iter()
+        .map(|(addr, _)| {
+            let request = client.request(HttpMethod::GET, format!("http://{}/", addr).as_str());
+            client.send_async(&request)
+        })
+        .collect();
+
+    for receiver in receivers {
+        let mut response = receiver.recv().unwrap().unwrap();
+        assert_eq!(response.status_code(), StatusCode::Ok200);
+        assert_eq!(response.body_as_string().unwrap(), "ok");
+    }
+    for (_, server) in servers {
+        server.join().unwrap();
+    }
+}
+
+#[test]
+fn test_send_async_reports_errors() {
+    let client = HttpClient::new();
+    let request = client.request(HttpMethod::GET, "http://unresolvable.invalid/");
+    assert!(client.send_async(&request).recv().unwrap().is_err());
+}
+
+#[test]
+fn test_send_batch_preserves_order_and_buffers_bodies() {
+    let client = HttpClient::new();
+    let servers: Vec<_> = [
+        &b"HTTP/1.1 200 OK\r\nContent-Length: 5\r\n\r\nfirst"[..],
+        b"HTTP/1.1 404 Not Found\r\nContent-Length: 6\r\n\r\nsecond",
+        b"HTTP/1.1 200 OK\r\n\r\nthird",
+    ]
+    .into_iter()
+    .map(serve_once)
+    .collect();
+
+    let mut requests: Vec<_> = servers
+        .iter()
+        .map(|(addr, _)| client.request(HttpMethod::GET, format!("http://{}/", addr).as_str()))
+        .collect();
+    requests.push(client.request(HttpMethod::GET, "http://unresolvable.invalid/"));
+
+    let mut results = client.send_batch(requests, 2);
+    for (_, server) in servers {
+        server.join().unwrap();
+    }
+
+    assert_eq!(results.len(), 4);
+    assert!(results.pop().unwrap().is_err());
+    let bodies: Vec<_> = results
+        .into_iter()
+        .map(|result| {
+            let mut response = result.ok().unwrap();
+            (response.status_code(), response.body_as_string().unwrap())
+        })
+        .collect();
+    assert_eq!(
+        bodies,
+        vec![
+            (StatusCode::Ok200, "first".to_string()),
+            (StatusCode::NotFound404, "second".to_string()),
+            (StatusCode::Ok200, "third".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_send_batch_empty() {
+    let client = HttpClient::new();
+    assert!(client.send_batch(Vec::new(), 4).is_empty());
+}
@@ -0,0 +1,21 @@
+#![cfg(feature = "socket-options")]
+
+mod common;
+
+use std::time::Duration;
+
+use clienter::{HttpClient, HttpMethod};
+use common::serve_once;
+
+#[test]
+fn test_request_with_socket_options() {
+    let (addr, server) = serve_once(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok");
+    let mut client = HttpClient::new();
+    client.tcp_keepalive = Some(Duration::from_secs(30));
+    client.tcp_linger = Some(Duration::from_secs(1));
+
+    let request = client.request(HttpMethod::GET, format!("http://{}/", addr).as_str());
+    let mut response = client.send(&request).unwrap();
+    assert_eq!(response.body_as_string().unwrap(), "ok");
+    server.join().unwrap();
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use clienter::{HttpClient, HttpError, HttpMethod, HttpResponse, ResponseError};
+use common::MockStream;
+
+fn send(strict: bool, raw: &[u8]) -> Result<HttpResponse, HttpError> {
+    let mut client = HttpClient::new();
+    client.strict = strict;
+    let (stream, _) = MockStream::new(raw);
+    let request = client.request(HttpMethod::GET, "http://example.com/");
+    client.send_on(stream, &request)
+}
+
+#[test]
+fn test_strict_accepts_compliant_response() {
+    let mut response = send(
+        true,
+        b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nX-Empty:\r\nX-Tab: a\tb\r\n\r\nok",
+    )
+    .unwrap();
+    assert_eq!(response.body_as_string().unwrap(), "ok");
+
+    assert!(send(true, b"HTTP/1.0 204 \r\n\r\n").is_ok());
+}
+
+#[test]
+fn test_strict_rejects_nonstandard_status_lines() {
+    for raw in [
+        &b"HTTP/1.1 200 OK\n\r\n"[..],
+        b"HTTP/2 200 OK\r\n\r\n",
+        b"HTTP/1.1  200 OK\r\n\r\n",
+        b"HTTP/1.1 200\r\n\r\n",
+        b"HTTP/1.1 0200 OK\r\n\r\n",
+    ] {
+        assert!(
+            matches!(
+                send(true, raw).err(),
+                Some(HttpError::InvalidResponse(
+                    ResponseError::InvalidStatusLine(_)
+                ))
+            ),
+            "{:?}",
+            String::from_utf8_lossy(raw)
+        );
+    }
+}
+
+#[test]
+fn test_strict_rejects_nonstandard_headers() {
+    for raw in [
+        &b"HTTP/1.1 200 OK\r\nContent-Length: 0\n\r\n"[..],
+        b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\n",
+        b"HTTP/1.1 200 OK\r\nContent-Length : 0\r\n\r\n",
+        b"HTTP/1.1 200 OK\r\nX-Control: a\x00b\r\n\r\n",
+    ] {
+        assert!(
+            matches!(
+                send(true, raw).err(),
+                Some(HttpError::InvalidResponse(ResponseError::InvalidHeader(_)))
+            ),
+            "{:?}",
+            String::from_utf8_lossy(raw)
+        );
+        assert!(send(false, raw).is_ok());
+    }
+
+    let folded = b"HTTP/1.1 200 OK\r\nX-Folded: a\r\n b\r\n\r\n";
+    assert!(matches!(
+        send(true, folded).err(),
+        Some(HttpError::InvalidResponse(ResponseError::InvalidHeader(_)))
+    ));
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use std::io::Write;
+use std::net::TcpListener;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use clienter::{HttpClient, HttpError, HttpMethod, ResponseError};
+
+/// Starts a server that writes `head` and then trickles one byte every 50ms until the client hangs up.
+fn serve_trickle(head: &'static [u8]) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        common::read_request_head(&mut stream);
+        stream.write_all(head).unwrap();
+        while stream.write_all(b"x").is_ok() {
+            thread::sleep(Duration::from_millis(50));
+        }
+    });
+    format!("http://{}/", addr)
+}
+
+#[test]
+fn test_total_timeout_during_headers() {
+    let uri = serve_trickle(b"HTTP/1.1 200 OK\r\nX-Slow: ");
+    let mut client = HttpClient::new();
+    client.total_timeout = Some(Duration::from_millis(300));
+
+    let start = Instant::now();
+    let request = client.request(HttpMethod::GET, uri);
+    let result = client.send(&request);
+
+    assert_eq!(result.err(), Some(HttpError::Timeout));
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn test_total_timeout_during_body() {
+    let uri = serve_trickle(b"HTTP/1.1 200 OK\r\nContent-Length: 1000\r\n\r\n");
+    let mut client = HttpClient::new();
+    client.total_timeout = Some(Duration::from_millis(300));
+
+    let start = Instant::now();
+    let request = client.request(HttpMethod::GET, uri);
+    let mut response = client.send(&request).unwrap();
+
+    assert_eq!(response.body().err(), Some(ResponseError::Timeout));
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn test_body_with_timeout_bounds_body_only() {
+    let uri = serve_trickle(b"HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\n");
+    let mut client = HttpClient::new();
+    client.timeout = Some(Duration::from_secs(10));
+
+    let request = client.request(HttpMethod::GET, uri);
+    let mut response = client.send(&request).unwrap();
+
+    let start = Instant::now();
+    let result = response.body_with_timeout(Duration::from_millis(300));
+
+    assert_eq!(result, Err(ResponseError::Timeout));
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn test_body_with_timeout_interrupts_stalled_read() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        common::read_request_head(&mut stream);
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 100\r\n\r\npartial")
+            .unwrap();
+        thread::sleep(Duration::from_secs(4));
+    });
+    let mut client = HttpClient::new();
+    client.read_timeout = None;
+
+    let request = client.request(HttpMethod::GET, format!("http://{}/", addr).as_str());
+    let mut response = client.send(&request).unwrap();
+
+    let start = Instant::now();
+    let result = response.body_with_timeout(Duration::from_millis(300));
+
+    assert_eq!(result, Err(ResponseError::Timeout));
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn test_new_clients_have_default_timeouts() {
+    let client = HttpClient::new();
+    assert_eq!(client.timeout, Some(Duration::from_secs(30)));
+    assert_eq!(client.read_timeout, Some(Duration::from_secs(30)));
+    assert_eq!(client.total_timeout, None);
+}
+
+#[test]
+fn test_read_timeout_on_stalled_server() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        common::read_request_head(&mut stream);
+        stream
+            .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nhalf")
+            .unwrap();
+        // Hold the connection open without sending the rest
+        thread::sleep(Duration::from_secs(2));
+    });
+    let mut client = HttpClient::new();
+    client.read_timeout = Some(Duration::from_millis(300));
+
+    let start = Instant::now();
+    let request = client.request(HttpMethod::GET, format!("http://{}/", addr).as_str());
+    let mut response = client.send(&request).unwrap();
+
+    assert_eq!(response.body().err(), Some(ResponseError::Timeout));
+    assert!(start.elapsed() < Duration::from_secs(2));
+    server.join().unwrap();
+}
+
+#[test]
+fn test_read_timeout_does_not_limit_steady_bodies() {
+    // One byte every 50ms takes longer than the read timeout in total
+    let uri = serve_trickle(b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\n");
+    let mut client = HttpClient::new();
+    client.read_timeout = Some(Duration::from_millis(300));
+
+    let request = client.request(HttpMethod::GET, uri);
+    let mut response = client.send(&request).unwrap();
+    assert_eq!(response.body().unwrap().len(), 10);
+}
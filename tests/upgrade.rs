@@ -0,0 +1,58 @@
+mod common;
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+
+use clienter::{HttpClient, HttpMethod, StatusCode};
+use common::{read_request_head, MockStream};
+
+#[test]
+fn test_into_stream_keeps_bytes_after_headers() {
+    let (stream, output) = MockStream::new(
+        b"HTTP/1.1 101 Switching Protocols\r\nUpgrade: echo\r\nConnection: Upgrade\r\n\r\nearly bytes",
+    );
+    let client = HttpClient::new();
+    let request = client.request(HttpMethod::GET, "http://example.com/");
+    let response = client.send_on(stream, &request).unwrap();
+    assert_eq!(response.status, StatusCode::SwitchingProtocols101);
+
+    let mut stream = response.into_stream();
+    let mut rest = String::new();
+    stream.read_to_string(&mut rest).unwrap();
+    assert_eq!(rest, "early bytes");
+
+    output.lock().unwrap().clear();
+    stream.write_all(b"ping").unwrap();
+    assert_eq!(output.lock().unwrap().as_slice(), b"ping");
+}
+
+#[test]
+fn test_into_stream_speaks_new_protocol() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        read_request_head(&mut stream);
+        stream
+            .write_all(b"HTTP/1.1 101 Switching Protocols\r\nUpgrade: echo\r\n\r\nhi")
+            .unwrap();
+        let mut message = [0u8; 4];
+        stream.read_exact(&mut message).unwrap();
+        stream.write_all(&message).unwrap();
+    });
+
+    let client = HttpClient::new();
+    let request = client.request(HttpMethod::GET, format!("http://{}/", addr).as_str());
+    let response = client.send(&request).unwrap();
+    let mut stream = response.into_stream();
+
+    let mut greeting = [0u8; 2];
+    stream.read_exact(&mut greeting).unwrap();
+    assert_eq!(&greeting, b"hi");
+
+    stream.write_all(b"ping").unwrap();
+    let mut echo = [0u8; 4];
+    stream.read_exact(&mut echo).unwrap();
+    assert_eq!(&echo, b"ping");
+    server.join().unwrap();
+}